@@ -0,0 +1,3 @@
+ALTER TABLE games DROP COLUMN bracket;
+ALTER TABLE tournaments DROP COLUMN is_double_elim;
+ALTER TABLE tournaments DROP COLUMN has_bracket_reset;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE games ADD COLUMN bracket INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE tournaments ADD COLUMN is_double_elim BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE tournaments ADD COLUMN has_bracket_reset BOOLEAN NOT NULL DEFAULT TRUE;
\ No newline at end of file
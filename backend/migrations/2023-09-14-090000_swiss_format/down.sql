@@ -0,0 +1,3 @@
+ALTER TABLE games DROP COLUMN round;
+ALTER TABLE tournaments DROP COLUMN is_swiss;
+ALTER TABLE tournaments DROP COLUMN swiss_rounds;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE games ADD COLUMN round INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE tournaments ADD COLUMN is_swiss BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE tournaments ADD COLUMN swiss_rounds INTEGER NULL;
\ No newline at end of file
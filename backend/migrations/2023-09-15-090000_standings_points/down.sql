@@ -0,0 +1,3 @@
+ALTER TABLE tournaments DROP COLUMN points_win;
+ALTER TABLE tournaments DROP COLUMN points_draw;
+ALTER TABLE tournaments DROP COLUMN points_loss;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE tournaments ADD COLUMN points_win INTEGER NOT NULL DEFAULT 3;
+ALTER TABLE tournaments ADD COLUMN points_draw INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE tournaments ADD COLUMN points_loss INTEGER NOT NULL DEFAULT 0;
\ No newline at end of file
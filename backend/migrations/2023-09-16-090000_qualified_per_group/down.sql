@@ -0,0 +1,2 @@
+ALTER TABLE tournaments DROP COLUMN qualified_per_group;
+ALTER TABLE tournaments DROP COLUMN wildcards;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE tournaments ADD COLUMN qualified_per_group INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE tournaments ADD COLUMN wildcards INTEGER NOT NULL DEFAULT 0;
\ No newline at end of file
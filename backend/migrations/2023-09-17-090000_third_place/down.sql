@@ -0,0 +1 @@
+ALTER TABLE tournaments DROP COLUMN has_third_place;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE tournaments ADD COLUMN has_third_place BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE games DROP COLUMN tiebreak;
+ALTER TABLE games DROP COLUMN tiebreak_winner;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN tiebreak INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN tiebreak_winner INTEGER NOT NULL DEFAULT 0;
\ No newline at end of file
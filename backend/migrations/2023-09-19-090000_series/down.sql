@@ -0,0 +1,2 @@
+DROP TABLE game_sets;
+DROP TABLE series_formats;
\ No newline at end of file
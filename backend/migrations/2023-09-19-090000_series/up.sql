@@ -0,0 +1,21 @@
+CREATE TABLE series_formats (
+  id INTEGER PRIMARY KEY AUTO_INCREMENT,
+  fk_tournaments INTEGER NOT NULL,
+  bracket INTEGER NOT NULL,
+  phase INTEGER NOT NULL,
+  best_of INTEGER NOT NULL DEFAULT 1,
+
+  CONSTRAINT series_formats_tournaments FOREIGN KEY (fk_tournaments) REFERENCES tournaments(id) ON DELETE CASCADE,
+  CONSTRAINT series_formats_round UNIQUE (fk_tournaments, bracket, phase)
+);
+
+CREATE TABLE game_sets (
+  id INTEGER PRIMARY KEY AUTO_INCREMENT,
+  fk_games INTEGER NOT NULL,
+  number INTEGER NOT NULL,
+  score1 INTEGER NOT NULL DEFAULT 0,
+  score2 INTEGER NOT NULL DEFAULT 0,
+
+  CONSTRAINT game_sets_games FOREIGN KEY (fk_games) REFERENCES games(id) ON DELETE CASCADE,
+  CONSTRAINT game_sets_number UNIQUE (fk_games, number)
+);
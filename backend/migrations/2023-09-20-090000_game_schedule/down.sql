@@ -0,0 +1,2 @@
+ALTER TABLE games DROP COLUMN scheduled_at;
+ALTER TABLE games DROP COLUMN court;
\ No newline at end of file
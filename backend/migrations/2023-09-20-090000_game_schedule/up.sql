@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN scheduled_at DATETIME NULL;
+ALTER TABLE games ADD COLUMN court INTEGER NULL;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE tournaments DROP COLUMN match_duration;
\ No newline at end of file
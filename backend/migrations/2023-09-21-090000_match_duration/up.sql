@@ -0,0 +1 @@
+ALTER TABLE tournaments ADD COLUMN match_duration INTEGER NULL;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE nut_transactions;
\ No newline at end of file
@@ -0,0 +1,17 @@
+CREATE TABLE nut_transactions (
+  id INTEGER PRIMARY KEY AUTO_INCREMENT,
+  fk_nuts INTEGER NOT NULL,
+  kind INTEGER NOT NULL,
+  amount INTEGER NOT NULL,
+  balance INTEGER NOT NULL,
+  fk_games INTEGER NULL,
+  reason VARCHAR(255) NULL,
+  created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  CONSTRAINT nut_transactions_nuts FOREIGN KEY (fk_nuts) REFERENCES nuts(id) ON DELETE CASCADE,
+  CONSTRAINT nut_transactions_games FOREIGN KEY (fk_games) REFERENCES games(id) ON DELETE SET NULL
+);
+
+-- the current stock of the existing nuts opens their ledger
+INSERT INTO nut_transactions (fk_nuts, kind, amount, balance)
+SELECT id, 0, stock, stock FROM nuts;
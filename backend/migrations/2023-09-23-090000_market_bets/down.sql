@@ -0,0 +1 @@
+DROP TABLE market_bets;
\ No newline at end of file
@@ -0,0 +1,20 @@
+CREATE TABLE market_bets (
+  id INTEGER PRIMARY KEY AUTO_INCREMENT,
+  market INTEGER NOT NULL,
+  fk_tournaments INTEGER NOT NULL,
+  fk_users INTEGER NOT NULL,
+  -- the game of an exact score bet
+  fk_games INTEGER NULL,
+  -- the team of a champion bet
+  fk_teams INTEGER NULL,
+  score1 INTEGER NULL,
+  score2 INTEGER NULL,
+  nb_nut INTEGER NOT NULL,
+  -- nuts given back once the market is settled, the stake included
+  payout INTEGER NULL,
+
+  CONSTRAINT market_bets_tournaments FOREIGN KEY (fk_tournaments) REFERENCES tournaments(id) ON DELETE CASCADE,
+  CONSTRAINT market_bets_users FOREIGN KEY (fk_users) REFERENCES users(id) ON DELETE CASCADE,
+  CONSTRAINT market_bets_games FOREIGN KEY (fk_games) REFERENCES games(id) ON DELETE CASCADE,
+  CONSTRAINT market_bets_teams FOREIGN KEY (fk_teams) REFERENCES teams(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE tournaments DROP COLUMN betting_lead_time;
@@ -0,0 +1 @@
+ALTER TABLE tournaments ADD COLUMN betting_lead_time INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+CREATE TABLE seasons (
+  id INTEGER PRIMARY KEY AUTO_INCREMENT,
+  name VARCHAR(255) NOT NULL,
+  -- a tournament belongs to the season its date falls in, the end excluded
+  start_date DATETIME NOT NULL,
+  end_date DATETIME NOT NULL
+);
@@ -0,0 +1,3 @@
+DELETE FROM tokens WHERE purpose <> 0;
+ALTER TABLE tokens DROP COLUMN purpose;
+ALTER TABLE users DROP COLUMN email_verified;
@@ -0,0 +1,6 @@
+-- the tokens sent by mail share the table of the login tokens
+ALTER TABLE tokens ADD COLUMN purpose INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- the users registered before the verification keep a valid account
+UPDATE users SET email_verified = TRUE;
@@ -0,0 +1,3 @@
+ALTER TABLE tokens DROP FOREIGN KEY tokens_sessions;
+ALTER TABLE tokens DROP COLUMN fk_sessions;
+DROP TABLE sessions;
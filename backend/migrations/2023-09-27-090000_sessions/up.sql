@@ -0,0 +1,17 @@
+-- a login on a device, kept alive by its refresh token
+CREATE TABLE sessions (
+  id INTEGER PRIMARY KEY AUTO_INCREMENT,
+  fk_users INTEGER NOT NULL,
+  -- replaced at each refresh
+  refresh_token VARCHAR(255) NOT NULL UNIQUE,
+  user_agent VARCHAR(255) NULL,
+  created_at DATETIME NOT NULL DEFAULT NOW(),
+  last_used_at DATETIME NOT NULL DEFAULT NOW(),
+  expiration_date DATETIME NOT NULL,
+
+  CONSTRAINT sessions_users FOREIGN KEY (fk_users) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- the access tokens of a session go with it
+ALTER TABLE tokens ADD COLUMN fk_sessions INTEGER NULL;
+ALTER TABLE tokens ADD CONSTRAINT tokens_sessions FOREIGN KEY (fk_sessions) REFERENCES sessions(id) ON DELETE CASCADE;
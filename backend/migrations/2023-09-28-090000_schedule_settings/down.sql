@@ -0,0 +1 @@
+ALTER TABLE tournaments DROP COLUMN break_duration, DROP COLUMN courts, DROP COLUMN min_rest;
\ No newline at end of file
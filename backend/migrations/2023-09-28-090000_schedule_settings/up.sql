@@ -0,0 +1 @@
+ALTER TABLE tournaments ADD COLUMN break_duration INTEGER NULL, ADD COLUMN courts INTEGER NULL, ADD COLUMN min_rest INTEGER NULL;
\ No newline at end of file
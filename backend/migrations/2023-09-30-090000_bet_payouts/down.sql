@@ -0,0 +1 @@
+ALTER TABLE bets DROP COLUMN payout;
\ No newline at end of file
@@ -0,0 +1,18 @@
+ALTER TABLE bets ADD COLUMN payout INTEGER NULL;
+
+-- the bets of the settled games get what the ledger paid for the game, without the score bets
+UPDATE bets
+INNER JOIN games ON games.id = bets.fk_games
+INNER JOIN nuts ON nuts.fk_users = bets.fk_users AND nuts.fk_tournaments = games.fk_tournaments
+SET bets.payout = COALESCE((
+  SELECT SUM(nut_transactions.amount) FROM nut_transactions
+  WHERE nut_transactions.fk_nuts = nuts.id
+    AND nut_transactions.fk_games = games.id
+    AND nut_transactions.kind IN (2, 3)
+), 0) - COALESCE((
+  SELECT SUM(market_bets.payout) FROM market_bets
+  WHERE market_bets.fk_users = bets.fk_users
+    AND market_bets.fk_games = games.id
+    AND market_bets.payout IS NOT NULL
+), 0)
+WHERE games.has_gained_nut = TRUE;
@@ -0,0 +1 @@
+ALTER TABLE users DROP INDEX users_email;
\ No newline at end of file
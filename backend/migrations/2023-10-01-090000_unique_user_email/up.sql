@@ -0,0 +1,2 @@
+-- two accounts can't share an address, the checks of the routes race otherwise
+ALTER TABLE users ADD CONSTRAINT users_email UNIQUE (email);
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Local;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Datetime};
+use log::{info, warn};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::tokio::{select, time};
+use rocket::{Orbit, Rocket};
+
+use crate::events::{EventKind, Events};
+use crate::models::game::Game;
+use crate::scheduler::is_betting_over;
+use crate::schema::{games, tournaments};
+use crate::MysqlConnection;
+
+// time between two looks for games whose bets are due to close
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Close the bets of the scheduled games once their deadline has passed, the
+// owner does not have to close them by hand
+pub struct AutoCloseBetting;
+
+#[rocket::async_trait]
+impl Fairing for AutoCloseBetting {
+    fn info(&self) -> Info {
+        Info {
+            name: "Close the bets of the games about to start",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let pool = match MysqlConnection::pool(rocket) {
+            Some(pool) => pool.clone(),
+            None => {
+                warn!("No database pool, the bets won't be closed automatically");
+                return;
+            }
+        };
+        let events = rocket.state::<Events>().cloned();
+        let mut shutdown = rocket.shutdown();
+
+        rocket::tokio::spawn(async move {
+            let mut interval = time::interval(CHECK_INTERVAL);
+
+            loop {
+                select! {
+                    _ = interval.tick() => {},
+                    _ = &mut shutdown => break,
+                };
+
+                let connection = match pool.get().await {
+                    Some(connection) => connection,
+                    None => continue,
+                };
+
+                let closed = match connection.run(close_due_games).await {
+                    Ok(closed) => closed,
+                    Err(e) => {
+                        warn!("{}", e);
+                        continue;
+                    }
+                };
+
+                for game in closed {
+                    info!(
+                        "{} - Bets closed on game {} of tournament {} starting at {} - auto_close/close_due_games()",
+                        Local::now().format("%d/%m/%Y %H:%M"),
+                        game.id,
+                        game.fk_tournaments,
+                        game.scheduled_at.map(|s| s.format("%d/%m/%Y %H:%M").to_string()).unwrap_or_default()
+                    );
+
+                    if let Some(events) = &events {
+                        events.send(game.fk_tournaments, EventKind::BettingClosed, Some(game.id));
+                    }
+                }
+            }
+        });
+    }
+}
+
+// open games whose deadline has passed, returned once their bets are closed
+fn close_due_games(c: &mut diesel::MysqlConnection) -> QueryResult<Vec<Game>> {
+    // the times of the timetable are local times
+    let now = Local::now().naive_local();
+
+    // the deadline is checked by the database, nothing is locked to find the due games
+    let lead_times: HashMap<i32, i32> = games::table
+        .inner_join(tournaments::table.on(tournaments::id.eq(games::fk_tournaments)))
+        .filter(games::status.eq(0))
+        .filter(games::fk_team1.ne(games::fk_team2))
+        .filter(
+            sql::<Bool>("games.scheduled_at <= DATE_ADD(")
+                .bind::<Datetime, _>(now)
+                .sql(", INTERVAL tournaments.betting_lead_time MINUTE)"),
+        )
+        .select((games::id, tournaments::betting_lead_time))
+        .load::<(i32, i32)>(c)?
+        .into_iter()
+        .collect();
+
+    if lead_times.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let ids: Vec<i32> = lead_times.keys().cloned().collect();
+
+    c.transaction(|c| {
+        // only the due games are locked, checked again as they may have started or moved since
+        let due: Vec<Game> = games::table
+            .filter(games::id.eq_any(ids))
+            .filter(games::status.eq(0))
+            .for_update()
+            .load::<Game>(c)?
+            .into_iter()
+            .filter(|game| match (game.scheduled_at, lead_times.get(&game.id)) {
+                (Some(start), Some(lead_time)) => is_betting_over(now, start, *lead_time as i64),
+                _ => false,
+            })
+            .collect();
+
+        if due.is_empty() {
+            return Ok(due);
+        }
+
+        diesel::update(games::table.filter(games::id.eq_any(due.iter().map(|g| g.id))))
+            .set(games::status.eq(1))
+            .execute(c)?;
+
+        Ok(due)
+    })
+}
@@ -0,0 +1,192 @@
+use rocket::serde::{Deserialize, Serialize};
+
+// Nuts a user put on a team
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Stake {
+    pub user_id: i32,
+    pub team_id: i32,
+    pub nuts: i32,
+}
+
+// Nuts given back to a user once the game is over, the stake included
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Payout {
+    pub user_id: i32,
+    pub nuts: i32,
+}
+
+impl Payout {
+    // what the user won or lost with the bet
+    pub fn gain(&self, stake: &Stake) -> i32 {
+        self.nuts - stake.nuts
+    }
+}
+
+/// Share the pot of a game between the bets on the winner
+///
+/// Each bet on the winner gets its stake back plus a part of the nuts bet on
+/// the other team proportional to its stake. Parts are whole nuts: each bet
+/// first gets the rounded down part, the nuts left go one by one to the bets
+/// with the largest remainders, bigger stakes first when tied. Every stake is
+/// refunded on a draw (`winner` is none) or when nobody bet on the winner.
+///
+/// Payouts are in the order of `stakes` and always add up to the pot.
+pub fn settle(winner: Option<i32>, stakes: &[Stake]) -> Vec<Payout> {
+    let refund = || {
+        stakes
+            .iter()
+            .map(|stake| Payout {
+                user_id: stake.user_id,
+                nuts: stake.nuts,
+            })
+            .collect()
+    };
+
+    let winner = match winner {
+        Some(winner) => winner,
+        None => return refund(),
+    };
+
+    let winning_total: i64 = stakes
+        .iter()
+        .filter(|s| s.team_id == winner)
+        .map(|s| s.nuts as i64)
+        .sum();
+    let losing_total: i64 = stakes
+        .iter()
+        .filter(|s| s.team_id != winner)
+        .map(|s| s.nuts as i64)
+        .sum();
+
+    if winning_total == 0 {
+        return refund();
+    }
+
+    let mut payouts: Vec<Payout> = stakes
+        .iter()
+        .map(|stake| Payout {
+            user_id: stake.user_id,
+            nuts: 0,
+        })
+        .collect();
+
+    // (index of the stake, remainder of its part)
+    let mut remainders: Vec<(usize, i64)> = vec![];
+    let mut distributed = 0;
+
+    for (index, stake) in stakes.iter().enumerate() {
+        if stake.team_id != winner {
+            continue;
+        }
+
+        let part = losing_total * stake.nuts as i64;
+        payouts[index].nuts = stake.nuts + (part / winning_total) as i32;
+        distributed += part / winning_total;
+        remainders.push((index, part % winning_total));
+    }
+
+    remainders.sort_by(|(a, a_remainder), (b, b_remainder)| {
+        b_remainder
+            .cmp(a_remainder)
+            .then(stakes[*b].nuts.cmp(&stakes[*a].nuts))
+            .then(a.cmp(b))
+    });
+
+    // less nuts left than winning bets, the remainders sum to a multiple of the winning total
+    for (index, _) in remainders
+        .iter()
+        .take((losing_total - distributed) as usize)
+    {
+        payouts[*index].nuts += 1;
+    }
+
+    payouts
+}
+
+// What the bets on a team look like while the game is open
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TeamOdds {
+    pub team_id: i32,
+    pub staked: i32,
+    pub bettors: i32,
+    // nuts paid back for each nut bet if the team wins, none while nobody bet on it
+    pub odds: Option<f64>,
+}
+
+/// Total staked, number of bettors and implied odds of each team of a game
+///
+/// The odds are the pot divided by the nuts bet on the team, what a nut bet
+/// on the team would bring back if the game ended now. They are left out when
+/// nobody bet on the team, its bettors would only get their stake back.
+pub fn odds(teams: &[i32], stakes: &[Stake]) -> Vec<TeamOdds> {
+    let pot: i64 = stakes.iter().map(|s| s.nuts as i64).sum();
+
+    teams
+        .iter()
+        .map(|team_id| {
+            let on_team = stakes.iter().filter(|s| s.team_id == *team_id);
+            let staked: i64 = on_team.clone().map(|s| s.nuts as i64).sum();
+
+            TeamOdds {
+                team_id: *team_id,
+                staked: staked as i32,
+                bettors: on_team.count() as i32,
+                odds: if staked > 0 {
+                    Some(pot as f64 / staked as f64)
+                } else {
+                    None
+                },
+            }
+        })
+        .collect()
+}
+
+/// Payout of `stake` if its team wins, with the bets placed so far
+///
+/// The stake replaces the current bet of its user, like updating the bet
+/// would, and is settled by `settle` so the preview is exactly what would be
+/// paid if nobody else bet before the end of the game.
+pub fn preview(stakes: &[Stake], stake: Stake) -> Payout {
+    let mut stakes = stakes.to_vec();
+
+    // an updated bet keeps its place, leftover nuts are shared in the same order
+    let index = match stakes.iter().position(|s| s.user_id == stake.user_id) {
+        Some(index) => {
+            stakes[index] = stake;
+            index
+        }
+        None => {
+            stakes.push(stake);
+            stakes.len() - 1
+        }
+    };
+
+    settle(Some(stake.team_id), &stakes)[index]
+}
+
+// Nuts a user put on the score of a game
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoreStake {
+    pub user_id: i32,
+    pub score: (i32, i32),
+    pub nuts: i32,
+}
+
+/// Share the pot of the exact score market of a game
+///
+/// The bets on the final `score` share the other bets like the bets on the
+/// winner of a game do in `settle`. Every stake is refunded when the game was
+/// not played (`score` is none) or when nobody found the score.
+pub fn settle_exact_score(score: Option<(i32, i32)>, stakes: &[ScoreStake]) -> Vec<Payout> {
+    // the right score is the winning team of a two teams market
+    let stakes: Vec<Stake> = stakes
+        .iter()
+        .map(|stake| Stake {
+            user_id: stake.user_id,
+            team_id: if Some(stake.score) == score { 1 } else { 0 },
+            nuts: stake.nuts,
+        })
+        .collect();
+
+    settle(score.map(|_| 1), &stakes)
+}
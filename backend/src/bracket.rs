@@ -0,0 +1,193 @@
+use rocket::serde::{Deserialize, Serialize};
+
+// Side of the elimination tree a game belongs to
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bracket {
+    Upper = 0,
+    Lower,
+    GrandFinal,
+    // played by the losers of the semi-finals
+    ThirdPlace,
+}
+
+impl Bracket {
+    pub fn from_i32(value: i32) -> Option<Bracket> {
+        match value {
+            0 => Some(Bracket::Upper),
+            1 => Some(Bracket::Lower),
+            2 => Some(Bracket::GrandFinal),
+            3 => Some(Bracket::ThirdPlace),
+            _ => None,
+        }
+    }
+}
+
+// Position of a game in the elimination phase
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Slot {
+    pub bracket: Bracket,
+    pub phase: i32,
+    pub place: i32,
+}
+
+impl Slot {
+    pub fn new(bracket: Bracket, phase: i32, place: i32) -> Slot {
+        Slot {
+            bracket,
+            phase,
+            place,
+        }
+    }
+}
+
+// Which team of a game is sent to another game
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Winner,
+    Loser,
+}
+
+// A game giving one of its teams to another game
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Feed {
+    pub slot: Slot,
+    pub outcome: Outcome,
+}
+
+/// Shape of an elimination phase
+///
+/// In a double elimination the losers of the upper bracket drop into a lower
+/// bracket, the lower bracket alternates between rounds played between its own
+/// winners (odd rounds) and rounds where the next upper round losers drop in
+/// (even rounds), and both brackets winners meet in the grand final.
+///
+/// A single elimination can also have the semi-finals losers play for the third
+/// place.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Layout {
+    pub rounds: i32,
+    pub double_elim: bool,
+    pub third_place: bool,
+}
+
+impl Layout {
+    pub fn new(first_round_games: usize, double_elim: bool) -> Layout {
+        Layout {
+            rounds: first_round_games.max(1).next_power_of_two().trailing_zeros() as i32 + 1,
+            double_elim,
+            third_place: false,
+        }
+    }
+
+    // the third place game needs semi-finals, the lower bracket already ranks the losers
+    pub fn with_third_place(mut self, third_place: bool) -> Layout {
+        self.third_place = third_place && !self.double_elim && self.rounds > 1;
+        self
+    }
+
+    pub fn lower_rounds(&self) -> i32 {
+        2 * (self.rounds - 1)
+    }
+
+    pub fn games_in_round(&self, bracket: Bracket, phase: i32) -> i32 {
+        match bracket {
+            Bracket::Upper => 1 << (self.rounds - phase),
+            Bracket::Lower => 1 << (self.rounds - 1 - (phase + 1) / 2),
+            Bracket::GrandFinal | Bracket::ThirdPlace => 1,
+        }
+    }
+
+    // game where the winner or the loser of the given game plays next
+    pub fn next(&self, slot: Slot, outcome: Outcome) -> Option<Slot> {
+        match (slot.bracket, outcome) {
+            (Bracket::Upper, Outcome::Winner) => {
+                if slot.phase < self.rounds {
+                    Some(Slot::new(Bracket::Upper, slot.phase + 1, slot.place / 2))
+                } else if self.double_elim {
+                    Some(Slot::new(Bracket::GrandFinal, 1, 0))
+                } else {
+                    None
+                }
+            }
+            (Bracket::Upper, Outcome::Loser) => {
+                if self.third_place && slot.phase == self.rounds - 1 {
+                    Some(Slot::new(Bracket::ThirdPlace, 1, 0))
+                } else if !self.double_elim {
+                    None
+                } else if self.rounds == 1 {
+                    Some(Slot::new(Bracket::GrandFinal, 1, 0))
+                } else if slot.phase == 1 {
+                    Some(Slot::new(Bracket::Lower, 1, slot.place / 2))
+                } else {
+                    // drop in reversed order to avoid immediate rematches
+                    let phase = 2 * (slot.phase - 1);
+                    let place = self.games_in_round(Bracket::Lower, phase) - 1 - slot.place;
+                    Some(Slot::new(Bracket::Lower, phase, place))
+                }
+            }
+            (Bracket::Lower, Outcome::Winner) => {
+                if slot.phase == self.lower_rounds() {
+                    Some(Slot::new(Bracket::GrandFinal, 1, 0))
+                } else if slot.phase % 2 == 1 {
+                    Some(Slot::new(Bracket::Lower, slot.phase + 1, slot.place))
+                } else {
+                    Some(Slot::new(Bracket::Lower, slot.phase + 1, slot.place / 2))
+                }
+            }
+            (Bracket::Lower, Outcome::Loser) => None,
+            // the bracket reset is decided on the result, not on the layout
+            (Bracket::GrandFinal, _) => None,
+            (Bracket::ThirdPlace, _) => None,
+        }
+    }
+
+    // the two games giving their teams to the given game, team1 first
+    pub fn feeds(&self, slot: Slot) -> Option<[Feed; 2]> {
+        let winner = |bracket, phase, place| Feed {
+            slot: Slot::new(bracket, phase, place),
+            outcome: Outcome::Winner,
+        };
+        let loser = |bracket, phase, place| Feed {
+            slot: Slot::new(bracket, phase, place),
+            outcome: Outcome::Loser,
+        };
+
+        match slot.bracket {
+            Bracket::Upper if slot.phase > 1 => Some([
+                winner(Bracket::Upper, slot.phase - 1, 2 * slot.place),
+                winner(Bracket::Upper, slot.phase - 1, 2 * slot.place + 1),
+            ]),
+            Bracket::Upper => None,
+            Bracket::Lower if slot.phase == 1 => Some([
+                loser(Bracket::Upper, 1, 2 * slot.place),
+                loser(Bracket::Upper, 1, 2 * slot.place + 1),
+            ]),
+            Bracket::Lower if slot.phase % 2 == 0 => {
+                let upper_phase = slot.phase / 2 + 1;
+                let upper_place = self.games_in_round(Bracket::Upper, upper_phase) - 1 - slot.place;
+                Some([
+                    winner(Bracket::Lower, slot.phase - 1, slot.place),
+                    loser(Bracket::Upper, upper_phase, upper_place),
+                ])
+            }
+            Bracket::Lower => Some([
+                winner(Bracket::Lower, slot.phase - 1, 2 * slot.place),
+                winner(Bracket::Lower, slot.phase - 1, 2 * slot.place + 1),
+            ]),
+            Bracket::GrandFinal if slot.phase == 1 => {
+                let lower_champion = if self.rounds == 1 {
+                    loser(Bracket::Upper, 1, 0)
+                } else {
+                    winner(Bracket::Lower, self.lower_rounds(), 0)
+                };
+                Some([winner(Bracket::Upper, self.rounds, 0), lower_champion])
+            }
+            Bracket::GrandFinal => None,
+            Bracket::ThirdPlace if self.third_place => Some([
+                loser(Bracket::Upper, self.rounds - 1, 0),
+                loser(Bracket::Upper, self.rounds - 1, 1),
+            ]),
+            Bracket::ThirdPlace => None,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{NaiveDate, NaiveDateTime};
+
+// longest line of a calendar, continuation lines start with a space
+const MAX_LINE_OCTETS: usize = 75;
+
+// One game of the calendar
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub uid: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub summary: String,
+    pub location: Option<String>,
+    pub description: String,
+    // raised each time the game changes so calendar apps update it
+    pub sequence: i32,
+}
+
+// escape a text value, RFC 5545 3.3.11
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+// split a content line in lines of 75 octets at most without cutting a character, RFC 5545 3.1
+pub fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut octets = 0;
+
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // the leading space counts in the length of the line
+            octets = 1;
+        }
+
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+
+    folded
+}
+
+// sequence of a game changed at `updated_at`, in seconds so every later change raises it
+pub fn sequence(updated_at: NaiveDateTime) -> i32 {
+    let epoch = NaiveDate::from_ymd_opt(2023, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+
+    (updated_at - epoch).num_seconds().clamp(0, i32::MAX as i64) as i32
+}
+
+// dates are floating local times, like the dates of the tournaments
+fn format_date(date: NaiveDateTime) -> String {
+    date.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Render a calendar in the iCalendar format
+///
+/// `stamp` is the time the calendar was generated, in UTC.
+pub fn render(name: &str, stamp: NaiveDateTime, events: &[Event]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Tournoix//Tournoix//FR".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}Z", format_date(stamp)));
+        lines.push(format!("DTSTART:{}", format_date(event.start)));
+        lines.push(format!("DTEND:{}", format_date(event.end)));
+        lines.push(format!("SEQUENCE:{}", event.sequence));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape(location)));
+        }
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
@@ -1,48 +1,99 @@
 use argon2::{Config, Error, Variant, Version};
 use rand::Rng;
+use rocket::serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 use uuid::Uuid;
 
-pub fn validate_password(password: &str) -> bool {
-    // Convert password to ascii
-    let password = password.as_bytes();
+// Rule of the password policy a password breaks
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PasswordRule {
+    TooShort,
+    TooLong,
+    NoUppercase,
+    NoLowercase,
+    NoDigit,
+    NoSpecial,
+}
+
+impl PasswordRule {
+    // name of the rule in the error sent back, the UI translates it
+    pub fn code(&self) -> &'static str {
+        match self {
+            PasswordRule::TooShort => "too_short",
+            PasswordRule::TooLong => "too_long",
+            PasswordRule::NoUppercase => "no_uppercase",
+            PasswordRule::NoLowercase => "no_lowercase",
+            PasswordRule::NoDigit => "no_digit",
+            PasswordRule::NoSpecial => "no_special",
+        }
+    }
+}
+
+/// Every rule of the password policy the password breaks, in the order of
+/// the policy
+///
+/// A password has between 8 and 64 characters with at least one uppercase
+/// letter, one lowercase letter, one digit and one special character.
+pub fn password_violations(password: &str) -> Vec<PasswordRule> {
+    let mut violations = vec![];
+    let length = password.chars().count();
 
     // Check if password is between 8 and 64 characters
-    if password.len() < 8 || password.len() > 64 {
-        return false;
+    if length < 8 {
+        violations.push(PasswordRule::TooShort);
+    }
+    if length > 64 {
+        violations.push(PasswordRule::TooLong);
     }
 
     // Check if password contains at least one uppercase letter
-    if !password.iter().any(|&c| c.is_ascii_uppercase()) {
-        return false;
+    if !password.chars().any(|c| c.is_uppercase()) {
+        violations.push(PasswordRule::NoUppercase);
     }
 
     // Check if password contains at least one lowercase letter
-    if !password.iter().any(|&c| c.is_ascii_lowercase()) {
-        return false;
+    if !password.chars().any(|c| c.is_lowercase()) {
+        violations.push(PasswordRule::NoLowercase);
     }
 
     // Check if password contains at least one number
-    if !password.iter().any(|&c| c.is_ascii_digit()) {
-        return false;
+    if !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push(PasswordRule::NoDigit);
     }
 
     // Check if password contains at least one special character
-    if !password.iter().any(|&c| c.is_ascii_punctuation()) {
-        return false;
+    if !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+        violations.push(PasswordRule::NoSpecial);
     }
 
-    // Password is valid
-    true
+    violations
 }
 
-// Hash password
-pub fn hash_password(password: &str) -> Result<String, Error> {
-    // Validate password
-    // if !validate_password(password) {
-    //     // Return error message if password is invalid
-    //     return Err(Error::PwdTooShort);
-    // }
+pub fn validate_password(password: &str) -> bool {
+    password_violations(password).is_empty()
+}
+
+// Cost of the Argon2 hashes, read from the `argon2` table of the Rocket config
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HashConfig {
+    // memory in KiB
+    pub mem_cost: u32,
+    pub time_cost: u32,
+    pub lanes: u32,
+}
+
+impl Default for HashConfig {
+    fn default() -> Self {
+        HashConfig {
+            mem_cost: 65536,
+            time_cost: 2,
+            lanes: 4,
+        }
+    }
+}
 
+// Hash password
+pub fn hash_password(password: &str, hash_config: &HashConfig) -> Result<String, Error> {
     // Convert password to ascii
     let password = password.as_bytes();
 
@@ -53,9 +104,9 @@ pub fn hash_password(password: &str) -> Result<String, Error> {
     let config = Config {
         variant: Variant::Argon2id,
         version: Version::Version13,
-        mem_cost: 65536,
-        time_cost: 2,
-        lanes: 4,
+        mem_cost: hash_config.mem_cost,
+        time_cost: hash_config.time_cost,
+        lanes: hash_config.lanes,
         thread_mode: argon2::ThreadMode::Parallel,
         secret: &[],
         ad: &[],
@@ -69,10 +120,42 @@ pub fn hash_password(password: &str) -> Result<String, Error> {
     Ok(hash)
 }
 
-// Verify password
+// Verify password, a malformed hash matches no password
 pub fn verify_password(hash: &str, password: &str) -> bool {
     // Verify password
-    argon2::verify_encoded(hash, password.as_bytes()).unwrap()
+    argon2::verify_encoded(hash, password.as_bytes()).unwrap_or(false)
+}
+
+// hash of a random password, made once with the parameters of the config
+static DUMMY_HASH: OnceLock<Option<String>> = OnceLock::new();
+
+/// Check a password against a hash no login uses
+///
+/// Takes as long as `verify_password` on a hash made with `hash_config`, a
+/// login to an unknown account answers in the same time as a wrong password.
+pub fn verify_dummy_password(password: &str, hash_config: &HashConfig) {
+    let hash = DUMMY_HASH.get_or_init(|| hash_password(&generate_token(), hash_config).ok());
+    if let Some(hash) = hash {
+        verify_password(hash, password);
+    }
+}
+
+/// Whether the hash was made with other parameters than `hash_config`
+///
+/// A hash that can't be read needs a new one as well. The hash is encoded as
+/// `$argon2id$v=19$m=65536,t=2,p=4$salt$hash`.
+pub fn needs_rehash(hash: &str, hash_config: &HashConfig) -> bool {
+    let parts: Vec<&str> = hash.split('$').collect();
+    if parts.len() != 6 || parts[1] != "argon2id" || parts[2] != "v=19" {
+        return true;
+    }
+
+    let expected = format!(
+        "m={},t={},p={}",
+        hash_config.mem_cost, hash_config.time_cost, hash_config.lanes
+    );
+
+    parts[3] != expected
 }
 
 // Generate uuid (Use as a temp user id alias i.e.)
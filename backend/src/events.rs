@@ -0,0 +1,49 @@
+use rocket::serde::Serialize;
+use rocket::tokio::sync::broadcast::{channel, Receiver, Sender};
+
+// events kept for a slow spectator before it starts missing some
+const CHANNEL_CAPACITY: usize = 1024;
+
+// What changed in a tournament
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    GameUpdated,
+    GameClosed,
+    BettingClosed,
+    BetsChanged,
+    TeamsChanged,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TournamentEvent {
+    pub tournament_id: i32,
+    pub kind: EventKind,
+    pub game_id: Option<i32>,
+}
+
+// Channel shared by the routes, every open stream gets a copy of each event
+#[derive(Clone)]
+pub struct Events {
+    sender: Sender<TournamentEvent>,
+}
+
+impl Events {
+    pub fn new() -> Events {
+        let (sender, _) = channel(CHANNEL_CAPACITY);
+        Events { sender }
+    }
+
+    pub fn send(&self, tournament_id: i32, kind: EventKind, game_id: Option<i32>) {
+        // nobody is watching the tournament
+        let _ = self.sender.send(TournamentEvent {
+            tournament_id,
+            kind,
+            game_id,
+        });
+    }
+
+    pub fn subscribe(&self) -> Receiver<TournamentEvent> {
+        self.sender.subscribe()
+    }
+}
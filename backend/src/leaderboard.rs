@@ -0,0 +1,83 @@
+use rocket::serde::{Deserialize, Serialize};
+
+// Settled bets of a user added up, a bet is won when it brought back more
+// than its stake and lost when it brought back less
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Totals {
+    pub user_id: i32,
+    pub bets: i32,
+    pub won: i32,
+    pub lost: i32,
+    pub staked: i32,
+    pub net: i32,
+}
+
+// Betting record of a user
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Record {
+    pub user_id: i32,
+    // users with the same net share their rank
+    pub rank: i32,
+    pub bets: i32,
+    pub won: i32,
+    pub lost: i32,
+    pub staked: i32,
+    pub net: i32,
+    // share of the bets won among the bets won or lost, none without such bet
+    pub accuracy: Option<f64>,
+    // net over staked, none without stake
+    pub roi: Option<f64>,
+}
+
+/// Rank the users by the nuts their settled bets won
+///
+/// The users with the best net come first, ties are broken by return on
+/// investment then by number of bets, and users with the same net share their
+/// rank. A refunded bet counts in neither the bets won nor the bets lost.
+pub fn leaderboard(totals: &[Totals]) -> Vec<Record> {
+    let mut records: Vec<Record> = totals
+        .iter()
+        .map(|totals| Record {
+            user_id: totals.user_id,
+            rank: 0,
+            bets: totals.bets,
+            won: totals.won,
+            lost: totals.lost,
+            staked: totals.staked,
+            net: totals.net,
+            accuracy: if totals.won + totals.lost > 0 {
+                Some(totals.won as f64 / (totals.won + totals.lost) as f64)
+            } else {
+                None
+            },
+            roi: if totals.staked > 0 {
+                Some(totals.net as f64 / totals.staked as f64)
+            } else {
+                None
+            },
+        })
+        .collect();
+
+    // the returns on investment are compared by cross multiplication to stay exact
+    records.sort_by(|a, b| {
+        b.net
+            .cmp(&a.net)
+            .then_with(|| {
+                let a_roi = a.net as i64 * b.staked.max(1) as i64;
+                let b_roi = b.net as i64 * a.staked.max(1) as i64;
+                b_roi.cmp(&a_roi)
+            })
+            .then(b.bets.cmp(&a.bets))
+            .then(a.user_id.cmp(&b.user_id))
+    });
+
+    for index in 0..records.len() {
+        records[index].rank = if index > 0 && records[index].net == records[index - 1].net {
+            records[index - 1].rank
+        } else {
+            index as i32 + 1
+        };
+    }
+
+    records
+}
@@ -0,0 +1,153 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Local;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use log::{info, warn};
+
+use crate::crypto;
+
+// Mail sent by the application
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Way the mails leave the application, chosen at launch by `from_env`
+pub trait Mailer: Send + Sync {
+    fn send(&self, mail: &Mail) -> Result<(), String>;
+}
+
+// Send the mails through an SMTP relay
+pub struct SmtpMailer {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: Option<u16>,
+        credentials: Option<(String, String)>,
+        from: &str,
+    ) -> Result<Self, String> {
+        let mut transport = SmtpTransport::starttls_relay(host).map_err(|e| e.to_string())?;
+        if let Some(port) = port {
+            transport = transport.port(port);
+        }
+        if let Some((username, password)) = credentials {
+            transport = transport.credentials(Credentials::new(username, password));
+        }
+
+        Ok(SmtpMailer {
+            transport: transport.build(),
+            from: from.parse().map_err(|e: lettre::address::AddressError| e.to_string())?,
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(mail.to.parse().map_err(|e: lettre::address::AddressError| e.to_string())?)
+            .subject(mail.subject.clone())
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body.clone())
+            .map_err(|e| e.to_string())?;
+
+        self.transport.send(&message).map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
+
+// Write the mails in a directory, or only log them without one, for local
+// development and tests
+pub struct FileMailer {
+    pub dir: Option<PathBuf>,
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        info!(
+            "{} - Mail \"{}\" to {}:\n{}",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            mail.subject,
+            mail.to,
+            mail.body
+        );
+
+        if let Some(dir) = &self.dir {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+            // the time keeps the mails in order, the token keeps two mails of the same instant apart
+            let path = dir.join(format!(
+                "{}-{}-{}.txt",
+                Local::now().format("%Y%m%d%H%M%S"),
+                mail.to,
+                crypto::generate_token()
+            ));
+            let content = format!("To: {}\nSubject: {}\n\n{}\n", mail.to, mail.subject, mail.body);
+            fs::write(path, content).map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+// SMTP when `SMTP_HOST` is set, files in `MAIL_DIR` otherwise
+pub fn from_env() -> Arc<dyn Mailer> {
+    if let Some(host) = env::var("SMTP_HOST").ok().filter(|h| !h.is_empty()) {
+        let port = env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok());
+        let credentials = match (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            (Ok(username), Ok(password)) => Some((username, password)),
+            _ => None,
+        };
+        let from = env::var("MAIL_FROM").unwrap_or("Tournoix <noreply@tournoix.ch>".into());
+
+        match SmtpMailer::new(&host, port, credentials, &from) {
+            Ok(mailer) => return Arc::new(mailer),
+            Err(e) => warn!("Invalid SMTP configuration, the mails will only be logged: {}", e),
+        }
+    }
+
+    Arc::new(FileMailer {
+        dir: env::var("MAIL_DIR").ok().map(PathBuf::from),
+    })
+}
+
+// address of the web application, the links of the mails point to it
+pub fn app_url() -> String {
+    env::var("APP_URL").unwrap_or("http://localhost:8080".into())
+}
+
+/// Mail asking a new user to confirm their address
+pub fn verification_mail(to: &str, name: &str, app_url: &str, token: &str) -> Mail {
+    Mail {
+        to: to.into(),
+        subject: "Confirmez votre adresse mail".into(),
+        body: format!(
+            "Bonjour {},\n\nBienvenue sur Tournoix ! Confirmez votre adresse mail en ouvrant ce lien :\n{}/verify/{}\n\nLe lien est valable 24 heures.",
+            name, app_url, token
+        ),
+    }
+}
+
+/// Mail with the link to choose a new password
+pub fn reset_mail(to: &str, name: &str, app_url: &str, token: &str) -> Mail {
+    Mail {
+        to: to.into(),
+        subject: "Réinitialisation de votre mot de passe".into(),
+        body: format!(
+            "Bonjour {},\n\nUne réinitialisation de votre mot de passe a été demandée. Choisissez-en un nouveau en ouvrant ce lien :\n{}/reset-password/{}\n\nLe lien est valable une heure et ne peut servir qu'une fois. Ignorez ce mail si vous n'êtes pas à l'origine de la demande.",
+            name, app_url, token
+        ),
+    }
+}
@@ -17,13 +17,27 @@ use rocket::{
 };
 use rocket_sync_db_pools::database;
 use routes::{
-    auth::{login, logout, register},
-    users::{get_current_user, get_user},
+    auth::{forgot_password, login, logout, refresh, register, resend_verification_email, reset_password, verify_email},
+    users::{
+        change_password, delete_all_sessions, delete_current_user, delete_session, get_current_user,
+        get_sessions, get_user, update_current_user,
+    },
 };
 use serde::Serialize;
 
+use crate::auto_close::AutoCloseBetting;
+use crate::crypto::HashConfig;
+use crate::events::Events;
+use crate::rate_limit::{MemoryStore, RateLimitConfig, RateLimiter};
+use crate::routes::auth::RetryAfter;
+use crate::routes::calendar::*;
+use crate::routes::events::*;
 use crate::routes::game::*;
+use crate::routes::leaderboard::*;
+use crate::routes::market::*;
 use crate::routes::nut::*;
+use crate::routes::schedule::*;
+use crate::routes::series::*;
 use crate::routes::subscription::*;
 use crate::routes::team::*;
 use crate::routes::tournoix::*;
@@ -31,10 +45,24 @@ use crate::routes::bet::*;
 
 use simplelog::{ColorChoice, Config, LevelFilter, TermLogger, TerminalMode};
 
+mod auto_close;
+mod betting;
+mod bracket;
+mod calendar;
 pub mod crypto;
+mod events;
+mod leaderboard;
+mod mailer;
 mod models;
+mod ranking;
+mod rate_limit;
 mod routes;
+mod scheduler;
 mod schema;
+mod seeding;
+mod series;
+mod standings;
+mod swiss;
 mod tests;
 
 #[database("tournoix_db")]
@@ -55,7 +83,7 @@ impl Fairing for CORS {
         response.set_header(Header::new("Access-Control-Allow-Origin", "*"));
         response.set_header(Header::new(
             "Access-Control-Allow-Methods",
-            "POST, GET, PATCH, DELETE, OPTIONS",
+            "POST, GET, PUT, PATCH, DELETE, OPTIONS",
         ));
         response.set_header(Header::new("Access-Control-Allow-Headers", "*"));
         response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
@@ -88,9 +116,38 @@ fn rocket() -> _ {
         ColorChoice::Auto,
     );
 
-    rocket::build()
+    let rocket = rocket::build();
+
+    // the default cost is kept when the config has no argon2 table
+    let hash_config = match rocket.figment().find_value("argon2") {
+        Ok(_) => rocket
+            .figment()
+            .extract_inner::<HashConfig>("argon2")
+            .expect("Invalid argon2 config"),
+        Err(_) => HashConfig::default(),
+    };
+
+    // the limits missing from the config keep their default
+    let rate_limit_config = match rocket.figment().find_value("rate_limit") {
+        Ok(_) => rocket
+            .figment()
+            .extract_inner::<RateLimitConfig>("rate_limit")
+            .expect("Invalid rate_limit config"),
+        Err(_) => RateLimitConfig::default(),
+    };
+
+    rocket
         .attach(MysqlConnection::fairing())
         .attach(CORS)
+        .attach(AutoCloseBetting)
+        .manage(Events::new())
+        .manage(mailer::from_env())
+        .manage(hash_config)
+        .manage(RateLimiter::new(
+            Box::new(MemoryStore::default()),
+            rate_limit_config,
+        ))
+        .register("/api", catchers![too_many_requests])
         .mount("/", routes![index, static_file])
         .mount(
             "/api",
@@ -100,6 +157,12 @@ fn rocket() -> _ {
                 get_user_tournoix,
                 get_user_subscription,
                 get_current_user,
+                update_current_user,
+                change_password,
+                delete_current_user,
+                get_sessions,
+                delete_session,
+                delete_all_sessions,
                 // Tournoix
                 get_tournoix_is_owner,
                 get_tournoix_results,
@@ -109,6 +172,8 @@ fn rocket() -> _ {
                 update_tournoix,
                 delete_tournoix,
                 get_tournoix_by_code,
+                get_group_standings,
+                get_swiss_standings,
                 // Teams
                 get_teams,
                 create_team,
@@ -120,7 +185,12 @@ fn rocket() -> _ {
                 // Auth
                 login,
                 logout,
+                refresh,
                 register,
+                verify_email,
+                resend_verification_email,
+                forgot_password,
+                reset_password,
                 // games
                 get_tournoix_game,
                 get_team_game,
@@ -132,16 +202,43 @@ fn rocket() -> _ {
                 remove_all_games,
                 create_games_elim,
                 remove_all_games_elim,
+                get_elim_seeding,
+                update_elim_seeding,
+                create_swiss_round,
+                // Series
+                get_series_formats,
+                update_series_formats,
+                get_tournament_sets,
+                update_game_sets,
+                // Schedule
+                create_schedule,
+                get_tournament_calendar,
+                get_team_calendar,
+                // Events
+                get_tournament_events,
                 // Nuts
                 get_nut,
+                get_nut_history,
+                get_nut_audit,
                 update_nut,
                 // Bets
                 get_game_bet,
                 get_user_game_bet,
                 get_user_game_bet_result,
+                get_game_odds,
                 create_bet,
                 update_bet,
                 delete_bet,
+                // Markets
+                get_score_bets,
+                create_score_bet,
+                delete_score_bet,
+                get_champion_bets,
+                create_champion_bet,
+                delete_champion_bet,
+                // Leaderboard
+                get_seasons,
+                get_leaderboard,
                 // Others
                 all_options,
                 api_hole,
@@ -149,6 +246,26 @@ fn rocket() -> _ {
         )
 }
 
+// requests refused by the rate limit guards
+#[catch(429)]
+fn too_many_requests(request: &Request) -> Json<ErrorResponse> {
+    let description = match request.local_cache(|| RetryAfter(None)).0 {
+        Some(wait) => format!(
+            "Too many requests, retry in {} seconds",
+            rate_limit::seconds(wait)
+        ),
+        None => "Too many requests".to_string(),
+    };
+
+    Json(ErrorResponse {
+        error: ErrorBody {
+            code: 429,
+            reason: "Too Many Requests".into(),
+            description,
+        },
+    })
+}
+
 #[options("/<_..>")]
 fn all_options() -> NoContent {
 	NoContent
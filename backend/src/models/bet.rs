@@ -1,3 +1,4 @@
+use crate::betting::TeamOdds;
 use crate::schema::bets;
 use rocket::serde::{Deserialize, Serialize};
 
@@ -12,6 +13,8 @@ pub struct Bet {
     pub fk_teams: i32,
     pub fk_users: i32,
     pub nb_nut: i32,
+    // nuts given back when the game was settled, the stake included
+    pub payout: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Identifiable, Clone)]
@@ -46,3 +49,21 @@ pub struct PathBet {
     pub fk_teams: Option<i32>,
     pub nb_nut: Option<i32>,
 }
+
+// Split of the pot of an open game, with the payout of a bet the user is about to place
+#[derive(Serialize, Deserialize)]
+pub struct GameOdds {
+    pub fk_games: i32,
+    pub pot: i32,
+    pub teams: Vec<TeamOdds>,
+    pub preview: Option<BetPreview>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BetPreview {
+    pub fk_teams: i32,
+    pub nb_nut: i32,
+    // nuts given back if the team wins, the stake included
+    pub payout: i32,
+    pub gain: i32,
+}
@@ -1,8 +1,42 @@
+use crate::bracket::{Bracket, Slot};
 use crate::schema::games;
 use rocket::serde::{Deserialize, Serialize};
 
 use super::team::Team;
 
+// How a draw was decided, the winner is stored in `tiebreak_winner`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tiebreak {
+    None = 0,
+    Penalties,
+    Overtime,
+    CoinToss,
+}
+
+impl Tiebreak {
+    pub fn from_i32(value: i32) -> Option<Tiebreak> {
+        match value {
+            0 => Some(Tiebreak::None),
+            1 => Some(Tiebreak::Penalties),
+            2 => Some(Tiebreak::Overtime),
+            3 => Some(Tiebreak::CoinToss),
+            _ => None,
+        }
+    }
+}
+
+// the tiebreak winner only counts when the scores are equal, 0 when there is none
+fn winner(team1: i32, team2: i32, score1: i32, score2: i32, tiebreak_winner: i32) -> Option<i32> {
+    if score1 > score2 {
+        Some(team1)
+    } else if score2 > score1 {
+        Some(team2)
+    } else if tiebreak_winner == team1 || tiebreak_winner == team2 {
+        Some(tiebreak_winner)
+    } else {
+        None
+    }
+}
 
 #[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
 #[diesel(belongs_to(Team))]
@@ -18,15 +52,52 @@ pub struct Game {
     pub place: i32,
     pub status: i32,
     pub has_gained_nut: bool,
+    pub bracket: i32,
+    pub round: i32,
+    pub tiebreak: i32,
+    pub tiebreak_winner: i32,
+    pub scheduled_at: Option<chrono::NaiveDateTime>,
+    pub court: Option<i32>,
+    // set by the database on every change of the game
+    pub updated_at: chrono::NaiveDateTime,
 }
 
 impl Game {
-    pub fn winner(&self) -> i32 {
-        if self.score1 > self.score2 {
-            return self.fk_team1;
-        } else {
-            return self.fk_team2;
+    // none for a draw without tiebreak result
+    pub fn winner(&self) -> Option<i32> {
+        if self.is_bye() {
+            return Some(self.fk_team1);
         }
+
+        winner(
+            self.fk_team1,
+            self.fk_team2,
+            self.score1,
+            self.score2,
+            self.tiebreak_winner,
+        )
+    }
+
+    pub fn loser(&self) -> Option<i32> {
+        match self.winner() {
+            Some(winner) if winner == self.fk_team1 => Some(self.fk_team2),
+            Some(_) => Some(self.fk_team1),
+            None => None,
+        }
+    }
+
+    // a team without opponent for the round, the game is closed on creation
+    pub fn is_bye(&self) -> bool {
+        self.fk_team1 == self.fk_team2
+    }
+
+    // position of the game in the elimination phase, none for qualification games
+    pub fn slot(&self) -> Option<Slot> {
+        if self.phase < 1 {
+            return None;
+        }
+
+        Bracket::from_i32(self.bracket).map(|bracket| Slot::new(bracket, self.phase, self.place))
     }
 }
 
@@ -44,16 +115,24 @@ pub struct GameWithGroup {
     pub place: i32,
     pub status: i32,
     pub has_gained_nut: bool,
+    pub bracket: i32,
+    pub round: i32,
+    pub tiebreak: i32,
+    pub tiebreak_winner: i32,
+    pub scheduled_at: Option<chrono::NaiveDateTime>,
+    pub court: Option<i32>,
     pub group: i32,
 }
 
 impl GameWithGroup {
-    pub fn winner(&self) -> i32 {
-        if self.score1 > self.score2 {
-            return self.fk_team1;
-        } else {
-            return self.fk_team2;
-        }
+    pub fn winner(&self) -> Option<i32> {
+        winner(
+            self.fk_team1,
+            self.fk_team2,
+            self.score1,
+            self.score2,
+            self.tiebreak_winner,
+        )
     }
 }
 
@@ -71,6 +150,12 @@ pub struct GameWithTeams {
     pub place: i32,
     pub status: i32,
     pub has_gained_nut: bool,
+    pub bracket: i32,
+    pub round: i32,
+    pub tiebreak: i32,
+    pub tiebreak_winner: i32,
+    pub scheduled_at: Option<chrono::NaiveDateTime>,
+    pub court: Option<i32>,
     pub group: i32,
 }
 
@@ -86,6 +171,8 @@ pub struct NewGame {
     pub phase: i32,
     pub place: i32,
     pub status: i32,
+    pub bracket: i32,
+    pub round: i32,
 }
 
 #[derive(Serialize, Deserialize, AsChangeset, Clone)]
@@ -99,4 +186,6 @@ pub struct PatchGame {
     pub place: Option<i32>,
     pub status: Option<i32>,
     pub has_gained_nut: Option<bool>,
+    pub tiebreak: Option<i32>,
+    pub tiebreak_winner: Option<i32>,
 }
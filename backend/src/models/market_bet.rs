@@ -0,0 +1,67 @@
+use crate::schema::market_bets;
+use rocket::serde::{Deserialize, Serialize};
+
+// What a market bet is on, the winner of a game is bet on in `bets`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Market {
+    // score of a game
+    ExactScore = 0,
+    // winner of the tournament
+    Champion,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
+#[diesel(belongs_to(Tournament))]
+#[diesel(belongs_to(User))]
+#[diesel(belongs_to(Game))]
+#[diesel(belongs_to(Team))]
+#[diesel(table_name = market_bets)]
+pub struct MarketBet {
+    pub id: i32,
+    pub market: i32,
+    pub fk_tournaments: i32,
+    pub fk_users: i32,
+    pub fk_games: Option<i32>,
+    pub fk_teams: Option<i32>,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub nb_nut: i32,
+    // none until the market is settled
+    pub payout: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Insertable)]
+#[diesel(table_name = market_bets)]
+pub struct NewMarketBet {
+    pub market: i32,
+    pub fk_tournaments: i32,
+    pub fk_users: i32,
+    pub fk_games: Option<i32>,
+    pub fk_teams: Option<i32>,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub nb_nut: i32,
+}
+
+#[derive(Serialize, Deserialize, Queryable, Clone)]
+pub struct MarketBetWithUser {
+    pub id: i32,
+    pub market: i32,
+    pub fk_tournaments: i32,
+    pub fk_users: i32,
+    pub fk_games: Option<i32>,
+    pub fk_teams: Option<i32>,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub nb_nut: i32,
+    pub payout: Option<i32>,
+    pub username: String,
+}
+
+// Bets of a market and whether new ones are taken
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MarketBets {
+    pub is_open: bool,
+    pub pot: i32,
+    pub bets: Vec<MarketBetWithUser>,
+}
@@ -1,6 +1,11 @@
 pub mod bet;
 pub mod game;
+pub mod market_bet;
 pub mod nut;
+pub mod nut_transaction;
+pub mod season;
+pub mod series;
+pub mod session;
 pub mod subscription;
 pub mod team;
 pub mod token;
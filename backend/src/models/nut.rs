@@ -1,6 +1,12 @@
-use crate::schema::nuts;
+use crate::schema::{nut_transactions, nuts};
+use diesel::prelude::*;
 use rocket::serde::{Deserialize, Serialize};
 
+use super::nut_transaction::{NewNutTransaction, TransactionKind};
+
+// nuts given to a player joining a tournament
+pub const INITIAL_STOCK: i32 = 20;
+
 #[derive(Serialize, Deserialize, Queryable, Identifiable, Clone)]
 #[diesel(belongs_to(User))]
 #[diesel(belongs_to(Tournament))]
@@ -12,6 +18,37 @@ pub struct Nut {
     pub stock: i32,
 }
 
+impl Nut {
+    // change the stock and write why in the ledger, to call in the transaction locking the nut
+    pub fn record(
+        &self,
+        c: &mut diesel::MysqlConnection,
+        kind: TransactionKind,
+        amount: i32,
+        fk_games: Option<i32>,
+        reason: Option<String>,
+    ) -> QueryResult<Nut> {
+        diesel::update(nuts::table.find(self.id))
+            .set(nuts::stock.eq(nuts::stock + amount))
+            .execute(c)?;
+
+        let nut = nuts::table.find(self.id).first::<Nut>(c)?;
+
+        diesel::insert_into(nut_transactions::table)
+            .values(NewNutTransaction {
+                fk_nuts: nut.id,
+                kind: kind as i32,
+                amount,
+                balance: nut.stock,
+                fk_games,
+                reason,
+            })
+            .execute(c)?;
+
+        Ok(nut)
+    }
+}
+
 #[derive(Serialize, Deserialize, Insertable)]
 #[diesel(belongs_to(User))]
 #[diesel(belongs_to(Tournament))]
@@ -22,10 +59,33 @@ pub struct NewNut {
     pub stock: i32,
 }
 
-#[derive(Serialize, Deserialize, AsChangeset, Clone)]
-#[diesel(belongs_to(User))]
-#[diesel(belongs_to(Tournament))]
-#[diesel(table_name = nuts)]
+impl NewNut {
+    // create the nut of a player with the initial grant as first entry of its ledger
+    pub fn grant(
+        c: &mut diesel::MysqlConnection,
+        fk_users: i32,
+        fk_tournaments: i32,
+    ) -> QueryResult<Nut> {
+        diesel::insert_into(nuts::table)
+            .values(NewNut {
+                fk_users,
+                fk_tournaments,
+                stock: 0,
+            })
+            .execute(c)?;
+
+        let nut = nuts::table
+            .filter(nuts::fk_users.eq(fk_users))
+            .filter(nuts::fk_tournaments.eq(fk_tournaments))
+            .first::<Nut>(c)?;
+
+        nut.record(c, TransactionKind::Grant, INITIAL_STOCK, None, None)
+    }
+}
+
+// Change of the stock by the owner of the tournament
+#[derive(Serialize, Deserialize, Clone)]
 pub struct PatchNut {
-    pub stock: Option<i32>,
+    pub stock: i32,
+    pub reason: String,
 }
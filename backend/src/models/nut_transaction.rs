@@ -0,0 +1,55 @@
+use crate::schema::nut_transactions;
+use rocket::serde::{Deserialize, Serialize};
+
+// Why the stock of a nut changed
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransactionKind {
+    // nuts given when joining the tournament
+    Grant = 0,
+    Stake,
+    Refund,
+    Payout,
+    // stock changed by the owner of the tournament
+    Adjustment,
+}
+
+// One change of the stock of a nut, the ledger is never updated
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
+#[diesel(belongs_to(Nut))]
+#[diesel(table_name = nut_transactions)]
+pub struct NutTransaction {
+    pub id: i32,
+    pub fk_nuts: i32,
+    pub kind: i32,
+    // negative when nuts are taken from the stock
+    pub amount: i32,
+    // stock after the transaction
+    pub balance: i32,
+    pub fk_games: Option<i32>,
+    pub reason: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable)]
+#[diesel(belongs_to(Nut))]
+#[diesel(table_name = nut_transactions)]
+pub struct NewNutTransaction {
+    pub fk_nuts: i32,
+    pub kind: i32,
+    pub amount: i32,
+    pub balance: i32,
+    pub fk_games: Option<i32>,
+    pub reason: Option<String>,
+}
+
+// Ledger of a player for the owner of the tournament
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NutAudit {
+    pub fk_nuts: i32,
+    pub fk_users: i32,
+    pub username: String,
+    pub stock: i32,
+    // sum of the ledger, differs from the stock if it was changed outside of it
+    pub ledger_stock: i32,
+    pub transactions: Vec<NutTransaction>,
+}
@@ -0,0 +1,61 @@
+use crate::leaderboard::{Record, Totals};
+use crate::schema::seasons;
+use diesel::sql_types::{BigInt, Integer, Varchar};
+use rocket::serde::{Deserialize, Serialize};
+
+// Date range grouping the tournaments of a leaderboard
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
+#[diesel(table_name = seasons)]
+pub struct Season {
+    pub id: i32,
+    pub name: String,
+    pub start_date: chrono::NaiveDateTime,
+    // excluded from the season
+    pub end_date: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub username: String,
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Leaderboard {
+    // none for the leaderboard across all tournaments
+    pub season: Option<Season>,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+// Settled bets of a user added up by the database, the sums are 64 bits
+#[derive(QueryableByName)]
+pub struct UserTotals {
+    #[diesel(sql_type = Integer)]
+    pub user_id: i32,
+    #[diesel(sql_type = Varchar)]
+    pub username: String,
+    #[diesel(sql_type = BigInt)]
+    pub bets: i64,
+    #[diesel(sql_type = BigInt)]
+    pub won: i64,
+    #[diesel(sql_type = BigInt)]
+    pub lost: i64,
+    #[diesel(sql_type = BigInt)]
+    pub staked: i64,
+    #[diesel(sql_type = BigInt)]
+    pub net: i64,
+}
+
+impl UserTotals {
+    pub fn totals(&self) -> Totals {
+        Totals {
+            user_id: self.user_id,
+            bets: self.bets as i32,
+            won: self.won as i32,
+            lost: self.lost as i32,
+            staked: self.staked as i32,
+            net: self.net as i32,
+        }
+    }
+}
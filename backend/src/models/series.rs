@@ -0,0 +1,56 @@
+use crate::schema::{game_sets, series_formats};
+use crate::series::SetScore;
+use rocket::serde::{Deserialize, Serialize};
+
+// Number of sets played in the games of an elimination round
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
+#[diesel(belongs_to(Tournament))]
+#[diesel(table_name = series_formats)]
+pub struct SeriesFormat {
+    pub id: i32,
+    pub fk_tournaments: i32,
+    pub bracket: i32,
+    pub phase: i32,
+    pub best_of: i32,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone)]
+#[diesel(belongs_to(Tournament))]
+#[diesel(table_name = series_formats)]
+pub struct NewSeriesFormat {
+    pub fk_tournaments: i32,
+    pub bracket: i32,
+    pub phase: i32,
+    pub best_of: i32,
+}
+
+// Result of one map/set of a game
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
+#[diesel(belongs_to(Game))]
+#[diesel(table_name = game_sets)]
+pub struct GameSet {
+    pub id: i32,
+    pub fk_games: i32,
+    pub number: i32,
+    pub score1: i32,
+    pub score2: i32,
+}
+
+impl GameSet {
+    pub fn score(&self) -> SetScore {
+        SetScore {
+            score1: self.score1,
+            score2: self.score2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone)]
+#[diesel(belongs_to(Game))]
+#[diesel(table_name = game_sets)]
+pub struct NewGameSet {
+    pub fk_games: i32,
+    pub number: i32,
+    pub score1: i32,
+    pub score2: i32,
+}
@@ -0,0 +1,37 @@
+use crate::schema::sessions;
+use rocket::serde::{Deserialize, Serialize};
+
+// Login of a user on a device, its refresh token gives new access tokens
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Clone, Debug)]
+#[diesel(belongs_to(User))]
+#[diesel(table_name = sessions)]
+pub struct Session {
+    pub id: i32,
+    pub fk_users: i32,
+    pub refresh_token: String,
+    pub user_agent: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: chrono::NaiveDateTime,
+    pub expiration_date: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone)]
+#[diesel(belongs_to(User))]
+#[diesel(table_name = sessions)]
+pub struct NewSession {
+    pub fk_users: i32,
+    pub refresh_token: String,
+    pub user_agent: Option<String>,
+    pub expiration_date: chrono::NaiveDateTime,
+}
+
+// Session shown to its user, without its refresh token
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionInfo {
+    pub id: i32,
+    pub user_agent: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: chrono::NaiveDateTime,
+    // the session of the request
+    pub is_current: bool,
+}
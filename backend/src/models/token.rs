@@ -1,6 +1,17 @@
 use crate::schema::tokens;
 use rocket::serde::{Deserialize, Serialize};
 
+// What a token gives access to
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenPurpose {
+    // logged in user
+    Session = 0,
+    // link confirming the address of a new user
+    EmailVerification,
+    // link choosing a new password, used once
+    PasswordReset,
+}
+
 #[derive(Serialize, Deserialize, Queryable, Identifiable, Debug)]
 #[diesel(primary_key(token))]
 #[diesel(belongs_to(User))]
@@ -10,6 +21,9 @@ pub struct Token {
     pub fk_users: i32,
     pub created_at: chrono::NaiveDateTime,
     pub expiration_date: chrono::NaiveDateTime,
+    pub purpose: i32,
+    // none for the tokens sent by mail
+    pub fk_sessions: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Insertable, Clone)]
@@ -20,4 +34,7 @@ pub struct NewToken {
     pub token: String,
     pub fk_users: i32,
     pub expiration_date: chrono::NaiveDateTime,
+    pub purpose: i32,
+    // none for the tokens sent by mail
+    pub fk_sessions: Option<i32>,
 }
@@ -1,4 +1,4 @@
-use crate::{schema::{tournaments, subscriptions}, MysqlConnection};
+use crate::{schema::{tournaments, subscriptions}, scheduler::ScheduleConfig, standings::Points, MysqlConnection};
 use diesel::{QueryDsl, ExpressionMethods};
 use rocket::serde::{Deserialize, Serialize};
 use diesel::prelude::*;
@@ -11,10 +11,24 @@ pub struct Score {
     pub val: i32,
 }
 
+// Final place of a team, see ranking::ranking
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TeamResult {
+    pub team_id: i32,
+    pub name: String,
+    pub place: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub scored: i32,
+    pub conceded: i32,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Results {
     pub subscribers: Vec<Score>,
-    pub teams: Vec<Score>,
+    pub teams: Vec<TeamResult>,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Identifiable)]
@@ -33,6 +47,24 @@ pub struct Tournament {
     pub is_qualif: bool,
     pub is_elim: bool,
     pub is_closed: bool,
+    pub is_double_elim: bool,
+    pub has_bracket_reset: bool,
+    pub is_swiss: bool,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: i32,
+    pub points_draw: i32,
+    pub points_loss: i32,
+    pub qualified_per_group: i32,
+    pub wildcards: i32,
+    pub has_third_place: bool,
+    // minutes, set when the games are scheduled
+    pub match_duration: Option<i32>,
+    // minutes before the start of a game when its bets close
+    pub betting_lead_time: i32,
+    // rest of the settings of the last schedule, the new games are placed with them
+    pub break_duration: Option<i32>,
+    pub courts: Option<i32>,
+    pub min_rest: Option<i32>,
 }
 
 impl Tournament {
@@ -51,6 +83,25 @@ impl Tournament {
             }
         }
     }
+
+    pub fn points(&self) -> Points {
+        Points {
+            win: self.points_win,
+            draw: self.points_draw,
+            loss: self.points_loss,
+        }
+    }
+
+    // settings of the last schedule from the given start, none before the games are scheduled
+    pub fn schedule_config(&self, start: chrono::NaiveDateTime) -> Option<ScheduleConfig> {
+        Some(ScheduleConfig {
+            start,
+            match_duration: self.match_duration? as i64,
+            break_duration: self.break_duration? as i64,
+            courts: self.courts?,
+            min_rest: self.min_rest? as i64,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Insertable, Clone)]
@@ -68,6 +119,16 @@ pub struct NewTournament {
     pub is_qualif: bool,
     pub is_elim: bool,
     pub is_closed: bool,
+    pub is_double_elim: bool,
+    pub has_bracket_reset: bool,
+    pub is_swiss: bool,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: i32,
+    pub points_draw: i32,
+    pub points_loss: i32,
+    pub qualified_per_group: i32,
+    pub wildcards: i32,
+    pub has_third_place: bool,
 }
 
 #[derive(Serialize, Deserialize, AsChangeset, Clone)]
@@ -83,4 +144,15 @@ pub struct PatchTournament {
     pub is_qualif: Option<bool>,
     pub is_elim: Option<bool>,
     pub is_closed: Option<bool>,
+    pub is_double_elim: Option<bool>,
+    pub has_bracket_reset: Option<bool>,
+    pub is_swiss: Option<bool>,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: Option<i32>,
+    pub points_draw: Option<i32>,
+    pub points_loss: Option<i32>,
+    pub qualified_per_group: Option<i32>,
+    pub wildcards: Option<i32>,
+    pub has_third_place: Option<bool>,
+    pub betting_lead_time: Option<i32>,
 }
@@ -8,6 +8,7 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub password: String,
+    pub email_verified: bool,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Identifiable, Clone)]
@@ -16,6 +17,8 @@ pub struct UserInfo {
     pub id: i32,
     pub name: String,
     pub email: String,
+    // the confirmation mail was followed
+    pub email_verified: bool,
 }
 
 #[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
@@ -25,3 +28,10 @@ pub struct NewUser {
     pub email: String,
     pub password: String,
 }
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Debug)]
+#[diesel(table_name = users)]
+pub struct PatchUser {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
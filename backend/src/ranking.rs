@@ -0,0 +1,183 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::bracket::{Bracket, Layout, Slot};
+use crate::scheduler::elimination_wave;
+use crate::standings::GameResult;
+
+// A closed elimination game, byes left out
+#[derive(Clone, Copy, Debug)]
+pub struct EliminationResult {
+    pub slot: Slot,
+    pub winner: i32,
+    pub loser: i32,
+}
+
+// Final place of a team with its record over the whole tournament
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Placement {
+    pub team_id: i32,
+    // teams knocked out in the same round share their place
+    pub place: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub scored: i32,
+    pub conceded: i32,
+}
+
+impl Placement {
+    fn new(team_id: i32, place: i32) -> Placement {
+        Placement {
+            team_id,
+            place,
+            played: 0,
+            won: 0,
+            drawn: 0,
+            lost: 0,
+            scored: 0,
+            conceded: 0,
+        }
+    }
+
+    fn add_result(&mut self, scored: i32, conceded: i32) {
+        self.played += 1;
+        self.scored += scored;
+        self.conceded += conceded;
+
+        if scored > conceded {
+            self.won += 1;
+        } else if scored < conceded {
+            self.lost += 1;
+        } else {
+            self.drawn += 1;
+        }
+    }
+}
+
+// a game after which the loser is out of the tournament
+fn is_eliminating(layout: &Layout, slot: &Slot) -> bool {
+    match slot.bracket {
+        Bracket::Upper => !layout.double_elim,
+        Bracket::Lower | Bracket::GrandFinal | Bracket::ThirdPlace => true,
+    }
+}
+
+// the last game of the elimination phase, played again after a bracket reset
+pub fn is_final(layout: &Layout, slot: &Slot) -> bool {
+    if layout.double_elim {
+        slot.bracket == Bracket::GrandFinal
+    } else {
+        slot.bracket == Bracket::Upper && slot.phase == layout.rounds
+    }
+}
+
+/// Final ranking of the teams, champion first
+///
+/// The teams of the elimination phase (`entrants`) come first: the winner and
+/// the loser of the final, the teams still playing for the title, the winner
+/// and the loser of the third place game, then the teams knocked out in the
+/// same round sharing a place, latest round first. The other teams follow in
+/// the `qualification` order. The record of each team counts every result,
+/// qualification and elimination games alike.
+pub fn ranking(
+    layout: &Layout,
+    entrants: &[i32],
+    eliminations: &[EliminationResult],
+    qualification: &[i32],
+    results: &[GameResult],
+) -> Vec<Placement> {
+    let qualification_order: HashMap<i32, usize> = qualification
+        .iter()
+        .enumerate()
+        .map(|(index, team)| (*team, index))
+        .collect();
+    // teams sharing a place are listed in qualification order
+    let sort_tier = |tier: &mut Vec<i32>| {
+        tier.sort_by_key(|team| (qualification_order.get(team).copied().unwrap_or(usize::MAX), *team))
+    };
+
+    let mut tiers: Vec<Vec<i32>> = vec![];
+    let mut placed: HashSet<i32> = HashSet::new();
+    let mut push_tier = |tier: Vec<i32>, tiers: &mut Vec<Vec<i32>>| {
+        let tier: Vec<i32> = tier.into_iter().filter(|team| placed.insert(*team)).collect();
+        if !tier.is_empty() {
+            tiers.push(tier);
+        }
+    };
+
+    // the grand final can be replayed after a bracket reset
+    let final_game = eliminations
+        .iter()
+        .filter(|e| is_final(layout, &e.slot))
+        .max_by_key(|e| e.slot.phase);
+    let third_place = eliminations
+        .iter()
+        .find(|e| e.slot.bracket == Bracket::ThirdPlace);
+
+    if let Some(final_game) = final_game {
+        push_tier(vec![final_game.winner], &mut tiers);
+        push_tier(vec![final_game.loser], &mut tiers);
+    }
+
+    let eliminated: HashSet<i32> = eliminations
+        .iter()
+        .filter(|e| is_eliminating(layout, &e.slot))
+        .map(|e| e.loser)
+        .collect();
+    let mut alive: Vec<i32> = entrants
+        .iter()
+        .copied()
+        .filter(|team| !eliminated.contains(team))
+        .collect();
+    sort_tier(&mut alive);
+    push_tier(alive, &mut tiers);
+
+    if let Some(third_place) = third_place {
+        push_tier(vec![third_place.winner], &mut tiers);
+        push_tier(vec![third_place.loser], &mut tiers);
+    }
+
+    let mut knocked_out: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for elimination in eliminations
+        .iter()
+        .filter(|e| is_eliminating(layout, &e.slot))
+    {
+        knocked_out
+            .entry(elimination_wave(layout, elimination.slot))
+            .or_default()
+            .push(elimination.loser);
+    }
+    for (_, mut tier) in knocked_out.into_iter().rev() {
+        sort_tier(&mut tier);
+        push_tier(tier, &mut tiers);
+    }
+
+    for team in qualification {
+        push_tier(vec![*team], &mut tiers);
+    }
+
+    let mut ranking: Vec<Placement> = vec![];
+    for tier in tiers {
+        let place = ranking.len() as i32 + 1;
+        ranking.extend(tier.into_iter().map(|team| Placement::new(team, place)));
+    }
+
+    let index: HashMap<i32, usize> = ranking
+        .iter()
+        .enumerate()
+        .map(|(index, placement)| (placement.team_id, index))
+        .collect();
+    for result in results.iter().filter(|r| r.team1 != r.team2) {
+        if let Some(i) = index.get(&result.team1) {
+            ranking[*i].add_result(result.score1, result.score2);
+        }
+        if let Some(i) = index.get(&result.team2) {
+            ranking[*i].add_result(result.score2, result.score1);
+        }
+    }
+
+    ranking
+}
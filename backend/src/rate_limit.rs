@@ -0,0 +1,327 @@
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rocket::serde::{Deserialize, Serialize};
+
+// entries kept by the memory store, the least recently used ones are dropped past it
+const MAX_ENTRIES: usize = 10_000;
+
+// Size and refill speed of a token bucket
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BucketConfig {
+    // requests allowed in a burst
+    pub capacity: u32,
+    // seconds to get back one request
+    pub refill_seconds: u64,
+}
+
+// Lockout of an account after failed logins in a row
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LockoutConfig {
+    // failed logins starting the first lockout
+    pub max_failures: u32,
+    // length of the first lockout, doubled by each new failure
+    pub base_seconds: u64,
+    pub max_seconds: u64,
+    // the failures are forgotten after this time without a new one
+    pub forget_seconds: u64,
+}
+
+// Limits of the auth routes, read from the `rate_limit` table of the Rocket config
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub login_ip: BucketConfig,
+    pub login_account: BucketConfig,
+    pub register_ip: BucketConfig,
+    // mails sent on request, password resets and confirmations
+    pub mail_ip: BucketConfig,
+    pub lockout: LockoutConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            login_ip: BucketConfig {
+                capacity: 20,
+                refill_seconds: 6,
+            },
+            login_account: BucketConfig {
+                capacity: 10,
+                refill_seconds: 12,
+            },
+            register_ip: BucketConfig {
+                capacity: 5,
+                refill_seconds: 600,
+            },
+            mail_ip: BucketConfig {
+                capacity: 5,
+                refill_seconds: 600,
+            },
+            lockout: LockoutConfig {
+                max_failures: 5,
+                base_seconds: 30,
+                max_seconds: 3600,
+                forget_seconds: 86400,
+            },
+        }
+    }
+}
+
+// Requests left to a client
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bucket {
+    config: BucketConfig,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    /// Full bucket
+    pub fn new(config: &BucketConfig, now: Instant) -> Self {
+        Bucket {
+            config: *config,
+            tokens: config.capacity as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if now <= self.updated_at {
+            return;
+        }
+
+        let elapsed = (now - self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed / self.config.refill_seconds.max(1) as f64)
+            .min(self.config.capacity as f64);
+        self.updated_at = now;
+    }
+
+    /// Take a request from the bucket, or tell how long to wait for the next one
+    pub fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) * self.config.refill_seconds.max(1) as f64,
+            ))
+        }
+    }
+}
+
+/// Length of the lockout following the given number of failed logins in a row
+///
+/// No lockout under `max_failures`, then `base_seconds` doubled by each new
+/// failure, up to `max_seconds`.
+pub fn lockout_duration(failures: u32, config: &LockoutConfig) -> Option<Duration> {
+    if failures < config.max_failures.max(1) {
+        return None;
+    }
+
+    let doublings = (failures - config.max_failures.max(1)).min(32);
+    let seconds = config
+        .base_seconds
+        .saturating_mul(1 << doublings)
+        .min(config.max_seconds);
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// Whole seconds to wait, rounded up, for the error messages
+pub fn seconds(wait: Duration) -> u64 {
+    let seconds = wait.as_secs() + if wait.subsec_nanos() > 0 { 1 } else { 0 };
+    seconds.max(1)
+}
+
+// Failed logins in a row of an account
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Failures {
+    pub count: u32,
+    pub last_at: Instant,
+    pub locked_until: Option<Instant>,
+}
+
+// Where the buckets and the failed logins are kept. The memory store is enough
+// for a single instance, a shared one is needed once several serve the API
+pub trait RateLimitStore: Send + Sync {
+    // take a request from the bucket of the key, the wait before the next one when it's empty
+    fn take(&self, key: &str, config: &BucketConfig, now: Instant) -> Result<(), Duration>;
+    // time left of the lockout of the key
+    fn locked(&self, key: &str, now: Instant) -> Option<Duration>;
+    // count a failed login of the key, the lockout it starts
+    fn record_failure(&self, key: &str, config: &LockoutConfig, now: Instant) -> Option<Duration>;
+    // forget the failed logins of the key after a successful one
+    fn clear_failures(&self, key: &str);
+}
+
+// Entries of the memory store, the least recently used one is dropped once it is full.
+// The keys are hashed, a long key chosen by a client takes no more room
+struct Lru<V> {
+    capacity: usize,
+    hasher: RandomState,
+    // value and last use of the entries
+    entries: HashMap<u64, (V, u64)>,
+    // hashed keys by last use, oldest first
+    order: BTreeMap<u64, u64>,
+    uses: u64,
+}
+
+impl<V> Lru<V> {
+    fn new(capacity: usize) -> Self {
+        Lru {
+            capacity: capacity.max(1),
+            hasher: RandomState::new(),
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            uses: 0,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&V> {
+        self.entries
+            .get(&self.hasher.hash_one(key))
+            .map(|(value, _)| value)
+    }
+
+    // the entry of the key, created with `init` when missing, becomes the latest used.
+    // Past the capacity the least recently used entry `evictable` accepts is dropped,
+    // none when there is no such entry and the key is not kept
+    fn get_or_insert_with(
+        &mut self,
+        key: &str,
+        init: impl FnOnce() -> V,
+        evictable: impl Fn(&V) -> bool,
+    ) -> Option<&mut V> {
+        let hash = self.hasher.hash_one(key);
+
+        if !self.entries.contains_key(&hash) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .order
+                .iter()
+                .find(|(_, hash)| evictable(&self.entries[*hash].0))
+                .map(|(last_use, hash)| (*last_use, *hash));
+
+            let (last_use, oldest) = oldest?;
+            self.order.remove(&last_use);
+            self.entries.remove(&oldest);
+        }
+
+        self.uses += 1;
+        let (_, last_use) = self
+            .entries
+            .entry(hash)
+            .or_insert_with(|| (init(), self.uses));
+        self.order.remove(last_use);
+        *last_use = self.uses;
+        self.order.insert(self.uses, hash);
+
+        self.entries.get_mut(&hash).map(|(value, _)| value)
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((_, last_use)) = self.entries.remove(&self.hasher.hash_one(key)) {
+            self.order.remove(&last_use);
+        }
+    }
+}
+
+pub struct MemoryStore {
+    buckets: Mutex<Lru<Bucket>>,
+    failures: Mutex<Lru<Failures>>,
+}
+
+impl MemoryStore {
+    // store keeping at most `capacity` buckets and as many accounts with failed logins
+    pub fn with_capacity(capacity: usize) -> Self {
+        MemoryStore {
+            buckets: Mutex::new(Lru::new(capacity)),
+            failures: Mutex::new(Lru::new(capacity)),
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore::with_capacity(MAX_ENTRIES)
+    }
+}
+
+impl RateLimitStore for MemoryStore {
+    fn take(&self, key: &str, config: &BucketConfig, now: Instant) -> Result<(), Duration> {
+        // any bucket can be dropped, the key always gets one
+        match self.buckets.lock().unwrap().get_or_insert_with(
+            key,
+            || Bucket::new(config, now),
+            |_| true,
+        ) {
+            Some(bucket) => bucket.take(now),
+            None => Ok(()),
+        }
+    }
+
+    fn locked(&self, key: &str, now: Instant) -> Option<Duration> {
+        self.failures
+            .lock()
+            .unwrap()
+            .get(key)
+            .and_then(|failures| failures.locked_until)
+            .filter(|locked_until| *locked_until > now)
+            .map(|locked_until| locked_until - now)
+    }
+
+    fn record_failure(&self, key: &str, config: &LockoutConfig, now: Instant) -> Option<Duration> {
+        let mut failures = self.failures.lock().unwrap();
+        let forget = Duration::from_secs(config.forget_seconds);
+
+        // a locked account is never dropped, failing logins on other accounts can't unlock it.
+        // once every kept account is locked the failure is not counted, the account bucket
+        // still limits the attempts
+        let entry = failures.get_or_insert_with(
+            key,
+            || Failures {
+                count: 0,
+                last_at: now,
+                locked_until: None,
+            },
+            |failures| {
+                failures
+                    .locked_until
+                    .is_none_or(|locked_until| locked_until <= now)
+            },
+        )?;
+
+        if now.saturating_duration_since(entry.last_at) >= forget {
+            entry.count = 0;
+        }
+
+        entry.count += 1;
+        entry.last_at = now;
+
+        let lockout = lockout_duration(entry.count, config);
+        entry.locked_until = lockout.map(|lockout| now + lockout);
+
+        lockout
+    }
+
+    fn clear_failures(&self, key: &str) {
+        self.failures.lock().unwrap().remove(key);
+    }
+}
+
+// Rate limits of the API, managed by Rocket
+pub struct RateLimiter {
+    pub store: Box<dyn RateLimitStore>,
+    pub config: RateLimitConfig,
+}
+
+impl RateLimiter {
+    pub fn new(store: Box<dyn RateLimitStore>, config: RateLimitConfig) -> Self {
+        RateLimiter { store, config }
+    }
+}
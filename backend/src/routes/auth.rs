@@ -1,22 +1,39 @@
-use crate::models::token::{NewToken, Token};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::mailer::{self, Mail, Mailer};
+use crate::models::session::{NewSession, Session};
+use crate::models::token::{NewToken, Token, TokenPurpose};
 use crate::models::user::{User, UserInfo};
+use crate::crypto::HashConfig;
+use crate::rate_limit::{self, BucketConfig, RateLimitConfig, RateLimiter};
 use crate::{crypto, ErrorBody, ErrorResponse, MysqlConnection};
 use crate::{
     models::user::NewUser,
     schema::{
-        tokens,
+        sessions, tokens,
         users::{self, email},
     },
 };
 use chrono::{Duration, Local};
 use diesel::{insert_into, prelude::*};
+use diesel::result::DatabaseErrorKind;
 
 use log::{info, warn};
 use rocket::http::Status;
 use rocket::request::{FromRequest, Outcome};
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
-use rocket::{request, Request};
+use rocket::{request, Request, State};
+
+// lifetime of the link confirming the address of a new user
+const VERIFICATION_TOKEN_HOURS: i64 = 24;
+// lifetime of the link choosing a new password
+const RESET_TOKEN_HOURS: i64 = 1;
+// lifetime of an access token, a new one is asked with the refresh token
+const ACCESS_TOKEN_MINUTES: i64 = 15;
+// a session unused for longer is logged out
+const REFRESH_TOKEN_DAYS: i64 = 30;
 
 #[derive(Responder, Debug)]
 pub enum ApiAuthResponse {
@@ -25,9 +42,10 @@ pub enum ApiAuthResponse {
 }
 
 /// Struct used for guarding request
-/// Contains the api token and the user
+/// Contains the api token, its session and the user
 pub struct ApiAuth {
     pub token: String,
+    pub session: Option<i32>,
     pub user: UserInfo,
 }
 
@@ -54,12 +72,18 @@ impl<'r> FromRequest<'r> for ApiAuth {
                 let token_str: String = keys[0][7..].into();
 
                 match connection
-                    .run(move |c| tokens::table.find(&token_str).first::<Token>(c))
+                    .run(move |c| {
+                        // the tokens sent by mail don't log in
+                        tokens::table
+                            .find(&token_str)
+                            .filter(tokens::purpose.eq(TokenPurpose::Session as i32))
+                            .first::<Token>(c)
+                    })
                     .await
                 {
                     Ok(token) => {
                         if token.expiration_date < chrono::Local::now().naive_local() {
-                            // Token is expired => 401 and delete the token
+                            // Token is expired => 401 and delete the token, its session gives a new one
                             connection
                                 .run(move |c| {
                                     diesel::delete(tokens::table.find(token.token)).execute(c)
@@ -77,13 +101,14 @@ impl<'r> FromRequest<'r> for ApiAuth {
                             .run(move |c| {
                                 users::table
                                     .find(token.fk_users)
-                                    .select((users::id, users::name, users::email))
+                                    .select((users::id, users::name, users::email, users::email_verified))
                                     .first::<UserInfo>(c)
                             })
                             .await
                         {
                             Ok(user) => Outcome::Success(ApiAuth {
                                 token: token.token,
+                                session: token.fk_sessions,
                                 user,
                             }),
 
@@ -125,13 +150,152 @@ pub struct LoginRequest {
 pub struct LoginResponse {
     pub token: String,
     pub expiration_date: chrono::NaiveDateTime,
+    pub refresh_token: String,
+    pub refresh_expiration_date: chrono::NaiveDateTime,
+}
+
+// User-Agent header of the request, shown in the list of sessions
+pub struct UserAgent(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for UserAgent {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        Outcome::Success(UserAgent(
+            request
+                .headers()
+                .get_one("User-Agent")
+                .map(|agent| agent.chars().take(255).collect()),
+        ))
+    }
+}
+
+// Wait before the next request of a client over its limit, read by the 429 catcher
+pub struct RetryAfter(pub Option<std::time::Duration>);
+
+// Request guards limiting the requests per IP address
+pub struct LoginRateLimit;
+pub struct RegisterRateLimit;
+pub struct MailRateLimit;
+
+fn ip_rate_limit(
+    request: &Request<'_>,
+    route: &str,
+    bucket: fn(&RateLimitConfig) -> BucketConfig,
+) -> request::Outcome<(), ()> {
+    // the address of the connection, a header like X-Real-IP is chosen by the client.
+    // the requests without address, from the local tests, aren't limited
+    let remote = request.remote().map(|address| address.ip());
+    let (limiter, ip) = match (request.rocket().state::<RateLimiter>(), remote) {
+        (Some(limiter), Some(ip)) => (limiter, ip),
+        _ => return Outcome::Success(()),
+    };
+
+    match limiter.store.take(
+        &format!("{}:ip:{}", route, ip),
+        &bucket(&limiter.config),
+        Instant::now(),
+    ) {
+        Ok(()) => Outcome::Success(()),
+
+        Err(wait) => {
+            warn!(
+                "{} - IP {} is over the rate limit of {} - routes/auth/ip_rate_limit()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                ip,
+                route
+            );
+            request.local_cache(|| RetryAfter(Some(wait)));
+            Outcome::Failure((Status::TooManyRequests, ()))
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LoginRateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        ip_rate_limit(request, "login", |config| config.login_ip).map(|_| LoginRateLimit)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RegisterRateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        ip_rate_limit(request, "register", |config| config.register_ip).map(|_| RegisterRateLimit)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for MailRateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        ip_rate_limit(request, "mail", |config| config.mail_ip).map(|_| MailRateLimit)
+    }
 }
 
 #[post("/auth/login", data = "<data>")]
 pub async fn login(
+    _rate_limit: LoginRateLimit,
     connection: MysqlConnection,
+    limiter: &State<RateLimiter>,
+    hash_config: &State<HashConfig>,
+    user_agent: UserAgent,
     data: Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, (Status, Json<ErrorResponse>)> {
+    // the account is limited too, whatever the addresses trying it
+    let account = format!("login:account:{}", data.email.to_lowercase());
+
+    if let Some(wait) = limiter.store.locked(&account, Instant::now()) {
+        warn!(
+            "{} - Login to locked account {} - routes/auth/login()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            data.email
+        );
+        return Err((
+            Status::TooManyRequests,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 429,
+                    reason: "Too Many Requests".into(),
+                    description: format!(
+                        "Account locked after too many failed logins, retry in {} seconds",
+                        rate_limit::seconds(wait)
+                    ),
+                },
+            }),
+        ));
+    }
+
+    if let Err(wait) = limiter
+        .store
+        .take(&account, &limiter.config.login_account, Instant::now())
+    {
+        warn!(
+            "{} - Account {} is over the login rate limit - routes/auth/login()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            data.email
+        );
+        return Err((
+            Status::TooManyRequests,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 429,
+                    reason: "Too Many Requests".into(),
+                    description: format!(
+                        "Too many requests, retry in {} seconds",
+                        rate_limit::seconds(wait)
+                    ),
+                },
+            }),
+        ));
+    }
+
     // Find user by email
     match connection
         .run({
@@ -143,28 +307,66 @@ pub async fn login(
         Ok(user) => {
             // Check if password is correct
             if crypto::verify_password(&user.password, &data.password) {
-                // Generate token
-                let token_string = crypto::generate_token();
+                limiter.store.clear_failures(&account);
 
-                // Add token to db
-                let token = NewToken {
-                    token: token_string,
+                // the hash follows the parameters of the config once they change
+                if crypto::needs_rehash(&user.password, hash_config) {
+                    match crypto::hash_password(&data.password, hash_config) {
+                        Ok(hash) => {
+                            let user_id = user.id;
+                            match connection
+                                .run(move |c| {
+                                    diesel::update(users::table.find(user_id))
+                                        .set(users::password.eq(hash))
+                                        .execute(c)
+                                })
+                                .await
+                            {
+                                Ok(_) => info!(
+                                    "{} - Password of user {} rehashed - routes/auth/login()",
+                                    Local::now().format("%d/%m/%Y %H:%M"),
+                                    user.id
+                                ),
+                                Err(e) => warn!("{}", e),
+                            }
+                        }
+                        Err(e) => warn!("{}", e),
+                    }
+                }
+
+                // Open a session on the device
+                let session = NewSession {
                     fk_users: user.id,
-                    expiration_date: (chrono::offset::Local::now() + Duration::hours(3))
+                    refresh_token: crypto::generate_token(),
+                    user_agent: user_agent.0,
+                    expiration_date: (Local::now() + Duration::days(REFRESH_TOKEN_DAYS))
                         .naive_local(),
                 };
 
                 match connection
                     .run({
-                        let token = token.clone();
-                        move |c| insert_into(tokens::dsl::tokens).values(token).execute(c)
+                        let session = session.clone();
+                        move |c| {
+                            c.transaction(|c| {
+                                insert_into(sessions::table).values(&session).execute(c)?;
+
+                                let session_id = sessions::table
+                                    .filter(sessions::refresh_token.eq(&session.refresh_token))
+                                    .select(sessions::id)
+                                    .first::<i32>(c)?;
+
+                                issue_access_token(c, session.fk_users, session_id)
+                            })
+                        }
                     })
                     .await
                 {
-                    Ok(_) => {
+                    Ok(token) => {
                         let reponse = LoginResponse {
                             token: token.token,
                             expiration_date: token.expiration_date,
+                            refresh_token: session.refresh_token,
+                            refresh_expiration_date: session.expiration_date,
                         };
                         info!(
                             "{} - User {} logged in",
@@ -194,6 +396,7 @@ pub async fn login(
                     Local::now().format("%d/%m/%Y %H:%M"),
                     user.id
                 );
+                lock_after_failure(limiter, &account);
                 return Err((
                     Status::Unauthorized,
                     Json(ErrorResponse {
@@ -213,6 +416,9 @@ pub async fn login(
                 Local::now().format("%d/%m/%Y %H:%M"),
                 data.email
             );
+            // unknown emails are checked and locked the same way, to not tell them apart
+            crypto::verify_dummy_password(&data.password, hash_config);
+            lock_after_failure(limiter, &account);
             return Err((
                 Status::Unauthorized,
                 Json(ErrorResponse {
@@ -227,6 +433,22 @@ pub async fn login(
     }
 }
 
+// count a failed login of the account, locking it after too many
+fn lock_after_failure(limiter: &RateLimiter, account: &str) {
+    let lockout = limiter
+        .store
+        .record_failure(account, &limiter.config.lockout, Instant::now());
+
+    if let Some(lockout) = lockout {
+        warn!(
+            "{} - {} locked for {} seconds - routes/auth/login()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            account,
+            lockout.as_secs()
+        );
+    }
+}
+
 // Logout user
 #[derive(Deserialize, Clone)]
 pub struct LogoutRequest {
@@ -238,9 +460,12 @@ pub async fn logout(
     connection: MysqlConnection,
     auth: ApiAuth,
 ) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
-    // Delete token from db
+    // Delete token from db, with the session giving new ones
     match connection
-        .run(move |c| diesel::delete(tokens::table.find(auth.token)).execute(c))
+        .run(move |c| match auth.session {
+            Some(session_id) => diesel::delete(sessions::table.find(session_id)).execute(c),
+            None => diesel::delete(tokens::table.find(auth.token)).execute(c),
+        })
         .await
     {
         Ok(_) => {
@@ -266,11 +491,144 @@ pub async fn logout(
     }
 }
 
+#[derive(Deserialize, Clone, Serialize, Debug)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+// new access token for a session, its refresh token is replaced
+//
+// A refresh token works once, a stolen one stops working as soon as the device
+// of the user refreshes its session.
+#[post("/auth/refresh", data = "<data>")]
+pub async fn refresh(
+    connection: MysqlConnection,
+    user_agent: UserAgent,
+    data: Json<RefreshRequest>,
+) -> Result<Json<LoginResponse>, (Status, Json<ErrorResponse>)> {
+    let refresh_token = crypto::generate_token();
+    let refresh_expiration_date = (Local::now() + Duration::days(REFRESH_TOKEN_DAYS)).naive_local();
+
+    match connection
+        .run({
+            let refresh_token = refresh_token.clone();
+            move |c| {
+                c.transaction(|c| {
+                    // a second refresh with the same token waits for the first one and finds it replaced
+                    let session = match sessions::table
+                        .filter(sessions::refresh_token.eq(&data.refresh_token))
+                        .for_update()
+                        .first::<Session>(c)
+                        .optional()?
+                    {
+                        Some(session) => session,
+                        None => return Ok(None),
+                    };
+
+                    if session.expiration_date < Local::now().naive_local() {
+                        diesel::delete(sessions::table.find(session.id)).execute(c)?;
+                        return Ok(None);
+                    }
+
+                    diesel::update(sessions::table.find(session.id))
+                        .set((
+                            sessions::refresh_token.eq(refresh_token),
+                            sessions::user_agent.eq(user_agent.0.or(session.user_agent)),
+                            sessions::last_used_at.eq(Local::now().naive_local()),
+                            sessions::expiration_date.eq(refresh_expiration_date),
+                        ))
+                        .execute(c)?;
+
+                    // the previous access tokens of the session stop working
+                    diesel::delete(tokens::table.filter(tokens::fk_sessions.eq(session.id)))
+                        .execute(c)?;
+
+                    issue_access_token(c, session.fk_users, session.id).map(Some)
+                })
+            }
+        })
+        .await
+    {
+        Ok(Some(token)) => Ok(Json(LoginResponse {
+            token: token.token,
+            expiration_date: token.expiration_date,
+            refresh_token,
+            refresh_expiration_date,
+        })),
+
+        Ok(None) => Err((
+            Status::Unauthorized,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 401,
+                    reason: "Unauthorized".into(),
+                    description: "Invalid or expired refresh token".into(),
+                },
+            }),
+        )),
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// short lived token of a session
+fn issue_access_token(
+    c: &mut diesel::MysqlConnection,
+    user_id: i32,
+    session_id: i32,
+) -> QueryResult<NewToken> {
+    let token = NewToken {
+        token: crypto::generate_token(),
+        fk_users: user_id,
+        expiration_date: (Local::now() + Duration::minutes(ACCESS_TOKEN_MINUTES)).naive_local(),
+        purpose: TokenPurpose::Session as i32,
+        fk_sessions: Some(session_id),
+    };
+
+    insert_into(tokens::table).values(&token).execute(c)?;
+
+    Ok(token)
+}
+
 #[post("/auth/register", data = "<data>")]
 pub async fn register(
+    _rate_limit: RegisterRateLimit,
     connection: MysqlConnection,
+    mailer: &State<Arc<dyn Mailer>>,
+    hash_config: &State<HashConfig>,
     data: Json<NewUser>,
 ) -> Result<Json<UserInfo>, (Status, Json<ErrorResponse>)> {
+    // the rules broken are sent back for the UI to explain them
+    let violations = crypto::password_violations(&data.password);
+    if !violations.is_empty() {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Weak Password".into(),
+                    description: violations
+                        .iter()
+                        .map(|rule| rule.code())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                },
+            }),
+        ));
+    }
+
     // Check if email is already used
     match connection
         .run({
@@ -294,7 +652,7 @@ pub async fn register(
 
         Err(_e) => {
             // Hash password and check if return error
-            let hashed_password = match crypto::hash_password(&data.password.as_str()) {
+            let hashed_password = match crypto::hash_password(&data.password.as_str(), hash_config) {
                 Ok(hashed_password) => hashed_password,
                 Err(_e) => {
                     return Err((
@@ -327,7 +685,7 @@ pub async fn register(
 
                             let user = users::table
                                 .order(users::id.desc())
-                                .select((users::id, users::name, users::email))
+                                .select((users::id, users::name, users::email, users::email_verified))
                                 .first::<UserInfo>(c)
                                 .map(Json)?;
 
@@ -343,9 +701,27 @@ pub async fn register(
                         Local::now().format("%d/%m/%Y %H:%M"),
                         user.id
                     );
+
+                    // the account is usable without the confirmation, a new mail can be asked for
+                    send_verification_mail(&connection, mailer.inner().clone(), user.0.clone()).await;
+
                     return Ok(user);
                 }
 
+                // registered with the same address since the check
+                Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                    return Err((
+                        Status::Conflict,
+                        Json(ErrorResponse {
+                            error: ErrorBody {
+                                code: 409,
+                                reason: "Conflict".into(),
+                                description: "Email already used".to_string(),
+                            },
+                        }),
+                    ))
+                }
+
                 Err(_e) => {
                     return Err((
                         Status::InternalServerError,
@@ -362,3 +738,418 @@ pub async fn register(
         }
     }
 }
+
+#[derive(Deserialize, Clone, Serialize, Debug)]
+pub struct TokenRequest {
+    pub token: String,
+}
+
+// confirm the address of the user with the token of the mail sent when registering
+#[post("/auth/verify_email", data = "<data>")]
+pub async fn verify_email(
+    connection: MysqlConnection,
+    data: Json<TokenRequest>,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                let token = match tokens::table
+                    .find(&data.token)
+                    .filter(tokens::purpose.eq(TokenPurpose::EmailVerification as i32))
+                    .for_update()
+                    .first::<Token>(c)
+                    .optional()?
+                {
+                    Some(token) => token,
+                    None => return Ok(None),
+                };
+
+                diesel::delete(tokens::table.find(&token.token)).execute(c)?;
+                if token.expiration_date < Local::now().naive_local() {
+                    return Ok(None);
+                }
+
+                diesel::update(users::table.find(token.fk_users))
+                    .set(users::email_verified.eq(true))
+                    .execute(c)?;
+
+                diesel::result::QueryResult::Ok(Some(token.fk_users))
+            })
+        })
+        .await
+    {
+        Ok(Some(user_id)) => {
+            info!(
+                "{} - User {} verified their email - routes/auth/verify_email()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user_id
+            );
+            Ok(Json("Email verified".into()))
+        }
+
+        Ok(None) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Invalid or expired token".into(),
+                },
+            }),
+        )),
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// send a new confirmation mail, the previous links stop working
+#[post("/auth/verification_email")]
+pub async fn resend_verification_email(
+    connection: MysqlConnection,
+    mailer: &State<Arc<dyn Mailer>>,
+    auth: ApiAuth,
+    _rate_limit: MailRateLimit,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+    let is_verified = match connection
+        .run(move |c| {
+            users::table
+                .find(user_id)
+                .select(users::email_verified)
+                .first::<bool>(c)
+        })
+        .await
+    {
+        Ok(is_verified) => is_verified,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    if is_verified {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Email already verified".into(),
+                },
+            }),
+        ));
+    }
+
+    if !send_verification_mail(&connection, mailer.inner().clone(), auth.user).await {
+        return Err((
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 500,
+                    reason: "Internal Server Error".into(),
+                    description: "The mail could not be sent".into(),
+                },
+            }),
+        ));
+    }
+
+    Ok(Json("Verification email sent".into()))
+}
+
+#[derive(Deserialize, Clone, Serialize, Debug)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+// send a link to choose a new password
+//
+// The answer is the same whether the address is known or not, it does not
+// tell who has an account.
+#[post("/auth/forgot_password", data = "<data>")]
+pub async fn forgot_password(
+    connection: MysqlConnection,
+    mailer: &State<Arc<dyn Mailer>>,
+    data: Json<ForgotPasswordRequest>,
+    _rate_limit: MailRateLimit,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    let token_string = crypto::generate_token();
+    let address = data.email.clone();
+
+    match connection
+        .run({
+            let token_string = token_string.clone();
+            move |c| {
+                c.transaction(|c| {
+                    let user = match users::table
+                        .filter(email.eq(&data.email))
+                        .first::<User>(c)
+                        .optional()?
+                    {
+                        Some(user) => user,
+                        None => return Ok(None),
+                    };
+
+                    // only the latest link works
+                    diesel::delete(
+                        tokens::table
+                            .filter(tokens::fk_users.eq(user.id))
+                            .filter(tokens::purpose.eq(TokenPurpose::PasswordReset as i32)),
+                    )
+                    .execute(c)?;
+
+                    insert_into(tokens::table)
+                        .values(NewToken {
+                            token: token_string,
+                            fk_users: user.id,
+                            expiration_date: (Local::now() + Duration::hours(RESET_TOKEN_HOURS))
+                                .naive_local(),
+                            purpose: TokenPurpose::PasswordReset as i32,
+                            fk_sessions: None,
+                        })
+                        .execute(c)?;
+
+                    diesel::result::QueryResult::Ok(Some(user))
+                })
+            }
+        })
+        .await
+    {
+        Ok(Some(user)) => {
+            info!(
+                "{} - User {} asked to reset their password - routes/auth/forgot_password()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user.id
+            );
+
+            let mail = mailer::reset_mail(&user.email, &user.name, &mailer::app_url(), &token_string);
+            // not awaited, the time of the answer would tell the address is known
+            rocket::tokio::spawn(send_mail(mailer.inner().clone(), mail));
+        }
+
+        Ok(None) => {
+            warn!(
+                "{} - Password reset asked for {} but no user has this email - routes/auth/forgot_password()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                address
+            );
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    }
+
+    Ok(Json("If an account uses this email, a reset link was sent".into()))
+}
+
+#[derive(Deserialize, Clone, Serialize, Debug)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub password: String,
+}
+
+// choose a new password with the token of the reset mail, the user is logged out everywhere
+#[post("/auth/reset_password", data = "<data>")]
+pub async fn reset_password(
+    connection: MysqlConnection,
+    hash_config: &State<HashConfig>,
+    data: Json<ResetPasswordRequest>,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    // the rules broken are sent back for the UI to explain them
+    let violations = crypto::password_violations(&data.password);
+    if !violations.is_empty() {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Weak Password".into(),
+                    description: violations
+                        .iter()
+                        .map(|rule| rule.code())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                },
+            }),
+        ));
+    }
+
+    let hashed_password = match crypto::hash_password(&data.password, hash_config) {
+        Ok(hashed_password) => hashed_password,
+        Err(_e) => {
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error has occured".to_string(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                // a second use of the token waits for the first one and finds it deleted
+                let token = match tokens::table
+                    .find(&data.token)
+                    .filter(tokens::purpose.eq(TokenPurpose::PasswordReset as i32))
+                    .for_update()
+                    .first::<Token>(c)
+                    .optional()?
+                {
+                    Some(token) => token,
+                    None => return Ok(None),
+                };
+
+                if token.expiration_date < Local::now().naive_local() {
+                    diesel::delete(tokens::table.find(&token.token)).execute(c)?;
+                    return Ok(None);
+                }
+
+                // the link was received by mail, the address is confirmed as well
+                diesel::update(users::table.find(token.fk_users))
+                    .set((
+                        users::password.eq(hashed_password),
+                        users::email_verified.eq(true),
+                    ))
+                    .execute(c)?;
+
+                diesel::delete(sessions::table.filter(sessions::fk_users.eq(token.fk_users)))
+                    .execute(c)?;
+                diesel::delete(tokens::table.filter(tokens::fk_users.eq(token.fk_users)))
+                    .execute(c)?;
+
+                diesel::result::QueryResult::Ok(Some(token.fk_users))
+            })
+        })
+        .await
+    {
+        Ok(Some(user_id)) => {
+            info!(
+                "{} - User {} reset their password - routes/auth/reset_password()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user_id
+            );
+            Ok(Json("Password reset".into()))
+        }
+
+        Ok(None) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Invalid or expired token".into(),
+                },
+            }),
+        )),
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// create a confirmation token replacing the previous ones and mail its link
+pub async fn send_verification_mail(
+    connection: &MysqlConnection,
+    mailer: Arc<dyn Mailer>,
+    user: UserInfo,
+) -> bool {
+    let token = NewToken {
+        token: crypto::generate_token(),
+        fk_users: user.id,
+        expiration_date: (Local::now() + Duration::hours(VERIFICATION_TOKEN_HOURS)).naive_local(),
+        purpose: TokenPurpose::EmailVerification as i32,
+        fk_sessions: None,
+    };
+    let mail = mailer::verification_mail(&user.email, &user.name, &mailer::app_url(), &token.token);
+
+    if let Err(e) = connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::delete(
+                    tokens::table
+                        .filter(tokens::fk_users.eq(user.id))
+                        .filter(tokens::purpose.eq(TokenPurpose::EmailVerification as i32)),
+                )
+                .execute(c)?;
+
+                insert_into(tokens::table).values(token).execute(c)
+            })
+        })
+        .await
+    {
+        warn!("{}", e);
+        return false;
+    }
+
+    send_mail(mailer, mail).await
+}
+
+// SMTP blocks, the mail is sent out of the async runtime
+async fn send_mail(mailer: Arc<dyn Mailer>, mail: Mail) -> bool {
+    let to = mail.to.clone();
+
+    match rocket::tokio::task::spawn_blocking(move || mailer.send(&mail)).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            warn!(
+                "{} - Mail to {} not sent: {} - routes/auth/send_mail()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                to,
+                e
+            );
+            false
+        }
+        Err(e) => {
+            warn!("{}", e);
+            false
+        }
+    }
+}
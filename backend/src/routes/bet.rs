@@ -1,16 +1,22 @@
-use crate::models::bet::{Bet, NewBet, PathBet, BetWithUser};
+use crate::betting::{self, Payout, Stake};
+use crate::events::{EventKind, Events};
+use crate::models::bet::{Bet, BetPreview, BetWithUser, GameOdds, NewBet, PathBet};
 use crate::models::game::Game;
 use crate::models::nut::Nut;
+use crate::models::nut_transaction::TransactionKind;
 use crate::models::subscription::Subscription;
 use crate::models::tournament::Tournament;
 use crate::routes::auth::ApiAuth;
+use crate::routes::game::advance_teams;
+use crate::routes::market::{refund_score_bets, settle_champion_bets, settle_score_bets};
 use crate::schema::{bets, games, nuts, subscriptions, teams, tournaments, users};
-use crate::{ErrorBody, ErrorResponse, MysqlConnection, EmptyResponse};
+use crate::{ErrorBody, ErrorResponse, MysqlConnection};
 use chrono::Local;
 use diesel::prelude::*;
 use log::warn;
 use rocket::http::Status;
 use rocket::serde::json::Json;
+use rocket::State;
 use serde::{Deserialize, Serialize};
 
 // Get all bets of a game
@@ -157,142 +163,195 @@ pub async fn get_user_game_bet(
     };
 }
 
-// calculate the gain of all users betting on a game
-pub async fn calculate_gain(
+// close a game and pay the bets on it in a single transaction, with the next games of the
+// bracket and the champion bets for an elimination game
+//
+// `score` replaces the score of the game, for a series decided by its sets.
+pub async fn settle_game(
     connection: &MysqlConnection,
     game_id: i32,
-) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
-    // get the game
-    let game = match connection
-        .run(move |c| games::table.find(game_id).first::<Game>(c))
-        .await
-    {
-        Ok(game) => game,
-        Err(_e) => {
-            return Err((
-                Status::NotFound,
-                Json(ErrorResponse {
-                    error: ErrorBody {
-                        code: 404,
-                        reason: "Not Found".into(),
-                        description: "Game not found".into(),
-                    },
-                }),
-            ))
-        }
-    };
-
-    // get all bets linked to the game
-    let bets = match connection
+    score: Option<(i32, i32)>,
+) -> Result<Game, (Status, Json<ErrorResponse>)> {
+    match connection
         .run(move |c| {
-            bets::table
-                .filter(bets::fk_games.eq(game_id))
-                .get_results::<Bet>(c)
-        })
-        .await
-    {
-        Ok(bets) => bets,
-        Err(_e) => {
-            return Err((
-                Status::NotFound,
-                Json(ErrorResponse {
-                    error: ErrorBody {
-                        code: 404,
-                        reason: "Not Found".into(),
-                        description: "Bets not found".into(),
-                    },
-                }),
-            ))
-        }
-    };
+            c.transaction(|c| {
+                // a second close waits for the first one and sees the game settled
+                let game = games::table.find(game_id).for_update().first::<Game>(c)?;
+
+                if game.has_gained_nut {
+                    return Err(BetError::AlreadySettled);
+                }
+
+                // locked before the nuts, in the same order as the champion bets
+                if game.phase >= 1 {
+                    tournaments::table
+                        .find(game.fk_tournaments)
+                        .for_update()
+                        .first::<Tournament>(c)?;
+                }
 
-    // check if there is a winner
-    if game.score1 == game.score2 {
-        // if there is no winner, give back the nut to the user
-        for bet in bets.clone() {
-            let nut = match connection
-                .run(move |c| nuts::table.filter(nuts::fk_users.eq(bet.fk_users)).filter(nuts::fk_tournaments.eq(game.fk_tournaments)).first::<Nut>(c))
-                .await
-            {
-                Ok(nut) => nut,
-                Err(_e) => {
-                    return Err((
-                        Status::NotFound,
-                        Json(ErrorResponse {
-                            error: ErrorBody {
-                                code: 404,
-                                reason: "Not Found".into(),
-                                description: "Nut not found".into(),
-                            },
-                        }),
+                let (score1, score2) = score.unwrap_or((game.score1, game.score2));
+                diesel::update(games::table.find(game_id))
+                    .set((
+                        games::score1.eq(score1),
+                        games::score2.eq(score2),
+                        games::status.eq(2),
+                        games::has_gained_nut.eq(true),
                     ))
+                    .execute(c)?;
+
+                let game = games::table.find(game_id).first::<Game>(c)?;
+                let bets = bets::table
+                    .filter(bets::fk_games.eq(game_id))
+                    .order(bets::id)
+                    .load::<Bet>(c)?;
+
+                // a draw decided by a tiebreak has a winner
+                let winner = game.winner();
+                let payouts = betting::settle(winner, &stakes(&bets));
+
+                // kept on the bets for the leaderboard, a later change of the score pays nothing more
+                for (bet, payout) in bets.iter().zip(&payouts) {
+                    diesel::update(bets::table.find(bet.id))
+                        .set(bets::payout.eq(payout.nuts))
+                        .execute(c)?;
                 }
-            };
 
-            let new_stock = nut.stock + bet.nb_nut;
-            set_stock(&connection, nut.id, new_stock).await?;
-        }
+                // the stakes are given back when nobody bet on the winner
+                let kind = match winner {
+                    Some(winner) if bets.iter().any(|b| b.fk_teams == winner) => TransactionKind::Payout,
+                    _ => TransactionKind::Refund,
+                };
 
-        return Ok(Json(EmptyResponse()));
-    }
+                let mut credits: Vec<(Payout, TransactionKind)> =
+                    payouts.into_iter().map(|p| (p, kind)).collect();
+                credits.extend(settle_score_bets(c, &game)?);
 
-    // find the winner
-    let winner = if game.score1 > game.score2 {
-        game.fk_team1
-    } else {
-        game.fk_team2
-    };
+                if game.phase >= 1 {
+                    // the next games of the bracket are created before the nuts are locked
+                    advance_teams(c, &game)?;
+                }
 
-    // if the user bet on the winning team
-    let mut winner_total_bet = 0;
-    let mut total_bet = 0;
-    let mut looser_total_bet = 0;
+                pay(c, game.fk_tournaments, Some(game_id), credits)?;
 
-    for bet in bets.clone() {
-        if bet.fk_teams == winner {
-            winner_total_bet += bet.nb_nut;
-        }
+                if game.phase >= 1 {
+                    // the champion is known once the last final is closed
+                    settle_champion_bets(c, game.fk_tournaments)?;
+                }
 
-        total_bet += bet.nb_nut;
+                Ok(game)
+            })
+        })
+        .await
+    {
+        Ok(game) => Ok(game),
+
+        Err(BetError::AlreadySettled) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The game is already closed".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Game not found".into(),
+                },
+            }),
+        )),
+
+        Err(_e) => Err((
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 500,
+                    reason: "Internal Server Error".into(),
+                    description: "An error occured".into(),
+                },
+            }),
+        )),
     }
+}
 
-    looser_total_bet = total_bet - winner_total_bet;
-
-    // calculate the gain for the winning team
-    for bet in bets {
-        if bet.fk_teams == winner {
-            let nut = match connection
-                .run(move |c| nuts::table.filter(nuts::fk_users.eq(bet.fk_users)).filter(nuts::fk_tournaments.eq(game.fk_tournaments)).first::<Nut>(c))
-                .await
-            {
-                Ok(nut) => nut,
-                Err(_e) => {
-                    return Err((
-                        Status::NotFound,
-                        Json(ErrorResponse {
-                            error: ErrorBody {
-                                code: 404,
-                                reason: "Not Found".into(),
-                                description: "Nuts not found".into(),
-                            },
-                        }),
-                    ))
-                }
-            };
+// give the payouts of a settlement back to the players, to call in the transaction settling the bets
+pub fn pay(
+    c: &mut diesel::MysqlConnection,
+    tournament_id: i32,
+    game_id: Option<i32>,
+    mut credits: Vec<(Payout, TransactionKind)>,
+) -> QueryResult<()> {
+    // nuts are locked in the same order by every settlement, two games closed together can't deadlock
+    credits.sort_by_key(|(p, _)| p.user_id);
+    for (payout, kind) in credits.iter().filter(|(p, _)| p.nuts > 0) {
+        let nut = nuts::table
+            .filter(nuts::fk_users.eq(payout.user_id))
+            .filter(nuts::fk_tournaments.eq(tournament_id))
+            .for_update()
+            .first::<Nut>(c)?;
+
+        nut.record(c, *kind, payout.nuts, game_id, None)?;
+    }
 
-            let participation = bet.nb_nut as f64 / winner_total_bet as f64;
+    Ok(())
+}
 
-            let new_stock = nut.stock
-                + bet.nb_nut
-                + (participation * looser_total_bet as f64).round() as i32;
-            set_stock(&connection, nut.id, new_stock).await?;
-        }
+// give back the stakes on the games about to be deleted, to call in the transaction deleting them,
+// the cascade would drop the bets and leave their stakes in the ledger
+pub fn refund_games(c: &mut diesel::MysqlConnection, game_ids: &[i32]) -> QueryResult<()> {
+    // a settled game was already paid
+    let games = games::table
+        .filter(games::id.eq_any(game_ids))
+        .filter(games::has_gained_nut.eq(false))
+        .order(games::id)
+        .load::<Game>(c)?;
+
+    for game in games {
+        let bets = bets::table
+            .filter(bets::fk_games.eq(game.id))
+            .load::<Bet>(c)?;
+
+        diesel::delete(bets::table.filter(bets::fk_games.eq(game.id))).execute(c)?;
+
+        let mut credits: Vec<(Payout, TransactionKind)> = bets
+            .iter()
+            .map(|b| {
+                (
+                    Payout {
+                        user_id: b.fk_users,
+                        nuts: b.nb_nut,
+                    },
+                    TransactionKind::Refund,
+                )
+            })
+            .collect();
+        credits.extend(refund_score_bets(c, game.id)?);
+
+        pay(c, game.fk_tournaments, Some(game.id), credits)?;
     }
 
-    return Ok(Json(EmptyResponse()));
+    Ok(())
 }
 
-// get the gain of the user for the game
+pub fn stakes(bets: &[Bet]) -> Vec<Stake> {
+    bets.iter()
+        .map(|b| Stake {
+            user_id: b.fk_users,
+            team_id: b.fk_teams,
+            nuts: b.nb_nut,
+        })
+        .collect()
+}
+
+// get the gain of the user for the game: what the bet won, the stake excluded,
+// minus the stake when it lost and 0 when it was given back
 #[get("/game/<id_game>/bet/result")]
 pub async fn get_user_game_bet_result(
     connection: MysqlConnection,
@@ -333,9 +392,6 @@ pub async fn get_user_game_bet_result(
         ));
     }
 
-    // get the user nut
-    // let nut = get_user_nut(&connection, auth.user.id, id_game).await?;
-
     // get the user bet
     let bet = match connection
         .run(move |c| {
@@ -343,51 +399,23 @@ pub async fn get_user_game_bet_result(
                 .filter(bets::fk_users.eq(auth.user.id))
                 .filter(bets::fk_games.eq(id_game))
                 .first::<Bet>(c)
+                .optional()
         })
         .await
     {
-        Ok(bet) => bet,
-        Err(_e) => {
+        Ok(Some(bet)) => bet,
+        Ok(None) => {
             return Err((
-                Status::InternalServerError,
+                Status::NotFound,
                 Json(ErrorResponse {
                     error: ErrorBody {
-                        code: 500,
-                        reason: "Internal Server Error".into(),
-                        description: "An error occured".into(),
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Bet not found".into(),
                     },
                 }),
             ))
         }
-    };
-
-    // if egality
-    if game.score1 == game.score2 {
-        return Ok(Json(0));
-    }
-
-    // calculate the winning team
-    let winner = if game.score1 > game.score2 {
-        game.fk_team1
-    } else {
-        game.fk_team2
-    };
-
-    // if the user bet on the loosing team
-    if bet.fk_teams != winner {
-        return Ok(Json(-bet.nb_nut));
-    }
-
-    // get all bets linked to the game
-    let bets = match connection
-        .run(move |c| {
-            bets::table
-                .filter(bets::fk_games.eq(id_game))
-                .get_results::<Bet>(c)
-        })
-        .await
-    {
-        Ok(bets) => bets,
         Err(_e) => {
             return Err((
                 Status::InternalServerError,
@@ -402,122 +430,107 @@ pub async fn get_user_game_bet_result(
         }
     };
 
-    // if the user bet on the winning team
-    let mut winner_total_bet = 0;
-    let mut total_bet = 0;
-
-    for bet in bets {
-        if bet.fk_teams == winner {
-            winner_total_bet += bet.nb_nut;
-        }
-
-        total_bet += bet.nb_nut;
-    }
+    // what settle_game paid, a bet without payout was given back
+    let gain = bet.payout.map_or(0, |payout| payout - bet.nb_nut);
 
-    return Ok(Json(
-        (bet.nb_nut as f64 * total_bet as f64 / winner_total_bet as f64).round() as i32
-            + bet.nb_nut,
-    ));
+    return Ok(Json(gain));
 }
 
-// change the stock of a player
-async fn set_stock(
-    connection: &MysqlConnection,
+// split of the pot of a game and what a bet of `nuts` on `team` would pay
+#[get("/game/<id>/odds?<team>&<nuts>")]
+pub async fn get_game_odds(
+    connection: MysqlConnection,
+    auth: ApiAuth,
     id: i32,
-    stock: i32,
-) -> Result<Nut, (Status, Json<ErrorResponse>)> {
-    match connection
-        .run(move |c| {
-            c.transaction(|c| {
-                diesel::update(nuts::table.find(id))
-                    .set(nuts::stock.eq(stock))
-                    .execute(c)?;
-
-                let nut = nuts::table.find(id).first::<Nut>(c)?;
-
-                diesel::result::QueryResult::Ok(nut)
-            })
-        })
+    team: Option<i32>,
+    nuts: Option<i32>,
+) -> Result<Json<GameOdds>, (Status, Json<ErrorResponse>)> {
+    let game = match connection
+        .run(move |c| games::table.find(id).first::<Game>(c))
         .await
     {
-        Ok(nut) => {
-            return Ok(nut);
-        }
-
+        Ok(game) => game,
         Err(_e) => {
             return Err((
-                Status::InternalServerError,
+                Status::NotFound,
                 Json(ErrorResponse {
                     error: ErrorBody {
-                        code: 500,
-                        reason: "Internal Server Error".into(),
-                        description: "An error occured".into(),
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Game not found".into(),
                     },
                 }),
             ))
         }
-    }
-}
+    };
 
-// get the nut of a user for a game
-async fn get_user_nut(
-    connection: &MysqlConnection,
-    id_user: i32,
-    id_game: i32,
-) -> Result<Nut, (Status, Json<ErrorResponse>)> {
-    match connection
+    let tournament_id = game.fk_tournaments;
+    let is_subscriber = match connection
         .run(move |c| {
-            games::table
-                .inner_join(teams::table.on(teams::id.eq(games::fk_team1)))
-                .inner_join(tournaments::table.on(tournaments::id.eq(teams::fk_tournaments)))
-                .inner_join(nuts::table.on(nuts::fk_tournaments.eq(tournaments::id)))
-                .filter(nuts::fk_users.eq(id_user))
-                .filter(games::id.eq(id_game))
-                .select((nuts::id, nuts::fk_users, nuts::fk_tournaments, nuts::stock))
-                .first::<(i32, i32, i32, i32)>(c)
+            subscriptions::table
+                .filter(subscriptions::fk_tournaments.eq(tournament_id))
+                .filter(subscriptions::fk_users.eq(auth.user.id))
+                .first::<Subscription>(c)
         })
         .await
     {
-        Ok(nut_data) => {
-            let nut = Nut {
-                id: nut_data.0,
-                fk_users: nut_data.1,
-                fk_tournaments: nut_data.2,
-                stock: nut_data.3,
-            };
-
-            return Ok(nut);
-        }
-        Err(e) => {
-            println!("{}", e);
+        Ok(_) => true,
+        Err(_) => false,
+    };
+    if !is_subscriber {
+        warn!(
+            "{} - User {} tried to get odds of tournament {} even if he is not a subscriber - routes/bet/get_game_odds()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            auth.user.id,
+            tournament_id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
 
-            return Err((
-                Status::InternalServerError,
-                Json(ErrorResponse {
-                    error: ErrorBody {
-                        code: 500,
-                        reason: "Internal Server Error".into(),
-                        description: "An error occured".into(),
-                    },
-                }),
-            ))
+    let preview_stake = match (team, nuts) {
+        (Some(team), Some(nuts)) => {
+            if (team != game.fk_team1 && team != game.fk_team2) || nuts < 1 {
+                return Err((
+                    Status::BadRequest,
+                    Json(ErrorResponse {
+                        error: ErrorBody {
+                            code: 400,
+                            reason: "Bad Request".into(),
+                            description: "The preview needs a team of the game and at least one nut".into(),
+                        },
+                    }),
+                ));
+            }
+
+            Some(Stake {
+                user_id: auth.user.id,
+                team_id: team,
+                nuts,
+            })
         }
-    }
-}
+        _ => None,
+    };
 
-// check if the game is open
-async fn is_game_open(
-    connection: &MysqlConnection,
-    id: i32,
-) -> Result<bool, (Status, Json<ErrorResponse>)> {
-    match connection
-        .run(move |c| games::table.find(id).select(games::status).first::<i32>(c))
+    // same order as settle_game, leftover nuts go to the same bets
+    let bets = match connection
+        .run(move |c| {
+            bets::table
+                .filter(bets::fk_games.eq(id))
+                .order(bets::id)
+                .load::<Bet>(c)
+        })
         .await
     {
-        Ok(status) => {
-            return Ok(status == 0);
-        }
-
+        Ok(bets) => bets,
         Err(_e) => {
             return Err((
                 Status::InternalServerError,
@@ -530,9 +543,69 @@ async fn is_game_open(
                 }),
             ))
         }
+    };
+
+    let stakes = stakes(&bets);
+    let teams = if game.is_bye() {
+        vec![game.fk_team1]
+    } else {
+        vec![game.fk_team1, game.fk_team2]
+    };
+
+    let preview = preview_stake.map(|stake| {
+        let payout = betting::preview(&stakes, stake);
+
+        BetPreview {
+            fk_teams: stake.team_id,
+            nb_nut: stake.nuts,
+            payout: payout.nuts,
+            gain: payout.gain(&stake),
+        }
+    });
+
+    Ok(Json(GameOdds {
+        fk_games: id,
+        pot: stakes.iter().map(|s| s.nuts).sum(),
+        teams: betting::odds(&teams, &stakes),
+        preview,
+    }))
+}
+
+// why a bet transaction was rolled back
+pub enum BetError {
+    GameNotOpen,
+    // the team doesn't play the game
+    NotInGame,
+    MarketClosed,
+    NotEnoughNut,
+    AlreadyBet,
+    AlreadySettled,
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for BetError {
+    fn from(e: diesel::result::Error) -> Self {
+        BetError::Database(e)
     }
 }
 
+// lock the game and the nuts of the user until the end of the transaction, always in this order
+pub fn lock_bet(
+    c: &mut diesel::MysqlConnection,
+    game_id: i32,
+    user_id: i32,
+) -> Result<(Game, Nut), BetError> {
+    let game = games::table.find(game_id).for_update().first::<Game>(c)?;
+
+    let nut = nuts::table
+        .filter(nuts::fk_users.eq(user_id))
+        .filter(nuts::fk_tournaments.eq(game.fk_tournaments))
+        .for_update()
+        .first::<Nut>(c)?;
+
+    Ok((game, nut))
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BetData {
     pub team_id: usize,
@@ -543,82 +616,149 @@ pub struct BetData {
 #[post("/game/<id>/bet", data = "<data>")]
 pub async fn create_bet(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
     data: Json<BetData>,
 ) -> Result<Json<Bet>, (Status, Json<ErrorResponse>)> {
-    // get the tournoix id
-    let nut = get_user_nut(&connection, auth.user.id, id).await?;
+    let user_id = auth.user.id;
+    let nb_nut = i32::try_from(data.nut).unwrap_or(i32::MAX);
+    let team_id = data.team_id as i32;
 
-    // if the game is not open
-    if !is_game_open(&connection, id).await? {
-        return Err((
-            Status::NotFound,
-            Json(ErrorResponse {
-                error: ErrorBody {
-                    code: 404,
-                    reason: "Not Found".into(),
-                    description: "Game is not open".into(),
-                },
-            }),
-        ));
-    }
-
-    // if the user don't have enough nut
-    if data.nut > nut.stock as u32 {
+    if nb_nut < 1 {
         return Err((
             Status::BadRequest,
             Json(ErrorResponse {
                 error: ErrorBody {
                     code: 400,
                     reason: "Bad Request".into(),
-                    description: "Not enough nut".into(),
+                    description: "A bet needs at least one nut".into(),
                 },
             }),
         ));
     }
 
-    // remove the nut from the user
-    let new_stock = nut.stock - data.nut as i32;
-    let nut = set_stock(&connection, nut.id, new_stock).await?;
+    // the stock is checked and spent while the nut is locked, parallel bets can't overspend it
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                let (game, nut) = lock_bet(c, id, user_id)?;
 
-    // create the bet
-    let new_bet = NewBet {
-        fk_users: auth.user.id,
-        fk_games: id,
-        nb_nut: data.nut as i32,
-        fk_teams: data.team_id as i32,
-    };
+                if game.status != 0 {
+                    return Err(BetError::GameNotOpen);
+                }
 
-    // add the bet to the database
-    match connection
-        .run(move |c| diesel::insert_into(bets::table).values(new_bet).execute(c))
-        .await
-    {
-        Ok(_) => {
-            // get the bet
-            let bet = match connection
-                .run(move |c| bets::table.order(bets::id.desc()).first::<Bet>(c))
-                .await
-            {
-                Ok(bet) => bet,
-                Err(_e) => {
-                    return Err((
-                        Status::InternalServerError,
-                        Json(ErrorResponse {
-                            error: ErrorBody {
-                                code: 500,
-                                reason: "Internal Server Error".into(),
-                                description: "An error occured".into(),
-                            },
-                        }),
-                    ))
+                // a stake on another team could never win, its nuts would only feed the pot
+                if team_id != game.fk_team1 && team_id != game.fk_team2 {
+                    return Err(BetError::NotInGame);
+                }
+
+                let has_bet = bets::table
+                    .filter(bets::fk_users.eq(user_id))
+                    .filter(bets::fk_games.eq(id))
+                    .first::<Bet>(c)
+                    .optional()?
+                    .is_some();
+                if has_bet {
+                    return Err(BetError::AlreadyBet);
                 }
-            };
 
+                if nb_nut > nut.stock {
+                    return Err(BetError::NotEnoughNut);
+                }
+
+                // remove the nut from the user
+                nut.record(c, TransactionKind::Stake, -nb_nut, Some(id), None)?;
+
+                diesel::insert_into(bets::table)
+                    .values(NewBet {
+                        fk_users: user_id,
+                        fk_games: id,
+                        nb_nut,
+                        fk_teams: team_id,
+                    })
+                    .execute(c)?;
+
+                let bet = bets::table
+                    .filter(bets::fk_users.eq(user_id))
+                    .filter(bets::fk_games.eq(id))
+                    .first::<Bet>(c)?;
+
+                Ok((bet, game.fk_tournaments))
+            })
+        })
+        .await
+    {
+        Ok((bet, tournament_id)) => {
+            events.send(tournament_id, EventKind::BetsChanged, Some(id));
             return Ok(Json(bet));
         }
 
+        Err(BetError::GameNotOpen) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Game is not open".into(),
+                    },
+                }),
+            ))
+        }
+
+        Err(BetError::NotInGame) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "The team doesn't play this game".into(),
+                    },
+                }),
+            ))
+        }
+
+        Err(BetError::NotEnoughNut) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "Not enough nut".into(),
+                    },
+                }),
+            ))
+        }
+
+        Err(BetError::AlreadyBet) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "A bet already exists for this game".into(),
+                    },
+                }),
+            ))
+        }
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Game or nut not found".into(),
+                    },
+                }),
+            ))
+        }
+
         Err(_e) => {
             return Err((
                 Status::InternalServerError,
@@ -638,135 +778,122 @@ pub async fn create_bet(
 #[patch("/game/<id>/bet", data = "<data>")]
 pub async fn update_bet(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
     data: Json<BetData>,
 ) -> Result<Json<Bet>, (Status, Json<ErrorResponse>)> {
-    // get the tournoix id
-    let nut = get_user_nut(&connection, auth.user.id, id).await?;
+    let user_id = auth.user.id;
+    let nb_nut = i32::try_from(data.nut).unwrap_or(i32::MAX);
+    let team_id = data.team_id as i32;
 
-    // get the betted nut
-    let bet = match connection
+    match connection
         .run(move |c| {
-            bets::table
-                .filter(bets::fk_games.eq(id))
-                .filter(bets::fk_users.eq(auth.user.id))
-                .first::<Bet>(c)
+            c.transaction(|c| {
+                let (game, nut) = lock_bet(c, id, user_id)?;
+
+                let bet = bets::table
+                    .filter(bets::fk_games.eq(id))
+                    .filter(bets::fk_users.eq(user_id))
+                    .for_update()
+                    .first::<Bet>(c)?;
+
+                if game.status != 0 {
+                    return Err(BetError::GameNotOpen);
+                }
+
+                if nb_nut > 0 && team_id != game.fk_team1 && team_id != game.fk_team2 {
+                    return Err(BetError::NotInGame);
+                }
+
+                // the difference between the old and the new bet, negative when nuts are given back
+                let diff = nb_nut - bet.nb_nut;
+                if diff > nut.stock {
+                    return Err(BetError::NotEnoughNut);
+                }
+
+                if diff > 0 {
+                    nut.record(c, TransactionKind::Stake, -diff, Some(id), None)?;
+                } else if diff < 0 {
+                    nut.record(c, TransactionKind::Refund, -diff, Some(id), None)?;
+                }
+
+                // if the new bet nut number is 0, remove the bet
+                if nb_nut == 0 {
+                    diesel::delete(bets::table.find(bet.id)).execute(c)?;
+
+                    return Ok((bet, game.fk_tournaments));
+                }
+
+                diesel::update(bets::table.find(bet.id))
+                    .set(PathBet {
+                        nb_nut: Some(nb_nut),
+                        fk_teams: Some(team_id),
+                    })
+                    .execute(c)?;
+
+                let bet = bets::table.find(bet.id).first::<Bet>(c)?;
+
+                Ok((bet, game.fk_tournaments))
+            })
         })
         .await
     {
-        Ok(bet) => bet,
-        Err(_e) => {
+        Ok((bet, tournament_id)) => {
+            events.send(tournament_id, EventKind::BetsChanged, Some(id));
+            return Ok(Json(bet));
+        }
+
+        Err(BetError::GameNotOpen) => {
             return Err((
-                Status::InternalServerError,
+                Status::BadRequest,
                 Json(ErrorResponse {
                     error: ErrorBody {
-                        code: 500,
-                        reason: "Internal Server Error".into(),
-                        description: "An error occured".into(),
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "The game is not open".into(),
                     },
                 }),
             ))
         }
-    };
-
-    // if the game is not open
-    if !is_game_open(&connection, id).await? {
-        return Err((
-            Status::BadRequest,
-            Json(ErrorResponse {
-                error: ErrorBody {
-                    code: 400,
-                    reason: "Bad Request".into(),
-                    description: "The game is not open".into(),
-                },
-            }),
-        ));
-    }
-
-    // calculate the difference between the old and the new bet
-    let diff = data.nut - bet.nb_nut as u32;
-
-    // if the user don't have enough nut
-    if diff > nut.stock as u32 {
-        return Err((
-            Status::BadRequest,
-            Json(ErrorResponse {
-                error: ErrorBody {
-                    code: 400,
-                    reason: "Bad Request".into(),
-                    description: "Not enough nut".into(),
-                },
-            }),
-        ));
-    }
 
-    // remove or give back the nut from the user
-    let new_stock = nut.stock - diff as i32;
-    let _ = set_stock(&connection, nut.id, new_stock).await?;
-
-    // if the new bet nut number is 0, remove the bet
-    if data.nut == 0 {
-        match connection
-            .run(move |c| diesel::delete(bets::table.find(bet.id)).execute(c))
-            .await
-        {
-            Ok(_) => {
-                return Ok(Json(bet));
-            }
-
-            Err(_e) => {
-                return Err((
-                    Status::InternalServerError,
-                    Json(ErrorResponse {
-                        error: ErrorBody {
-                            code: 500,
-                            reason: "Internal Server Error".into(),
-                            description: "An error occured".into(),
-                        },
-                    }),
-                ))
-            }
-        };
-    }
-
-    // create the bet
-    let updated_bet = PathBet {
-        nb_nut: Some(data.nut as i32),
-        fk_teams: Some(data.team_id as i32),
-    };
+        Err(BetError::NotInGame) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "The team doesn't play this game".into(),
+                    },
+                }),
+            ))
+        }
 
-    // add the bet to the database
-    match connection
-        .run(move |c| {
-            diesel::update(bets::table.find(bet.id))
-                .set(updated_bet)
-                .execute(c)
-        })
-        .await
-    {
-        Ok(_) => {
-            // get the bet
-            let bet = match connection
-                .run(move |c| bets::table.find(bet.id).first::<Bet>(c))
-                .await
-            {
-                Ok(bet) => bet,
-                Err(_e) => {
-                    return Err((
-                        Status::InternalServerError,
-                        Json(ErrorResponse {
-                            error: ErrorBody {
-                                code: 500,
-                                reason: "Internal Server Error".into(),
-                                description: "An error occured".into(),
-                            },
-                        }),
-                    ))
-                }
-            };
+        Err(BetError::NotEnoughNut) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "Not enough nut".into(),
+                    },
+                }),
+            ))
+        }
 
-            return Ok(Json(bet));
+        Err(BetError::Database(diesel::result::Error::NotFound)) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Bet not found".into(),
+                    },
+                }),
+            ))
         }
 
         Err(_e) => {
@@ -788,36 +915,69 @@ pub async fn update_bet(
 #[delete("/game/<id>/bet")]
 pub async fn delete_bet(
     connection: MysqlConnection,
+    events: &State<Events>,
     auth: ApiAuth,
     id: i32,
 ) -> Result<Json<Bet>, (Status, Json<ErrorResponse>)> {
-    //get the nut of the player
-    let nut = get_user_nut(&connection, auth.user.id, id).await?;
+    let user_id = auth.user.id;
 
     match connection
         .run(move |c| {
             c.transaction(|c| {
-                // get the bet
+                let (game, nut) = lock_bet(c, id, user_id)?;
+
                 let bet = bets::table
-                    .filter(bets::fk_users.eq(auth.user.id))
+                    .filter(bets::fk_users.eq(user_id))
                     .filter(bets::fk_games.eq(id))
+                    .for_update()
                     .first::<Bet>(c)?;
 
-                // remove the bet
+                // the bet is settled once the game is over
+                if game.status != 0 {
+                    return Err(BetError::GameNotOpen);
+                }
+
                 diesel::delete(bets::table.find(bet.id)).execute(c)?;
 
-                diesel::result::QueryResult::Ok(bet)
+                // place the nut back in the stock
+                nut.record(c, TransactionKind::Refund, bet.nb_nut, Some(id), None)?;
+
+                Ok((bet, game.fk_tournaments))
             })
         })
         .await
     {
-        Ok(bet) => {
-            // place the nut back in the stock
-            let new_stock = nut.stock + bet.nb_nut;
-            set_stock(&connection, nut.id, new_stock).await?;
-
+        Ok((bet, tournament_id)) => {
+            events.send(tournament_id, EventKind::BetsChanged, Some(id));
             return Ok(Json(bet));
         }
+
+        Err(BetError::GameNotOpen) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "The game is not open".into(),
+                    },
+                }),
+            ))
+        }
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Bet not found".into(),
+                    },
+                }),
+            ))
+        }
+
         Err(_e) => {
             return Err((
                 Status::InternalServerError,
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::calendar::{self, Event};
+use crate::models::game::Game;
+use crate::models::team::Team;
+use crate::models::tournament::Tournament;
+use crate::schema::{games, teams, tournaments};
+use crate::{ErrorBody, ErrorResponse, MysqlConnection};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use log::warn;
+use rocket::http::{ContentType, Status};
+use rocket::serde::json::Json;
+
+// length of the games when the timetable was generated without one
+const DEFAULT_MATCH_DURATION: i32 = 30;
+
+// calendar apps can't send a login token, the join code of the tournament is asked instead
+#[get("/tournoix/<id>/calendar.ics?<code>")]
+pub async fn get_tournament_calendar(
+    connection: MysqlConnection,
+    id: i32,
+    code: String,
+) -> Result<(ContentType, String), (Status, Json<ErrorResponse>)> {
+    let tournament = load_tournament_with_code(&connection, id, code).await?;
+
+    tournament_calendar(&connection, tournament, None).await
+}
+
+// games of a single team
+#[get("/teams/<id>/calendar.ics?<code>")]
+pub async fn get_team_calendar(
+    connection: MysqlConnection,
+    id: i32,
+    code: String,
+) -> Result<(ContentType, String), (Status, Json<ErrorResponse>)> {
+    let team = match connection
+        .run(move |c| teams::table.find(id).first::<Team>(c))
+        .await
+    {
+        Ok(team) => team,
+        Err(_) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Team not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    let tournament = load_tournament_with_code(&connection, team.fk_tournaments, code).await?;
+
+    tournament_calendar(&connection, tournament, Some(team)).await
+}
+
+// the join code gives access to the tournament without a login token
+pub async fn load_tournament_with_code(
+    connection: &MysqlConnection,
+    id: i32,
+    code: String,
+) -> Result<Tournament, (Status, Json<ErrorResponse>)> {
+    let tournament = match connection
+        .run(move |c| tournaments::table.find(id).first::<Tournament>(c))
+        .await
+    {
+        Ok(tournament) => tournament,
+        Err(_) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Tournament not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    if tournament.code != code {
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    Ok(tournament)
+}
+
+async fn tournament_calendar(
+    connection: &MysqlConnection,
+    tournament: Tournament,
+    team: Option<Team>,
+) -> Result<(ContentType, String), (Status, Json<ErrorResponse>)> {
+    let tournament_id = tournament.id;
+    let team_id = team.as_ref().map(|t| t.id);
+
+    let (games, teams) = match connection
+        .run(move |c| {
+            let mut query = games::table
+                .filter(games::fk_tournaments.eq(tournament_id))
+                .filter(games::scheduled_at.is_not_null())
+                .into_boxed();
+
+            if let Some(team_id) = team_id {
+                query = query.filter(games::fk_team1.eq(team_id).or(games::fk_team2.eq(team_id)));
+            }
+
+            let games = query.order(games::scheduled_at).load::<Game>(c)?;
+            let teams = teams::table
+                .filter(teams::fk_tournaments.eq(tournament_id))
+                .load::<Team>(c)?;
+
+            diesel::result::QueryResult::Ok((games, teams))
+        })
+        .await
+    {
+        Ok(data) => data,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    let names: HashMap<i32, String> = teams.into_iter().map(|t| (t.id, t.name)).collect();
+    let name = |id: i32| names.get(&id).cloned().unwrap_or_default();
+    let duration =
+        Duration::minutes(tournament.match_duration.unwrap_or(DEFAULT_MATCH_DURATION) as i64);
+
+    let events: Vec<Event> = games
+        .iter()
+        .filter(|g| !g.is_bye())
+        .filter_map(|g| {
+            let start = g.scheduled_at?;
+            let description = match g.status {
+                0 => "En attente".to_string(),
+                1 => format!("En cours : {} - {}", g.score1, g.score2),
+                _ => match g.winner() {
+                    Some(winner) => format!(
+                        "Terminé : {} - {}, victoire de {}",
+                        g.score1,
+                        g.score2,
+                        name(winner)
+                    ),
+                    None => format!("Terminé : {} - {}", g.score1, g.score2),
+                },
+            };
+            let location = match (g.court, &tournament.location) {
+                (Some(court), Some(location)) => Some(format!("Terrain {}, {}", court, location)),
+                (Some(court), None) => Some(format!("Terrain {}", court)),
+                (None, location) => location.clone(),
+            };
+
+            Some(Event {
+                uid: format!("game-{}@tournoix", g.id),
+                start,
+                end: start + duration,
+                summary: format!(
+                    "{} - {} | {}",
+                    name(g.fk_team1),
+                    name(g.fk_team2),
+                    tournament.name
+                ),
+                location,
+                description,
+                sequence: calendar::sequence(g.updated_at),
+            })
+        })
+        .collect();
+
+    let calendar_name = match team {
+        Some(team) => format!("{} - {}", tournament.name, team.name),
+        None => tournament.name.clone(),
+    };
+
+    Ok((
+        ContentType::Calendar,
+        calendar::render(&calendar_name, Utc::now().naive_utc(), &events),
+    ))
+}
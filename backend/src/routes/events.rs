@@ -0,0 +1,42 @@
+use crate::events::Events;
+use crate::{ErrorResponse, MysqlConnection};
+use rocket::http::Status;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{Shutdown, State};
+
+use super::calendar::load_tournament_with_code;
+
+// live changes of a tournament, EventSource can't send a login token so the join code is asked instead
+#[get("/tournoix/<id>/events?<code>")]
+pub async fn get_tournament_events(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    id: i32,
+    code: String,
+    mut end: Shutdown,
+) -> Result<EventStream![], (Status, Json<ErrorResponse>)> {
+    load_tournament_with_code(&connection, id, code).await?;
+
+    let mut receiver = events.subscribe();
+
+    Ok(EventStream! {
+        loop {
+            let event = select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => break,
+                    // the spectator missed some events, the next one refreshes the page anyway
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = &mut end => break,
+            };
+
+            if event.tournament_id == id {
+                yield Event::json(&event);
+            }
+        }
+    })
+}
@@ -1,5 +1,7 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 
+use crate::bracket::{Bracket, Feed, Layout, Outcome};
+use crate::events::{EventKind, Events};
 use crate::models::game::Game;
 use crate::models::game::*;
 use crate::models::subscription::Subscription;
@@ -7,17 +9,24 @@ use crate::models::team::Team;
 use crate::models::tournament::Tournament;
 use crate::routes::auth::ApiAuth;
 use crate::schema::{games, subscriptions, teams, tournaments};
+use crate::seeding::{self, Qualifier};
+use crate::series;
+use crate::standings::{self, GameResult, Standing};
+use crate::swiss::{self, SwissResult};
 use crate::{EmptyResponse, ErrorBody, ErrorResponse, MysqlConnection};
 use chrono::Local;
 use diesel::prelude::*;
-use log::warn;
+use log::{info, warn};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
+use rocket::State;
 
-use super::bet::calculate_gain;
+use super::bet::{refund_games, settle_game};
+use super::series::{game_best_of, load_game_sets};
+use super::schedule::schedule_games;
 use super::tournoix::is_owner;
 
 // get all match from a tournament
@@ -91,6 +100,12 @@ pub async fn get_tournoix_game(
                     games::place,
                     games::status,
                     games::has_gained_nut,
+                    games::bracket,
+                    games::round,
+                    games::tiebreak,
+                    games::tiebreak_winner,
+                    games::scheduled_at,
+                    games::court,
                     teams1.field(teams::group),
                 ))
                 .filter(games::fk_tournaments.eq(id))
@@ -135,6 +150,12 @@ pub async fn get_team_game(
                     games::place,
                     games::status,
                     games::has_gained_nut,
+                    games::bracket,
+                    games::round,
+                    games::tiebreak,
+                    games::tiebreak_winner,
+                    games::scheduled_at,
+                    games::court,
                     teams::group,
                 ))
                 .filter(games::fk_team1.eq(id).or(games::fk_team2.eq(id)))
@@ -172,6 +193,12 @@ pub async fn get_game(
                     games::place,
                     games::status,
                     games::has_gained_nut,
+                    games::bracket,
+                    games::round,
+                    games::tiebreak,
+                    games::tiebreak_winner,
+                    games::scheduled_at,
+                    games::court,
                     teams1.field(teams::group),
                 ))
                 .filter(games::id.eq(id))
@@ -190,6 +217,7 @@ pub async fn get_game(
 #[post("/games/<id>/close")]
 pub async fn close_game(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
@@ -213,100 +241,77 @@ pub async fn close_game(
         ));
     }
 
-    let game = connection
+    let mut game = match connection
         .run(move |c| games::table.find(id).first::<Game>(c))
         .await
-        .ok();
+    {
+        Ok(game) => game,
+        Err(_) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Game not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
 
-    update_game_fn(
-        &connection,
-        Json(PatchGame {
-            has_gained_nut: Some(false),
-            fk_team1: None,
-            fk_team2: None,
-            score1: None,
-            score2: None,
-            place: None,
-            status: Some(2),
-        }),
-        id,
-    )
-    .await?;
-
-    if let Some(game) = game {
-        if game.phase >= 1 {
-            // Elim game, need to move team forward
-
-            let other_game_place = game.place + {
-                if game.place % 2 == 0 {
-                    1
-                } else {
-                    -1
-                }
-            };
-            let other_game = connection
-                .run(move |c| {
-                    games::table
-                        .filter(games::phase.eq(game.phase))
-                        .filter(games::place.eq(other_game_place))
-                        .first::<Game>(c)
-                })
-                .await
-                .ok();
-
-            if let Some(other_game) = other_game {
-                if other_game.status == 2 {
-                    let team1 = if game.place % 2 == 0 {
-                        game.winner()
-                    } else {
-                        other_game.winner()
-                    };
-                    let team2 = if game.place % 2 == 0 {
-                        other_game.winner()
-                    } else {
-                        game.winner()
-                    };
-
-                    let new_game = NewGame {
-                        fk_tournaments: game.fk_tournaments,
-                        fk_team1: team1,
-                        fk_team2: team2,
-                        score1: 0,
-                        score2: 0,
-                        place: (game.place as f32 / 2.0).floor() as i32,
-                        phase: game.phase + 1,
-                        status: 0,
-                    };
-
-                    match connection
-                        .run(move |c| {
-                            diesel::insert_into(games::table)
-                                .values(new_game)
-                                .execute(c)
-                        })
-                        .await
-                    {
-                        Ok(_) => {}
-                        Err(e) => {
-                            println!("{}", e);
-                            return Err((
-                                Status::InternalServerError,
-                                Json(ErrorResponse {
-                                    error: ErrorBody {
-                                        code: 500,
-                                        reason: "Internel Server Error".into(),
-                                        description: "An error occured".into(),
-                                    },
-                                }),
-                            ));
-                        }
-                    }
-                }
-            }
+    let tournament_id = game.fk_tournaments;
+
+    // the score of a series is the number of sets won by each team
+    let best_of = game_best_of(&connection, &game).await;
+    let mut series_score = None;
+    if best_of > 1 && !game.is_bye() {
+        let sets = load_game_sets(&connection, id).await;
+
+        if !series::is_over(best_of, &sets) {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "series_not_over".into(),
+                        description: format!("No team has won the best of {} yet", best_of),
+                    },
+                }),
+            ));
         }
+
+        let (score1, score2) = series::series_score(&sets);
+        game.score1 = score1;
+        game.score2 = score2;
+        series_score = Some((score1, score2));
     }
 
-    return calculate_gain(&connection, id).await;
+    // somebody has to go through in an elimination game, a draw says how
+    let is_draw = game.score1 == game.score2 && !game.is_bye();
+    if game.phase >= 1
+        && (game.winner().is_none() || (is_draw && game.tiebreak == Tiebreak::None as i32))
+    {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "draw".into(),
+                    description: "A draw in an elimination game needs a tiebreak result".into(),
+                },
+            }),
+        ));
+    }
+
+    // the game is closed with its payouts, the next games of the bracket and the champion bets,
+    // or not at all
+    settle_game(&connection, id, series_score).await?;
+
+    events.send(tournament_id, EventKind::GameUpdated, Some(id));
+    events.send(tournament_id, EventKind::GameClosed, Some(id));
+
+    return Ok(Json(EmptyResponse()));
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -317,6 +322,9 @@ pub struct AddGame {
     pub place: i32,
 }
 
+// biggest elimination phase, 7 rounds
+const MAX_ELIM_TEAMS: usize = 128;
+
 // enum for the phase of the tournament
 #[derive(Serialize, Deserialize, Clone)]
 pub enum Phase {
@@ -327,6 +335,7 @@ pub enum Phase {
 #[post("/tournoix/<id>/qualif")]
 pub async fn create_games(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<Vec<Game>>, (Status, Json<ErrorResponse>)> {
@@ -345,6 +354,30 @@ pub async fn create_games(
         ));
     }
 
+    // swiss rounds are generated one by one
+    let is_swiss = connection
+        .run(move |c| {
+            tournaments::table
+                .find(id)
+                .select(tournaments::is_swiss)
+                .first::<bool>(c)
+        })
+        .await
+        .unwrap_or(false);
+
+    if is_swiss {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Swiss tournaments generate their games round by round".into(),
+                },
+            }),
+        ));
+    }
+
     if !delete_tournament_games_qualif(&connection, id).await {
         return Err((
             Status::BadRequest,
@@ -422,6 +455,8 @@ pub async fn create_games(
                     place: nb_game_added,
                     phase: Phase::Qualification as i32,
                     status: 0,
+                    bracket: Bracket::Upper as i32,
+                    round: 0,
                 };
 
                 // position of the game in the group
@@ -471,12 +506,15 @@ pub async fn create_games(
         }
     }
 
+    events.send(id, EventKind::GameUpdated, None);
+
     Ok(Json(games_added))
 }
 
 #[post("/tournoix/<id>/elim")]
 pub async fn create_games_elim(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
@@ -495,7 +533,36 @@ pub async fn create_games_elim(
         ));
     }
 
-    if !delete_tournament_games_elim(&connection, id).await {
+    // the existing bracket is only replaced by create_first_round, once the seeds are valid
+    let started = match connection
+        .run(move |c| {
+            games::table
+                .filter(games::fk_tournaments.eq(id))
+                .filter(games::phase.gt(0))
+                .filter(games::status.ne(0))
+                .filter(games::fk_team1.ne(games::fk_team2))
+                .count()
+                .get_result::<i64>(c)
+        })
+        .await
+    {
+        Ok(started) => started,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    if started > 0 {
         return Err((
             Status::BadRequest,
             Json(ErrorResponse {
@@ -527,131 +594,57 @@ pub async fn create_games_elim(
         }
     };
 
-    if tournament.is_qualif {
+    let seeds: Vec<Qualifier> = if tournament.is_qualif {
         // Tournament has qualification phase
         // Need to check if all games in qualif are over
-        // And then get all the winners
-
-        let games: Option<Vec<GameWithGroup>> = connection
-            .run(move |c| {
-                games::table
-                    .inner_join(teams::table.on(games::fk_team1.eq(teams::id)))
-                    .filter(games::fk_tournaments.eq(id))
-                    .select((
-                        games::id,
-                        games::fk_tournaments,
-                        games::fk_team1,
-                        games::fk_team2,
-                        games::score1,
-                        games::score2,
-                        games::phase,
-                        games::place,
-                        games::status,
-                        games::has_gained_nut,
-                        teams::group,
-                    ))
-                    .load::<GameWithGroup>(c)
-            })
-            .await
-            .ok();
-
-        if let Some(games) = games {
-            // Check if all games are over
-            if games.len() == 0 || games.iter().any(|g| g.status != 2) {
-                return Err((
-                    Status::BadRequest,
-                    Json(ErrorResponse {
-                        error: ErrorBody {
-                            code: 400,
-                            reason: "Bad Request".into(),
-                            description: "All games in qualification need to be finisged".into(),
-                        },
-                    }),
-                ));
-            }
-
-            // group team by group
-            let mut groups: BTreeMap<i32, Vec<GameWithGroup>> = BTreeMap::new();
-            for game in games {
-                if groups.contains_key(&game.group) {
-                    groups.get_mut(&game.group).unwrap().push(game);
-                } else {
-                    groups.insert(game.group, vec![game]);
-                }
-            }
-
-            let mut teams_score: HashMap<i32, HashMap<i32, i32>> = HashMap::new();
-            for (id_group, games) in groups {
-                for game in games {
-                    let winner = game.winner();
-                    *teams_score
-                        .entry(id_group)
-                        .or_insert(HashMap::new())
-                        .entry(winner)
-                        .or_insert(0) += 1;
-                }
-            }
-
-            let mut winners = teams_score
-                .iter()
-                .map(|(_, teams)| teams.iter().max().unwrap().0)
-                .collect::<Vec<&i32>>();
-
-            // Shuffle teams
-            winners.shuffle(&mut thread_rng());
-            let mut games: Vec<NewGame> = vec![];
-            let mut nb_game_added = 0;
-
-            for i in (0..winners.len()).step_by(2) {
-                games.push(NewGame {
-                    fk_tournaments: id,
-                    fk_team1: *winners[i],
-                    fk_team2: *winners[i + 1],
-                    score1: 0,
-                    score2: 0,
-                    place: nb_game_added,
-                    phase: 1,
-                    status: 0,
-                });
-
-                nb_game_added += 1;
-            }
+        // And then get the qualified teams of each group
+        let (_, teams, games) = load_qualification(&connection, id).await?;
 
-            match connection
-                .run(move |c| {
-                    diesel::insert_into(games::table)
-                        .values(games.clone())
-                        .execute(c)
-                })
-                .await
-            {
-                Ok(_) => return Ok(Json(EmptyResponse())),
-
-                Err(_e) => {
-                    return Err((
-                        Status::InternalServerError,
-                        Json(ErrorResponse {
-                            error: ErrorBody {
-                                code: 500,
-                                reason: "Internel Server Error".into(),
-                                description: "An error occured".into(),
-                            },
-                        }),
-                    ))
-                }
-            };
-        } else {
+        // Check if all games are over
+        if games.len() == 0 || games.iter().any(|g| g.status != 2) {
             return Err((
-                Status::InternalServerError,
+                Status::BadRequest,
                 Json(ErrorResponse {
                     error: ErrorBody {
-                        code: 500,
-                        reason: "Internel Server Error".into(),
-                        description: "An error occured".into(),
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "All games in qualification need to be finisged".into(),
                     },
                 }),
             ));
         }
+
+        if tournament.is_swiss {
+            // a swiss tournament is a single pool
+            let team_ids: Vec<i32> = teams.iter().map(|t| t.id).collect();
+            swiss::standings(&team_ids, &swiss_results(&games), &tournament.points())
+                .iter()
+                .take(tournament.qualified_per_group.max(0) as usize)
+                .enumerate()
+                .map(|(index, standing)| Qualifier {
+                    team_id: standing.team_id,
+                    group: 0,
+                    rank: index + 1,
+                })
+                .collect()
+        } else {
+            // teams in group 0 are not in any group
+            let groups: BTreeMap<i32, Vec<Standing>> = teams
+                .iter()
+                .map(|t| t.group)
+                .filter(|g| *g != 0)
+                .collect::<BTreeSet<i32>>()
+                .into_iter()
+                .map(|group| (group, group_standings(&tournament, &teams, &games, group)))
+                .collect();
+
+            seeding::qualifiers(
+                &groups,
+                tournament.qualified_per_group.max(0) as usize,
+                tournament.wildcards.max(0) as usize,
+                tournament.id as u64,
+            )
+        }
     } else {
         // No qualif, just get all teams
         let mut teams: Vec<Team> = match connection
@@ -677,70 +670,47 @@ pub async fn create_games_elim(
             }
         };
 
-        // Check if teams are a power of 2
-        // We can't generate elimination phase wihtout a power of 2
-        if teams.len() & (teams.len() - 1) != 0 {
-            return Err((
-                Status::BadRequest,
-                Json(ErrorResponse {
-                    error: ErrorBody {
-                        code: 400,
-                        reason: "Bad Request".into(),
-                        description: "The number of teams must be a power of 2".into(),
-                    },
-                }),
-            ));
-        }
-
-        // Shuffle teams
+        // Shuffle teams, without qualification there is no seed
         teams.shuffle(&mut thread_rng());
-        let mut games: Vec<NewGame> = vec![];
-        let mut nb_game_added = 0;
-
-        for i in (0..teams.len()).step_by(2) {
-            games.push(NewGame {
-                fk_tournaments: id,
-                fk_team1: teams[i].id,
-                fk_team2: teams[i + 1].id,
-                score1: 0,
-                score2: 0,
-                place: nb_game_added,
-                phase: 1,
-                status: 0,
-            });
-
-            nb_game_added += 1;
-        }
 
-        match connection
-            .run(move |c| {
-                diesel::insert_into(games::table)
-                    .values(games.clone())
-                    .execute(c)
+        teams
+            .iter()
+            .map(|team| Qualifier {
+                team_id: team.id,
+                group: 0,
+                rank: 1,
             })
-            .await
-        {
-            Ok(_) => return Ok(Json(EmptyResponse())),
+            .collect()
+    };
 
-            Err(_e) => {
-                return Err((
-                    Status::InternalServerError,
-                    Json(ErrorResponse {
-                        error: ErrorBody {
-                            code: 500,
-                            reason: "Internel Server Error".into(),
-                            description: "An error occured".into(),
-                        },
-                    }),
-                ))
-            }
-        };
+    if seeds.len() < 2 || seeds.len() > MAX_ELIM_TEAMS {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: format!(
+                        "The elimination phase needs between 2 and {} teams",
+                        MAX_ELIM_TEAMS
+                    ),
+                },
+            }),
+        ));
     }
+
+    // the best seeds get a bye when the number of teams is not a power of 2
+    create_first_round(&connection, id, seeding::first_round(&seeds)).await?;
+
+    events.send(id, EventKind::GameUpdated, None);
+
+    Ok(Json(EmptyResponse()))
 }
 
 #[delete("/tournoix/<id>/qualif")]
 pub async fn remove_all_games(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
@@ -760,12 +730,15 @@ pub async fn remove_all_games(
 
     delete_tournament_games_qualif(&connection, id).await;
 
+    events.send(id, EventKind::GameUpdated, None);
+
     Ok(Json(EmptyResponse()))
 }
 
 #[delete("/tournoix/<id>/elim")]
 pub async fn remove_all_games_elim(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
@@ -785,74 +758,575 @@ pub async fn remove_all_games_elim(
 
     delete_tournament_games_elim(&connection, id).await;
 
+    events.send(id, EventKind::GameUpdated, None);
+
     Ok(Json(EmptyResponse()))
 }
 
-async fn update_game_fn(
-    connection: &MysqlConnection,
-    data: Json<PatchGame>,
-    id: i32,
-) -> Result<Json<Game>, (Status, Json<ErrorResponse>)> {
-    let game = data.0;
+// Seeding of the elimination phase, best seed first
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EliminationSeeding {
+    pub seeds: Vec<i32>,
+}
 
-    // cannot update the game it the betting is closed
-    /*
-    if game.status == Some(false) {
-        return Err((Status::BadRequest, "Betting is closed".to_string()));
+#[get("/tournoix/<id>/elim/seeding")]
+pub async fn get_elim_seeding(
+    connection: MysqlConnection,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<EliminationSeeding>, (Status, Json<ErrorResponse>)> {
+    if !is_owner(&connection, id, &auth).await {
+        warn!("{} - User {} tried to get the seeding of tournament {} - routes/game/get_elim_seeding()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
     }
-    */
-
-    match connection
-        .run(move |c| {
-            c.transaction(|c| {
-                diesel::update(games::table.find(id))
-                    .set(game.clone())
-                    .execute(c)?;
-
-                let game = games::table
-                    .order(games::id.desc())
-                    .first::<Game>(c)
-                    .map(Json)?;
-
-                diesel::result::QueryResult::Ok(game)
-            })
-        })
-        .await
-    {
-        Ok(game) => {
-            return Ok(game);
-        }
 
-        Err(e) => {
-            warn!("{}", e);
+    let games = load_elim_games(&connection, id).await?;
 
-            return Err((
-                Status::InternalServerError,
-                Json(ErrorResponse {
-                    error: ErrorBody {
-                        code: 500,
-                        reason: "Internel Server Error".into(),
-                        description: "An error occured".into(),
-                    },
-                }),
-            ));
-        }
-    }
+    Ok(Json(EliminationSeeding {
+        seeds: seeding::seeds_from_first_round(&first_round_pairs(&games)),
+    }))
 }
 
-#[patch("/games/<id>", data = "<data>")]
-pub async fn update_game(
+// replace the first round of the elimination phase with the given seeding
+#[put("/tournoix/<id>/elim/seeding", data = "<data>")]
+pub async fn update_elim_seeding(
     connection: MysqlConnection,
-    data: Json<PatchGame>,
+    events: &State<Events>,
+    data: Json<EliminationSeeding>,
     id: i32,
-) -> Result<Json<Game>, (Status, Json<ErrorResponse>)> {
-    return update_game_fn(&connection, data, id).await;
-}
-
-// block the action of betting on a game
+    auth: ApiAuth,
+) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
+    if !is_owner(&connection, id, &auth).await {
+        warn!("{} - User {} tried to change the seeding of tournament {} - routes/game/update_elim_seeding()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let games = load_elim_games(&connection, id).await?;
+
+    // byes are closed on creation, they don't count as started games
+    if games.iter().any(|g| g.status != 0 && !g.is_bye()) {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Can't change the seeding if a game has started".into(),
+                },
+            }),
+        ));
+    }
+
+    // the qualified teams are the ones playing the current first round
+    let qualified: BTreeSet<i32> = first_round_pairs(&games)
+        .iter()
+        .flat_map(|(team1, team2)| [Some(*team1), *team2])
+        .flatten()
+        .collect();
+    let seeds: BTreeSet<i32> = data.seeds.iter().cloned().collect();
+
+    if seeds.len() != data.seeds.len() || seeds != qualified {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "invalid_seeding".into(),
+                    description: "Every qualified team must be seeded exactly once".into(),
+                },
+            }),
+        ));
+    }
+
+    // no group, the owner decides who meets who
+    let seeds: Vec<Qualifier> = data
+        .seeds
+        .iter()
+        .enumerate()
+        .map(|(index, team_id)| Qualifier {
+            team_id: *team_id,
+            group: 0,
+            rank: index + 1,
+        })
+        .collect();
+
+    create_first_round(&connection, id, seeding::first_round(&seeds)).await?;
+
+    events.send(id, EventKind::GameUpdated, None);
+
+    info!("{} - User {} changed the seeding of tournament {} - routes/game/update_elim_seeding()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+
+    Ok(Json(EmptyResponse()))
+}
+
+// generate the next round of a swiss tournament from the closed games
+#[post("/tournoix/<id>/swiss/next")]
+pub async fn create_swiss_round(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<Game>>, (Status, Json<ErrorResponse>)> {
+    // verify if the user is the owner of the tournament
+    if !is_owner(&connection, id, &auth).await {
+        warn!("{} - User {} tried to create a swiss round for tournament {} - routes/game/create_swiss_round()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let (tournament, teams, games) = load_swiss_tournament(&connection, id).await?;
+
+    if teams.len() < 2 {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "not_enough_team".into(),
+                    description: "Not enought team in the tournament to generate games".into(),
+                },
+            }),
+        ));
+    }
+
+    if games.iter().any(|g| g.status != 2) {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "All games of the current round need to be finished".into(),
+                },
+            }),
+        ));
+    }
+
+    let round = games.iter().map(|g| g.round).max().unwrap_or(0) + 1;
+
+    if let Some(nb_rounds) = tournament.swiss_rounds {
+        if round > nb_rounds {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "All the rounds have already been played".into(),
+                    },
+                }),
+            ));
+        }
+    }
+
+    let team_ids: Vec<i32> = teams.iter().map(|t| t.id).collect();
+    let standings = swiss::standings(&team_ids, &swiss_results(&games), &tournament.points());
+
+    let pairing = match swiss::pair_round(&standings) {
+        Some(pairing) => pairing,
+        None => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "no_pairing".into(),
+                        description: "Every pairing of the round would repeat a game".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    let mut new_games: Vec<NewGame> = vec![];
+    for (team1, team2) in pairing.games {
+        new_games.push(NewGame {
+            fk_tournaments: id,
+            fk_team1: team1,
+            fk_team2: team2,
+            score1: 0,
+            score2: 0,
+            phase: Phase::Qualification as i32,
+            place: new_games.len() as i32,
+            status: 0,
+            bracket: Bracket::Upper as i32,
+            round,
+        });
+    }
+
+    // the bye is a game against itself, already over
+    if let Some(team) = pairing.bye {
+        new_games.push(NewGame {
+            fk_tournaments: id,
+            fk_team1: team,
+            fk_team2: team,
+            score1: 0,
+            score2: 0,
+            phase: Phase::Qualification as i32,
+            place: new_games.len() as i32,
+            status: 2,
+            bracket: Bracket::Upper as i32,
+            round,
+        });
+    }
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::insert_into(games::table)
+                    .values(new_games)
+                    .execute(c)?;
+
+                // the new round of a scheduled tournament is placed after the games already scheduled
+                let start = tournament.date.max(Local::now().naive_local());
+                if let Some(config) = tournament.schedule_config(start) {
+                    schedule_games(c, &tournament, &config, false)?;
+                }
+
+                games::table
+                    .filter(games::fk_tournaments.eq(id))
+                    .filter(games::phase.eq(Phase::Qualification as i32))
+                    .filter(games::round.eq(round))
+                    .order(games::place.asc())
+                    .load::<Game>(c)
+            })
+        })
+        .await
+    {
+        Ok(games) => {
+            info!(
+                "{} - User {} generated round {} of tournament {} - routes/game/create_swiss_round()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                auth.user.id,
+                round,
+                id
+            );
+
+            events.send(id, EventKind::GameUpdated, None);
+
+            Ok(Json(games))
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// load a swiss tournament with its teams and its qualification games
+pub async fn load_swiss_tournament(
+    connection: &MysqlConnection,
+    id: i32,
+) -> Result<(Tournament, Vec<Team>, Vec<Game>), (Status, Json<ErrorResponse>)> {
+    let (tournament, teams, games) = load_qualification(connection, id).await?;
+
+    if !tournament.is_swiss {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "not_swiss".into(),
+                    description: "The tournament doesn't use the swiss format".into(),
+                },
+            }),
+        ));
+    }
+
+    Ok((tournament, teams, games))
+}
+
+// load a tournament with its teams and its qualification games
+pub async fn load_qualification(
+    connection: &MysqlConnection,
+    id: i32,
+) -> Result<(Tournament, Vec<Team>, Vec<Game>), (Status, Json<ErrorResponse>)> {
+    let tournament = match connection
+        .run(move |c| tournaments::table.find(id).first::<Tournament>(c))
+        .await
+    {
+        Ok(t) => t,
+        Err(_e) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Tournament not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    match connection
+        .run(move |c| {
+            let teams = teams::table
+                .filter(teams::fk_tournaments.eq(id))
+                .load::<Team>(c)?;
+
+            let games = games::table
+                .filter(games::fk_tournaments.eq(id))
+                .filter(games::phase.eq(Phase::Qualification as i32))
+                .load::<Game>(c)?;
+
+            diesel::result::QueryResult::Ok((teams, games))
+        })
+        .await
+    {
+        Ok((teams, games)) => Ok((tournament, teams, games)),
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// standings of a group from the closed qualification games
+pub fn group_standings(
+    tournament: &Tournament,
+    teams: &[Team],
+    games: &[Game],
+    group: i32,
+) -> Vec<Standing> {
+    let team_ids: Vec<i32> = teams
+        .iter()
+        .filter(|t| t.group == group)
+        .map(|t| t.id)
+        .collect();
+
+    let results: Vec<GameResult> = games
+        .iter()
+        .filter(|g| g.status == 2)
+        .map(|g| GameResult {
+            team1: g.fk_team1,
+            team2: g.fk_team2,
+            score1: g.score1,
+            score2: g.score2,
+        })
+        .collect();
+
+    // same draw for the teams still tied each time the standings are computed
+    let seed = ((tournament.id as u64) << 32) | group as u32 as u64;
+
+    standings::standings(&team_ids, &results, &tournament.points(), seed)
+}
+
+// results of the closed swiss games
+pub fn swiss_results(games: &[Game]) -> Vec<SwissResult> {
+    games
+        .iter()
+        .filter(|g| g.status == 2)
+        .map(|g| SwissResult {
+            team1: g.fk_team1,
+            team2: g.fk_team2,
+            score1: g.score1,
+            score2: g.score2,
+        })
+        .collect()
+}
+
+async fn update_game_fn(
+    connection: &MysqlConnection,
+    events: &Events,
+    data: Json<PatchGame>,
+    id: i32,
+) -> Result<Json<Game>, (Status, Json<ErrorResponse>)> {
+    let game = data.0;
+
+    // cannot update the game it the betting is closed
+    /*
+    if game.status == Some(false) {
+        return Err((Status::BadRequest, "Betting is closed".to_string()));
+    }
+    */
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::update(games::table.find(id))
+                    .set(game.clone())
+                    .execute(c)?;
+
+                let game = games::table.find(id).first::<Game>(c).map(Json)?;
+
+                diesel::result::QueryResult::Ok(game)
+            })
+        })
+        .await
+    {
+        Ok(game) => {
+            events.send(game.fk_tournaments, EventKind::GameUpdated, Some(id));
+            return Ok(game);
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    }
+}
+
+#[patch("/games/<id>", data = "<data>")]
+pub async fn update_game(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    data: Json<PatchGame>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Game>, (Status, Json<ErrorResponse>)> {
+    // the user is not the owner of the game
+    if !is_owner_game(&connection, id, &auth).await {
+        warn!(
+            "{} - User {} tried to update game {} - routes/game/update_game()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            auth.user.id,
+            id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    if let Some(tiebreak) = data.tiebreak {
+        if Tiebreak::from_i32(tiebreak).is_none() {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "Unknown tiebreak".into(),
+                    },
+                }),
+            ));
+        }
+    }
+
+    if data.tiebreak.is_some() || data.tiebreak_winner.is_some() {
+        let game = match connection
+            .run(move |c| games::table.find(id).first::<Game>(c))
+            .await
+        {
+            Ok(game) => game,
+            Err(_e) => {
+                return Err((
+                    Status::NotFound,
+                    Json(ErrorResponse {
+                        error: ErrorBody {
+                            code: 404,
+                            reason: "Not Found".into(),
+                            description: "Game not found".into(),
+                        },
+                    }),
+                ));
+            }
+        };
+
+        // the tiebreak winner is one of the teams of the game, 0 to remove it
+        if let Some(tiebreak_winner) = data.tiebreak_winner.filter(|w| *w != 0) {
+            if game.fk_team1 != tiebreak_winner && game.fk_team2 != tiebreak_winner {
+                return Err((
+                    Status::BadRequest,
+                    Json(ErrorResponse {
+                        error: ErrorBody {
+                            code: 400,
+                            reason: "Bad Request".into(),
+                            description: "The tiebreak winner must be a team of the game".into(),
+                        },
+                    }),
+                ));
+            }
+        }
+
+        // a game decided by a tiebreak records how, with the values already stored when not patched
+        let tiebreak = data.tiebreak.unwrap_or(game.tiebreak);
+        let tiebreak_winner = data.tiebreak_winner.unwrap_or(game.tiebreak_winner);
+        if tiebreak_winner != 0 && tiebreak == Tiebreak::None as i32 {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "A tiebreak winner needs the kind of tiebreak".into(),
+                    },
+                }),
+            ));
+        }
+    }
+
+    return update_game_fn(&connection, events, data, id).await;
+}
+
+// block the action of betting on a game
 #[patch("/games/<id>/closeBetting")]
 pub async fn close_game_betting(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<Game>, (Status, String)> {
@@ -874,10 +1348,7 @@ pub async fn close_game_betting(
                     .set(games::status.eq(1))
                     .execute(c)?;
 
-                let game = games::table
-                    .order(games::id.desc())
-                    .first::<Game>(c)
-                    .map(Json)?;
+                let game = games::table.find(id).first::<Game>(c).map(Json)?;
 
                 diesel::result::QueryResult::Ok(game)
             })
@@ -893,11 +1364,13 @@ pub async fn close_game_betting(
         }
     };
 
+    events.send(game.fk_tournaments, EventKind::BettingClosed, Some(id));
+
     Ok(game)
 }
 
 // verify if the user can edit a game
-async fn is_owner_game(connection: &MysqlConnection, id: i32, auth: &ApiAuth) -> bool {
+pub async fn is_owner_game(connection: &MysqlConnection, id: i32, auth: &ApiAuth) -> bool {
     let user_id = auth.user.id;
     let game = match connection
         .run(move |c| {
@@ -917,6 +1390,330 @@ async fn is_owner_game(connection: &MysqlConnection, id: i32, auth: &ApiAuth) ->
     return game;
 }
 
+// team sent by a game of the elimination phase to another game
+enum Entrant {
+    Team(i32),
+    // loser of a bye, or anyone coming out of a game nobody played
+    Nobody,
+}
+
+// none while the game giving the team is not over
+fn entrant(layout: &Layout, games: &[Game], feed: Feed) -> Option<Entrant> {
+    match games.iter().find(|g| g.slot() == Some(feed.slot)) {
+        Some(game) if game.status == 2 => match feed.outcome {
+            Outcome::Winner => game.winner().map(Entrant::Team),
+            Outcome::Loser if game.is_bye() => Some(Entrant::Nobody),
+            Outcome::Loser => game.loser().map(Entrant::Team),
+        },
+        Some(_) => None,
+        None => {
+            // a game between two missing teams is never created
+            let feeds = layout.feeds(feed.slot)?;
+            match (
+                entrant(layout, games, feeds[0]),
+                entrant(layout, games, feeds[1]),
+            ) {
+                (Some(Entrant::Nobody), Some(Entrant::Nobody)) => Some(Entrant::Nobody),
+                _ => None,
+            }
+        }
+    }
+}
+
+// create the games that were waiting for the result of a closed elimination game,
+// to call in the transaction closing it
+pub fn advance_teams(c: &mut diesel::MysqlConnection, game: &Game) -> QueryResult<()> {
+    let slot = match game.slot() {
+        Some(slot) => slot,
+        None => return Ok(()),
+    };
+
+    let tournament_id = game.fk_tournaments;
+    let tournament = tournaments::table
+        .find(tournament_id)
+        .first::<Tournament>(c)?;
+    let mut games = games::table
+        .filter(games::fk_tournaments.eq(tournament_id))
+        .filter(games::phase.gt(0))
+        .load::<Game>(c)?;
+
+    let first_round = games
+        .iter()
+        .filter(|g| g.bracket == Bracket::Upper as i32 && g.phase == 1)
+        .count();
+    let layout = Layout::new(first_round, tournament.is_double_elim)
+        .with_third_place(tournament.has_third_place);
+
+    let mut new_games: Vec<NewGame> = vec![];
+
+    // The lower bracket champion won the grand final, both teams now have one loss
+    if slot.bracket == Bracket::GrandFinal
+        && slot.phase == 1
+        && tournament.has_bracket_reset
+        && game.winner() == Some(game.fk_team2)
+    {
+        new_games.push(NewGame {
+            fk_tournaments: tournament_id,
+            fk_team1: game.fk_team1,
+            fk_team2: game.fk_team2,
+            score1: 0,
+            score2: 0,
+            phase: 2,
+            place: 0,
+            status: 0,
+            bracket: Bracket::GrandFinal as i32,
+            round: 0,
+        });
+    }
+
+    // games whose result is known, byes created here go through the same steps
+    let mut resolved = vec![slot];
+
+    while let Some(slot) = resolved.pop() {
+        for outcome in [Outcome::Winner, Outcome::Loser] {
+            let next = match layout.next(slot, outcome) {
+                Some(next) => next,
+                None => continue,
+            };
+
+            // the game has already been created
+            if games.iter().any(|g| g.slot() == Some(next)) {
+                continue;
+            }
+
+            let feeds = match layout.feeds(next) {
+                Some(feeds) => feeds,
+                None => continue,
+            };
+
+            let (team1, team2) = match (
+                entrant(&layout, &games, feeds[0]),
+                entrant(&layout, &games, feeds[1]),
+            ) {
+                (Some(Entrant::Team(team1)), Some(Entrant::Team(team2))) => (team1, team2),
+                // the team has no opponent, it gets a bye
+                (Some(Entrant::Team(team)), Some(Entrant::Nobody))
+                | (Some(Entrant::Nobody), Some(Entrant::Team(team))) => {
+                    resolved.push(next);
+                    (team, team)
+                }
+                // nobody plays this game, the next ones may still get a team
+                (Some(Entrant::Nobody), Some(Entrant::Nobody)) => {
+                    resolved.push(next);
+                    continue;
+                }
+                // the other game is not over yet
+                _ => continue,
+            };
+
+            let new_game = NewGame {
+                fk_tournaments: tournament_id,
+                fk_team1: team1,
+                fk_team2: team2,
+                score1: 0,
+                score2: 0,
+                phase: next.phase,
+                place: next.place,
+                status: if team1 == team2 { 2 } else { 0 },
+                bracket: next.bracket as i32,
+                round: 0,
+            };
+
+            games.push(Game {
+                id: 0,
+                fk_tournaments: new_game.fk_tournaments,
+                fk_team1: new_game.fk_team1,
+                fk_team2: new_game.fk_team2,
+                score1: 0,
+                score2: 0,
+                phase: new_game.phase,
+                place: new_game.place,
+                status: new_game.status,
+                has_gained_nut: false,
+                bracket: new_game.bracket,
+                round: 0,
+                tiebreak: 0,
+                tiebreak_winner: 0,
+                scheduled_at: None,
+                court: None,
+                updated_at: Local::now().naive_local(),
+            });
+            new_games.push(new_game);
+        }
+    }
+
+    if new_games.is_empty() {
+        return Ok(());
+    }
+
+    diesel::insert_into(games::table)
+        .values(new_games)
+        .execute(c)?;
+
+    // the new games of a scheduled tournament are placed after the ones already scheduled
+    let start = tournament.date.max(Local::now().naive_local());
+    if let Some(config) = tournament.schedule_config(start) {
+        schedule_games(c, &tournament, &config, false)?;
+    }
+
+    Ok(())
+}
+
+// games of the elimination phase, the phase needs to be generated
+async fn load_elim_games(
+    connection: &MysqlConnection,
+    tournament_id: i32,
+) -> Result<Vec<Game>, (Status, Json<ErrorResponse>)> {
+    let games = match connection
+        .run(move |c| {
+            games::table
+                .filter(games::fk_tournaments.eq(tournament_id))
+                .filter(games::phase.gt(0))
+                .load::<Game>(c)
+        })
+        .await
+    {
+        Ok(games) => games,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    if games.is_empty() {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The elimination phase has not been generated".into(),
+                },
+            }),
+        ));
+    }
+
+    Ok(games)
+}
+
+// teams of the first elimination round by place, None for the opponent of a bye
+fn first_round_pairs(games: &[Game]) -> Vec<(i32, Option<i32>)> {
+    let mut first_round: Vec<&Game> = games
+        .iter()
+        .filter(|g| g.phase == 1 && g.bracket == Bracket::Upper as i32)
+        .collect();
+    first_round.sort_by_key(|g| g.place);
+
+    first_round
+        .iter()
+        .map(|g| (g.fk_team1, if g.is_bye() { None } else { Some(g.fk_team2) }))
+        .collect()
+}
+
+// insert the first elimination round in place of the existing elimination games,
+// a team without opponent gets a bye and goes to the next round
+async fn create_first_round(
+    connection: &MysqlConnection,
+    tournament_id: i32,
+    pairs: Vec<(i32, Option<i32>)>,
+) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
+    let games: Vec<NewGame> = pairs
+        .iter()
+        .enumerate()
+        .map(|(place, (team1, team2))| NewGame {
+            fk_tournaments: tournament_id,
+            fk_team1: *team1,
+            fk_team2: team2.unwrap_or(*team1),
+            score1: 0,
+            score2: 0,
+            place: place as i32,
+            phase: 1,
+            status: if team2.is_some() { 0 } else { 2 },
+            bracket: Bracket::Upper as i32,
+            round: 0,
+        })
+        .collect();
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                // checked again with the games locked, a game may have started since the validation
+                let existing = games::table
+                    .filter(games::fk_tournaments.eq(tournament_id))
+                    .filter(games::phase.gt(0))
+                    .for_update()
+                    .load::<Game>(c)?;
+
+                // byes are closed on creation, they don't count as started games
+                if existing.iter().any(|g| g.status != 0 && !g.is_bye()) {
+                    return diesel::result::QueryResult::Ok(None);
+                }
+
+                // the bets on the replaced games are given back
+                let ids: Vec<i32> = existing.iter().map(|g| g.id).collect();
+                refund_games(c, &ids)?;
+
+                diesel::delete(games::table.filter(games::id.eq_any(&ids))).execute(c)?;
+
+                diesel::insert_into(games::table)
+                    .values(games)
+                    .execute(c)?;
+
+                // a team with a bye goes to the next round
+                let byes = games::table
+                    .filter(games::fk_tournaments.eq(tournament_id))
+                    .filter(games::phase.eq(1))
+                    .filter(games::fk_team1.eq(games::fk_team2))
+                    .load::<Game>(c)?;
+                for bye in byes.iter() {
+                    advance_teams(c, bye)?;
+                }
+
+                diesel::result::QueryResult::Ok(Some(()))
+            })
+        })
+        .await
+    {
+        Ok(Some(())) => {}
+        Ok(None) => {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "Can't reset games if a game has started".into(),
+                    },
+                }),
+            ));
+        }
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    Ok(Json(EmptyResponse()))
+}
+
 pub async fn delete_tournament_games_qualif(
     connection: &MysqlConnection,
     tournament_id: i32,
@@ -933,15 +1730,19 @@ pub async fn delete_tournament_games_qualif(
         .ok();
 
     if let Some(games) = games {
-        if games.iter().any(|g| g.status != 0) {
+        // byes are closed on creation, they don't count as started games
+        if games.iter().any(|g| g.status != 0 && !g.is_bye()) {
             return false;
         } else {
             let _ = connection
                 .run(move |c| {
-                    diesel::delete(
-                        games::table.filter(games::id.eq_any(games.iter().map(|g| g.id))),
-                    )
-                    .execute(c)
+                    c.transaction(|c| {
+                        // the bets on the deleted games are given back
+                        let ids: Vec<i32> = games.iter().map(|g| g.id).collect();
+                        refund_games(c, &ids)?;
+
+                        diesel::delete(games::table.filter(games::id.eq_any(&ids))).execute(c)
+                    })
                 })
                 .await;
         }
@@ -966,15 +1767,19 @@ pub async fn delete_tournament_games_elim(
         .ok();
 
     if let Some(games) = games {
-        if games.iter().any(|g| g.status != 0) {
+        // byes are closed on creation, they don't count as started games
+        if games.iter().any(|g| g.status != 0 && !g.is_bye()) {
             return false;
         } else {
             let _ = connection
                 .run(move |c| {
-                    diesel::delete(
-                        games::table.filter(games::id.eq_any(games.iter().map(|g| g.id))),
-                    )
-                    .execute(c)
+                    c.transaction(|c| {
+                        // the bets on the deleted games are given back
+                        let ids: Vec<i32> = games.iter().map(|g| g.id).collect();
+                        refund_games(c, &ids)?;
+
+                        diesel::delete(games::table.filter(games::id.eq_any(&ids))).execute(c)
+                    })
                 })
                 .await;
         }
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::leaderboard::{self, Totals};
+use crate::models::season::{Leaderboard, LeaderboardEntry, Season, UserTotals};
+use crate::routes::auth::ApiAuth;
+use crate::schema::seasons;
+use crate::{ErrorBody, ErrorResponse, MysqlConnection};
+use diesel::prelude::*;
+use diesel::sql_types::Datetime;
+use log::warn;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+
+// seasons of the leaderboard, latest first
+#[get("/seasons")]
+pub async fn get_seasons(
+    connection: MysqlConnection,
+    _auth: ApiAuth,
+) -> Result<Json<Vec<Season>>, (Status, Json<ErrorResponse>)> {
+    match connection
+        .run(move |c| {
+            seasons::table
+                .order(seasons::start_date.desc())
+                .load::<Season>(c)
+        })
+        .await
+    {
+        Ok(seasons) => Ok(Json(seasons)),
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// nuts won by the users across the tournaments of a season, or of all tournaments without one
+#[get("/leaderboard?<season>")]
+pub async fn get_leaderboard(
+    connection: MysqlConnection,
+    season: Option<i32>,
+    _auth: ApiAuth,
+) -> Result<Json<Leaderboard>, (Status, Json<ErrorResponse>)> {
+    let season = match season {
+        Some(id) => match connection
+            .run(move |c| seasons::table.find(id).first::<Season>(c))
+            .await
+        {
+            Ok(season) => Some(season),
+            Err(_) => {
+                return Err((
+                    Status::NotFound,
+                    Json(ErrorResponse {
+                        error: ErrorBody {
+                            code: 404,
+                            reason: "Not Found".into(),
+                            description: "Season not found".into(),
+                        },
+                    }),
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let range = season.as_ref().map(|s| (s.start_date, s.end_date));
+
+    let totals = match connection
+        .run(move |c| {
+            // the payouts kept when the bets were settled, a score changed later doesn't count
+            let season_filter = match range {
+                Some(_) => "WHERE tournaments.date >= ? AND tournaments.date < ?",
+                None => "",
+            };
+
+            let mut query = diesel::sql_query(format!(
+                "SELECT settled.fk_users AS user_id, users.name AS username, \
+                 COUNT(*) AS bets, \
+                 CAST(SUM(settled.payout > settled.nb_nut) AS SIGNED) AS won, \
+                 CAST(SUM(settled.payout < settled.nb_nut) AS SIGNED) AS lost, \
+                 CAST(SUM(settled.nb_nut) AS SIGNED) AS staked, \
+                 CAST(SUM(settled.payout - settled.nb_nut) AS SIGNED) AS net \
+                 FROM ( \
+                 SELECT bets.fk_users, bets.nb_nut, bets.payout, games.fk_tournaments \
+                 FROM bets INNER JOIN games ON games.id = bets.fk_games \
+                 WHERE bets.payout IS NOT NULL \
+                 UNION ALL \
+                 SELECT fk_users, nb_nut, payout, fk_tournaments \
+                 FROM market_bets WHERE payout IS NOT NULL \
+                 ) AS settled \
+                 INNER JOIN tournaments ON tournaments.id = settled.fk_tournaments \
+                 INNER JOIN users ON users.id = settled.fk_users \
+                 {} \
+                 GROUP BY settled.fk_users, users.name",
+                season_filter
+            ))
+            .into_boxed();
+
+            if let Some((start, end)) = range {
+                query = query.bind::<Datetime, _>(start).bind::<Datetime, _>(end);
+            }
+
+            query.load::<UserTotals>(c)
+        })
+        .await
+    {
+        Ok(totals) => totals,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    let usernames: HashMap<i32, String> = totals
+        .iter()
+        .map(|t| (t.user_id, t.username.clone()))
+        .collect();
+    let totals: Vec<Totals> = totals.iter().map(|t| t.totals()).collect();
+
+    Ok(Json(Leaderboard {
+        season,
+        entries: leaderboard::leaderboard(&totals)
+            .into_iter()
+            .map(|record| LeaderboardEntry {
+                username: usernames.get(&record.user_id).cloned().unwrap_or_default(),
+                record,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,882 @@
+use crate::betting::{self, Payout, ScoreStake, Stake};
+use crate::bracket::{Bracket, Layout};
+use crate::events::{EventKind, Events};
+use crate::models::game::Game;
+use crate::models::market_bet::{Market, MarketBet, MarketBetWithUser, MarketBets, NewMarketBet};
+use crate::models::nut::Nut;
+use crate::models::nut_transaction::TransactionKind;
+use crate::models::subscription::Subscription;
+use crate::models::team::Team;
+use crate::models::tournament::Tournament;
+use crate::ranking::is_final;
+use crate::routes::auth::ApiAuth;
+use crate::routes::bet::{lock_bet, pay, BetError};
+use crate::schema::{games, market_bets, nuts, subscriptions, teams, tournaments, users};
+use crate::{ErrorBody, ErrorResponse, MysqlConnection};
+use chrono::Local;
+use diesel::prelude::*;
+use log::warn;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScoreBetData {
+    pub score1: i32,
+    pub score2: i32,
+    pub nut: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChampionBetData {
+    pub team_id: i32,
+    pub nut: u32,
+}
+
+// Get the exact score bets of a game
+#[get("/game/<id>/score_bet")]
+pub async fn get_score_bets(
+    connection: MysqlConnection,
+    auth: ApiAuth,
+    id: i32,
+) -> Result<Json<MarketBets>, (Status, Json<ErrorResponse>)> {
+    let game = match connection
+        .run(move |c| games::table.find(id).first::<Game>(c))
+        .await
+    {
+        Ok(game) => game,
+        Err(_e) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Game not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    if !is_subscriber(&connection, game.fk_tournaments, &auth).await {
+        warn!(
+            "{} - User {} tried to get score bets of tournament {} even if he is not a subscriber - routes/market/get_score_bets()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            auth.user.id,
+            game.fk_tournaments
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let bets = load_market_bets(
+        &connection,
+        Market::ExactScore,
+        game.fk_tournaments,
+        Some(id),
+    )
+    .await?;
+
+    Ok(Json(MarketBets {
+        is_open: game.status == 0,
+        pot: bets.iter().map(|b| b.nb_nut).sum(),
+        bets,
+    }))
+}
+
+// bet on the score of a game
+#[post("/game/<id>/score_bet", data = "<data>")]
+pub async fn create_score_bet(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    id: i32,
+    auth: ApiAuth,
+    data: Json<ScoreBetData>,
+) -> Result<Json<MarketBet>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+    let nb_nut = i32::try_from(data.nut).unwrap_or(i32::MAX);
+    let (score1, score2) = (data.score1, data.score2);
+
+    if score1 < 0 || score2 < 0 || nb_nut < 1 {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "A score bet needs a positive score and at least one nut".into(),
+                },
+            }),
+        ));
+    }
+
+    let result = connection
+        .run(move |c| {
+            c.transaction(|c| {
+                let (game, nut) = lock_bet(c, id, user_id)?;
+
+                if game.status != 0 {
+                    return Err(BetError::GameNotOpen);
+                }
+
+                let has_bet = market_bets::table
+                    .filter(market_bets::market.eq(Market::ExactScore as i32))
+                    .filter(market_bets::fk_games.eq(id))
+                    .filter(market_bets::fk_users.eq(user_id))
+                    .first::<MarketBet>(c)
+                    .optional()?
+                    .is_some();
+                if has_bet {
+                    return Err(BetError::AlreadyBet);
+                }
+
+                if nb_nut > nut.stock {
+                    return Err(BetError::NotEnoughNut);
+                }
+
+                nut.record(c, TransactionKind::Stake, -nb_nut, Some(id), None)?;
+
+                diesel::insert_into(market_bets::table)
+                    .values(NewMarketBet {
+                        market: Market::ExactScore as i32,
+                        fk_tournaments: game.fk_tournaments,
+                        fk_users: user_id,
+                        fk_games: Some(id),
+                        fk_teams: None,
+                        score1: Some(score1),
+                        score2: Some(score2),
+                        nb_nut,
+                    })
+                    .execute(c)?;
+
+                let bet = market_bets::table
+                    .filter(market_bets::market.eq(Market::ExactScore as i32))
+                    .filter(market_bets::fk_games.eq(id))
+                    .filter(market_bets::fk_users.eq(user_id))
+                    .first::<MarketBet>(c)?;
+
+                Ok(bet)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(bet) => {
+            events.send(bet.fk_tournaments, EventKind::BetsChanged, Some(id));
+            Ok(Json(bet))
+        }
+        Err(BetError::GameNotOpen) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The game is not open".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::NotEnoughNut) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Not enough nut".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::AlreadyBet) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "A bet already exists for this market".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Bet not found".into(),
+                },
+            }),
+        )),
+
+        Err(_e) => Err((
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 500,
+                    reason: "Internal Server Error".into(),
+                    description: "An error occured".into(),
+                },
+            }),
+        )),
+    }
+}
+
+// cancel a score bet while the game is open
+#[delete("/game/<id>/score_bet")]
+pub async fn delete_score_bet(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<MarketBet>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+
+    let result = connection
+        .run(move |c| {
+            c.transaction(|c| {
+                let (game, nut) = lock_bet(c, id, user_id)?;
+
+                let bet = market_bets::table
+                    .filter(market_bets::market.eq(Market::ExactScore as i32))
+                    .filter(market_bets::fk_games.eq(id))
+                    .filter(market_bets::fk_users.eq(user_id))
+                    .for_update()
+                    .first::<MarketBet>(c)?;
+
+                if game.status != 0 {
+                    return Err(BetError::GameNotOpen);
+                }
+
+                diesel::delete(market_bets::table.find(bet.id)).execute(c)?;
+                nut.record(c, TransactionKind::Refund, bet.nb_nut, Some(id), None)?;
+
+                Ok(bet)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(bet) => {
+            events.send(bet.fk_tournaments, EventKind::BetsChanged, Some(id));
+            Ok(Json(bet))
+        }
+        Err(BetError::GameNotOpen) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The game is not open".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Bet not found".into(),
+                },
+            }),
+        )),
+
+        Err(_e) => Err((
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 500,
+                    reason: "Internal Server Error".into(),
+                    description: "An error occured".into(),
+                },
+            }),
+        )),
+    }
+}
+
+// Get the bets on the winner of a tournament
+#[get("/tournoix/<id>/champion_bet")]
+pub async fn get_champion_bets(
+    connection: MysqlConnection,
+    auth: ApiAuth,
+    id: i32,
+) -> Result<Json<MarketBets>, (Status, Json<ErrorResponse>)> {
+    if !is_subscriber(&connection, id, &auth).await {
+        warn!(
+            "{} - User {} tried to get champion bets of tournament {} even if he is not a subscriber - routes/market/get_champion_bets()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            auth.user.id,
+            id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let (tournament, games) = match connection
+        .run(move |c| {
+            let tournament = tournaments::table.find(id).first::<Tournament>(c)?;
+            let games = elimination_games(c, id)?;
+
+            diesel::result::QueryResult::Ok((tournament, games))
+        })
+        .await
+    {
+        Ok(data) => data,
+        Err(_e) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Tournament not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    let bets = load_market_bets(&connection, Market::Champion, id, None).await?;
+
+    Ok(Json(MarketBets {
+        is_open: is_champion_market_open(&tournament, &games),
+        pot: bets.iter().map(|b| b.nb_nut).sum(),
+        bets,
+    }))
+}
+
+// bet on the winner of a tournament
+#[post("/tournoix/<id>/champion_bet", data = "<data>")]
+pub async fn create_champion_bet(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    id: i32,
+    auth: ApiAuth,
+    data: Json<ChampionBetData>,
+) -> Result<Json<MarketBet>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+    let nb_nut = i32::try_from(data.nut).unwrap_or(i32::MAX);
+    let team_id = data.team_id;
+
+    if nb_nut < 1 {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "A bet needs at least one nut".into(),
+                },
+            }),
+        ));
+    }
+
+    let is_team = connection
+        .run(move |c| {
+            teams::table
+                .find(team_id)
+                .filter(teams::fk_tournaments.eq(id))
+                .first::<Team>(c)
+                .optional()
+        })
+        .await
+        .ok()
+        .flatten()
+        .is_some();
+    if !is_team {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The team is not in this tournament".into(),
+                },
+            }),
+        ));
+    }
+
+    let result = connection
+        .run(move |c| {
+            c.transaction(|c| {
+                // the settlement of the market locks the tournament too
+                let tournament = tournaments::table
+                    .find(id)
+                    .for_update()
+                    .first::<Tournament>(c)?;
+                let games = elimination_games(c, id)?;
+
+                if !is_champion_market_open(&tournament, &games) {
+                    return Err(BetError::MarketClosed);
+                }
+
+                let nut = nuts::table
+                    .filter(nuts::fk_users.eq(user_id))
+                    .filter(nuts::fk_tournaments.eq(id))
+                    .for_update()
+                    .first::<Nut>(c)?;
+
+                let has_bet = market_bets::table
+                    .filter(market_bets::market.eq(Market::Champion as i32))
+                    .filter(market_bets::fk_tournaments.eq(id))
+                    .filter(market_bets::fk_users.eq(user_id))
+                    .first::<MarketBet>(c)
+                    .optional()?
+                    .is_some();
+                if has_bet {
+                    return Err(BetError::AlreadyBet);
+                }
+
+                if nb_nut > nut.stock {
+                    return Err(BetError::NotEnoughNut);
+                }
+
+                nut.record(c, TransactionKind::Stake, -nb_nut, None, None)?;
+
+                diesel::insert_into(market_bets::table)
+                    .values(NewMarketBet {
+                        market: Market::Champion as i32,
+                        fk_tournaments: id,
+                        fk_users: user_id,
+                        fk_games: None,
+                        fk_teams: Some(team_id),
+                        score1: None,
+                        score2: None,
+                        nb_nut,
+                    })
+                    .execute(c)?;
+
+                let bet = market_bets::table
+                    .filter(market_bets::market.eq(Market::Champion as i32))
+                    .filter(market_bets::fk_tournaments.eq(id))
+                    .filter(market_bets::fk_users.eq(user_id))
+                    .first::<MarketBet>(c)?;
+
+                Ok(bet)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(bet) => {
+            events.send(id, EventKind::BetsChanged, None);
+            Ok(Json(bet))
+        }
+        Err(BetError::MarketClosed) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The champion market is closed".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::NotEnoughNut) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Not enough nut".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::AlreadyBet) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "A bet already exists for this market".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Bet not found".into(),
+                },
+            }),
+        )),
+
+        Err(_e) => Err((
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 500,
+                    reason: "Internal Server Error".into(),
+                    description: "An error occured".into(),
+                },
+            }),
+        )),
+    }
+}
+
+// cancel a champion bet while the elimination phase has not started
+#[delete("/tournoix/<id>/champion_bet")]
+pub async fn delete_champion_bet(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<MarketBet>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+
+    let result = connection
+        .run(move |c| {
+            c.transaction(|c| {
+                let tournament = tournaments::table
+                    .find(id)
+                    .for_update()
+                    .first::<Tournament>(c)?;
+                let games = elimination_games(c, id)?;
+
+                let nut = nuts::table
+                    .filter(nuts::fk_users.eq(user_id))
+                    .filter(nuts::fk_tournaments.eq(id))
+                    .for_update()
+                    .first::<Nut>(c)?;
+
+                let bet = market_bets::table
+                    .filter(market_bets::market.eq(Market::Champion as i32))
+                    .filter(market_bets::fk_tournaments.eq(id))
+                    .filter(market_bets::fk_users.eq(user_id))
+                    .for_update()
+                    .first::<MarketBet>(c)?;
+
+                if !is_champion_market_open(&tournament, &games) {
+                    return Err(BetError::MarketClosed);
+                }
+
+                diesel::delete(market_bets::table.find(bet.id)).execute(c)?;
+                nut.record(c, TransactionKind::Refund, bet.nb_nut, None, None)?;
+
+                Ok(bet)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(bet) => {
+            events.send(id, EventKind::BetsChanged, None);
+            Ok(Json(bet))
+        }
+        Err(BetError::MarketClosed) => Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The champion market is closed".into(),
+                },
+            }),
+        )),
+
+        Err(BetError::Database(diesel::result::Error::NotFound)) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Bet not found".into(),
+                },
+            }),
+        )),
+
+        Err(_e) => Err((
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 500,
+                    reason: "Internal Server Error".into(),
+                    description: "An error occured".into(),
+                },
+            }),
+        )),
+    }
+}
+
+// share the exact score bets of a game, to call in the transaction closing it
+pub fn settle_score_bets(
+    c: &mut diesel::MysqlConnection,
+    game: &Game,
+) -> QueryResult<Vec<(Payout, TransactionKind)>> {
+    let bets = market_bets::table
+        .filter(market_bets::market.eq(Market::ExactScore as i32))
+        .filter(market_bets::fk_games.eq(game.id))
+        .filter(market_bets::payout.is_null())
+        .order(market_bets::id)
+        .load::<MarketBet>(c)?;
+
+    let stakes: Vec<ScoreStake> = bets
+        .iter()
+        .map(|b| ScoreStake {
+            user_id: b.fk_users,
+            score: (b.score1.unwrap_or(0), b.score2.unwrap_or(0)),
+            nuts: b.nb_nut,
+        })
+        .collect();
+    let score = (game.score1, game.score2);
+    let payouts = betting::settle_exact_score(Some(score), &stakes);
+
+    // the stakes are given back when nobody found the score
+    let kind = if stakes.iter().any(|s| s.score == score) {
+        TransactionKind::Payout
+    } else {
+        TransactionKind::Refund
+    };
+
+    for (bet, payout) in bets.iter().zip(payouts.iter()) {
+        diesel::update(market_bets::table.find(bet.id))
+            .set(market_bets::payout.eq(payout.nuts))
+            .execute(c)?;
+    }
+
+    Ok(payouts.into_iter().map(|p| (p, kind)).collect())
+}
+
+// give back the exact score bets of a game about to be deleted, to call in the transaction deleting it
+pub fn refund_score_bets(
+    c: &mut diesel::MysqlConnection,
+    game_id: i32,
+) -> QueryResult<Vec<(Payout, TransactionKind)>> {
+    let bets = market_bets::table
+        .filter(market_bets::market.eq(Market::ExactScore as i32))
+        .filter(market_bets::fk_games.eq(game_id))
+        .filter(market_bets::payout.is_null())
+        .load::<MarketBet>(c)?;
+
+    diesel::delete(market_bets::table.filter(market_bets::id.eq_any(bets.iter().map(|b| b.id))))
+        .execute(c)?;
+
+    Ok(bets
+        .iter()
+        .map(|b| {
+            (
+                Payout {
+                    user_id: b.fk_users,
+                    nuts: b.nb_nut,
+                },
+                TransactionKind::Refund,
+            )
+        })
+        .collect())
+}
+
+// give back the champion bets on a team about to be deleted, to call in the transaction deleting it
+pub fn refund_champion_bets(c: &mut diesel::MysqlConnection, team_id: i32) -> QueryResult<()> {
+    let bets = market_bets::table
+        .filter(market_bets::market.eq(Market::Champion as i32))
+        .filter(market_bets::fk_teams.eq(team_id))
+        .filter(market_bets::payout.is_null())
+        .load::<MarketBet>(c)?;
+
+    let tournament_id = match bets.first() {
+        Some(bet) => bet.fk_tournaments,
+        None => return Ok(()),
+    };
+
+    diesel::delete(market_bets::table.filter(market_bets::id.eq_any(bets.iter().map(|b| b.id))))
+        .execute(c)?;
+
+    pay(
+        c,
+        tournament_id,
+        None,
+        bets.iter()
+            .map(|b| {
+                (
+                    Payout {
+                        user_id: b.fk_users,
+                        nuts: b.nb_nut,
+                    },
+                    TransactionKind::Refund,
+                )
+            })
+            .collect(),
+    )
+}
+
+// pay the champion bets once the final is over, nothing to do before, to call in the
+// transaction closing the game. Only the unsettled bets are paid, it can run again
+pub fn settle_champion_bets(c: &mut diesel::MysqlConnection, tournament_id: i32) -> QueryResult<()> {
+    // two finals closed together settle the market once
+    let tournament = tournaments::table
+        .find(tournament_id)
+        .for_update()
+        .first::<Tournament>(c)?;
+    let games = elimination_games(c, tournament_id)?;
+
+    let champion = match champion(&tournament, &games) {
+        Some(champion) => champion,
+        None => return Ok(()),
+    };
+
+    let bets = market_bets::table
+        .filter(market_bets::market.eq(Market::Champion as i32))
+        .filter(market_bets::fk_tournaments.eq(tournament_id))
+        .filter(market_bets::payout.is_null())
+        .order(market_bets::id)
+        .load::<MarketBet>(c)?;
+
+    let stakes: Vec<Stake> = bets
+        .iter()
+        .map(|b| Stake {
+            user_id: b.fk_users,
+            team_id: b.fk_teams.unwrap_or(0),
+            nuts: b.nb_nut,
+        })
+        .collect();
+    let payouts = betting::settle(Some(champion), &stakes);
+
+    let kind = if stakes.iter().any(|s| s.team_id == champion) {
+        TransactionKind::Payout
+    } else {
+        TransactionKind::Refund
+    };
+
+    for (bet, payout) in bets.iter().zip(payouts.iter()) {
+        diesel::update(market_bets::table.find(bet.id))
+            .set(market_bets::payout.eq(payout.nuts))
+            .execute(c)?;
+    }
+
+    pay(
+        c,
+        tournament_id,
+        None,
+        payouts.into_iter().map(|p| (p, kind)).collect(),
+    )
+}
+
+fn elimination_games(
+    c: &mut diesel::MysqlConnection,
+    tournament_id: i32,
+) -> QueryResult<Vec<Game>> {
+    games::table
+        .filter(games::fk_tournaments.eq(tournament_id))
+        .filter(games::phase.gt(0))
+        .load::<Game>(c)
+}
+
+// the market closes when the first game of the elimination phase starts
+fn is_champion_market_open(tournament: &Tournament, games: &[Game]) -> bool {
+    tournament.is_elim && !games.iter().any(|g| !g.is_bye() && g.status != 0)
+}
+
+// winner of the last final, none while a final is left to play
+fn champion(tournament: &Tournament, games: &[Game]) -> Option<i32> {
+    let first_round = games
+        .iter()
+        .filter(|g| g.bracket == Bracket::Upper as i32 && g.phase == 1)
+        .count();
+    let layout = Layout::new(first_round, tournament.is_double_elim)
+        .with_third_place(tournament.has_third_place);
+
+    let finals: Vec<&Game> = games
+        .iter()
+        .filter(|g| g.slot().is_some_and(|slot| is_final(&layout, &slot)))
+        .collect();
+
+    // a bracket reset adds a final once the first one is closed
+    if finals.iter().any(|g| g.status != 2) {
+        return None;
+    }
+
+    finals.iter().max_by_key(|g| g.phase)?.winner()
+}
+
+async fn load_market_bets(
+    connection: &MysqlConnection,
+    market: Market,
+    tournament_id: i32,
+    game_id: Option<i32>,
+) -> Result<Vec<MarketBetWithUser>, (Status, Json<ErrorResponse>)> {
+    match connection
+        .run(move |c| {
+            let mut query = market_bets::table
+                .inner_join(users::table.on(users::id.eq(market_bets::fk_users)))
+                .filter(market_bets::market.eq(market as i32))
+                .filter(market_bets::fk_tournaments.eq(tournament_id))
+                .into_boxed();
+
+            if let Some(game_id) = game_id {
+                query = query.filter(market_bets::fk_games.eq(game_id));
+            }
+
+            query
+                .select((
+                    market_bets::id,
+                    market_bets::market,
+                    market_bets::fk_tournaments,
+                    market_bets::fk_users,
+                    market_bets::fk_games,
+                    market_bets::fk_teams,
+                    market_bets::score1,
+                    market_bets::score2,
+                    market_bets::nb_nut,
+                    market_bets::payout,
+                    users::name,
+                ))
+                .order(market_bets::nb_nut.desc())
+                .load::<MarketBetWithUser>(c)
+        })
+        .await
+    {
+        Ok(bets) => Ok(bets),
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+async fn is_subscriber(connection: &MysqlConnection, tournament_id: i32, auth: &ApiAuth) -> bool {
+    let user_id = auth.user.id;
+
+    connection
+        .run(move |c| {
+            subscriptions::table
+                .filter(subscriptions::fk_tournaments.eq(tournament_id))
+                .filter(subscriptions::fk_users.eq(user_id))
+                .first::<Subscription>(c)
+        })
+        .await
+        .is_ok()
+}
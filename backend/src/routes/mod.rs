@@ -1,7 +1,13 @@
 pub mod auth;
 pub mod bet;
+pub mod calendar;
+pub mod leaderboard;
+pub mod events;
 pub mod game;
+pub mod market;
 pub mod nut;
+pub mod schedule;
+pub mod series;
 pub mod subscription;
 pub mod team;
 pub mod tournoix;
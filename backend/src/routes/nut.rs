@@ -1,11 +1,16 @@
+use std::collections::HashMap;
+
 use crate::models::nut::{Nut, PatchNut};
-use crate::models::tournament::Tournament;
+use crate::models::nut_transaction::{NutAudit, NutTransaction, TransactionKind};
+use crate::models::user::User;
 use crate::routes::auth::ApiAuth;
+use crate::routes::tournoix::is_owner;
 use crate::schema::nuts::{self, fk_tournaments, fk_users};
-use crate::schema::tournaments;
+use crate::schema::{nut_transactions, users};
 use crate::{ErrorBody, ErrorResponse, MysqlConnection};
+use chrono::Local;
 use diesel::prelude::*;
-use log::warn;
+use log::{info, warn};
 use rocket::http::Status;
 use rocket::serde::json::Json;
 
@@ -41,54 +46,247 @@ pub async fn get_nut(
     }
 }
 
-#[patch("/nut/<id>", data = "<data>")]
-pub async fn update_nut(
+// history of the nut of a user for a tournament, latest first
+#[get("/tournoix/<id>/nut/history")]
+pub async fn get_nut_history(
     connection: MysqlConnection,
     id: i32,
-    data: Json<PatchNut>,
     auth: ApiAuth,
-) -> Result<Json<Nut>, (Status, String)> {
-    // Check if the caller is an owner of the tournament
+) -> Result<Json<Vec<NutTransaction>>, (Status, Json<ErrorResponse>)> {
     match connection
         .run(move |c| {
-            tournaments::table
-                .filter(tournaments::id.eq(id))
-                .filter(tournaments::fk_users.eq(auth.user.id))
-                .first::<Tournament>(c)
+            nut_transactions::table
+                .inner_join(nuts::table)
+                .filter(fk_tournaments.eq(id))
+                .filter(fk_users.eq(auth.user.id))
+                .select(nut_transactions::all_columns)
+                .order(nut_transactions::id.desc())
+                .load::<NutTransaction>(c)
+        })
+        .await
+    {
+        Ok(transactions) => Ok(Json(transactions)),
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// ledger of every player of a tournament, for its owner
+#[get("/tournoix/<id>/nut/audit")]
+pub async fn get_nut_audit(
+    connection: MysqlConnection,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<NutAudit>>, (Status, Json<ErrorResponse>)> {
+    if !is_owner(&connection, id, &auth).await {
+        warn!(
+            "{} - User {} tried to audit the nuts of tournament {} - routes/nut/get_nut_audit()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            auth.user.id,
+            id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let (players, transactions) = match connection
+        .run(move |c| {
+            let players = nuts::table
+                .inner_join(users::table)
+                .filter(fk_tournaments.eq(id))
+                .order(users::name)
+                .load::<(Nut, User)>(c)?;
+
+            let transactions = nut_transactions::table
+                .inner_join(nuts::table)
+                .filter(fk_tournaments.eq(id))
+                .select(nut_transactions::all_columns)
+                .order(nut_transactions::id.desc())
+                .load::<NutTransaction>(c)?;
+
+            diesel::result::QueryResult::Ok((players, transactions))
         })
         .await
     {
-        Ok(_) => (),
+        Ok(data) => data,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    let mut ledgers: HashMap<i32, Vec<NutTransaction>> = HashMap::new();
+    for transaction in transactions {
+        ledgers.entry(transaction.fk_nuts).or_default().push(transaction);
+    }
+
+    Ok(Json(
+        players
+            .into_iter()
+            .map(|(nut, user)| {
+                let transactions = ledgers.remove(&nut.id).unwrap_or_default();
+
+                NutAudit {
+                    fk_nuts: nut.id,
+                    fk_users: user.id,
+                    username: user.name,
+                    stock: nut.stock,
+                    ledger_stock: transactions.iter().map(|t| t.amount).sum(),
+                    transactions,
+                }
+            })
+            .collect(),
+    ))
+}
+
+// longest reason of an adjustment
+const MAX_REASON_LENGTH: usize = 255;
+
+// the owner of the tournament changes the stock of a player, the reason is kept in the ledger
+#[patch("/nut/<id>", data = "<data>")]
+pub async fn update_nut(
+    connection: MysqlConnection,
+    id: i32,
+    data: Json<PatchNut>,
+    auth: ApiAuth,
+) -> Result<Json<Nut>, (Status, Json<ErrorResponse>)> {
+    let nut = match connection
+        .run(move |c| nuts::table.find(id).first::<Nut>(c))
+        .await
+    {
+        Ok(nut) => nut,
         Err(_) => {
-            warn!(
-                "User {} tried to update the nut of tournament {} - routes/nut/update_nut()",
-                auth.user.id, id
-            );
-            return Err((Status::Forbidden, "Access Forbidden".to_string()));
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Nuts not found".into(),
+                    },
+                }),
+            ))
         }
     };
 
+    // Check if the caller is an owner of the tournament
+    if !is_owner(&connection, nut.fk_tournaments, &auth).await {
+        warn!(
+            "{} - User {} tried to update the nut {} - routes/nut/update_nut()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            auth.user.id,
+            id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let adjustment = data.0;
+    if adjustment.stock < 0 || adjustment.reason.trim().is_empty() {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The stock can't be negative and needs a reason".into(),
+                },
+            }),
+        ));
+    }
+
+    // the reason is kept in the ledger, in a VARCHAR(255)
+    if adjustment.reason.trim().chars().count() > MAX_REASON_LENGTH {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: format!(
+                        "The reason can't be longer than {} characters",
+                        MAX_REASON_LENGTH
+                    ),
+                },
+            }),
+        ));
+    }
+
     match connection
         .run(move |c| {
             c.transaction(|c| {
-                diesel::update(nuts::table.find(id))
-                    .set(data.0)
-                    .execute(c)?;
-
-                let nut = nuts::table
-                    .order(nuts::id.desc())
-                    .first::<Nut>(c)
-                    .map(Json)?;
+                let nut = nuts::table.find(id).for_update().first::<Nut>(c)?;
 
-                diesel::result::QueryResult::Ok(nut)
+                nut.record(
+                    c,
+                    TransactionKind::Adjustment,
+                    adjustment.stock - nut.stock,
+                    None,
+                    Some(adjustment.reason.trim().to_string()),
+                )
             })
         })
         .await
     {
         Ok(nut) => {
-            return Ok(nut);
+            info!(
+                "{} - User {} set the nut {} to {} - routes/nut/update_nut()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                auth.user.id,
+                id,
+                nut.stock
+            );
+            return Ok(Json(nut));
         }
 
-        Err(_e) => return Err((Status::InternalServerError, "No nuts found".to_string())),
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
     }
 }
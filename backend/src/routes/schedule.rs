@@ -0,0 +1,213 @@
+use crate::bracket::{Bracket, Layout};
+use crate::models::game::Game;
+use crate::models::tournament::Tournament;
+use crate::routes::auth::ApiAuth;
+use crate::scheduler::{self, Booking, Fixture, ScheduleConfig};
+use crate::schema::{games, tournaments};
+use crate::{ErrorBody, ErrorResponse, MysqlConnection};
+use chrono::Local;
+use diesel::prelude::*;
+use log::{info, warn};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+
+use super::tournoix::is_owner;
+
+// durations are in minutes, the timetable starts at the date of the tournament by default
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScheduleRequest {
+    pub start: Option<chrono::NaiveDateTime>,
+    pub match_duration: i32,
+    pub break_duration: i32,
+    pub courts: i32,
+    pub min_rest: i32,
+}
+
+// give a start time and a court to every game that has not started yet, around the games
+// already started or played
+#[post("/tournoix/<id>/schedule", data = "<data>")]
+pub async fn create_schedule(
+    connection: MysqlConnection,
+    data: Json<ScheduleRequest>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<Game>>, (Status, Json<ErrorResponse>)> {
+    if !is_owner(&connection, id, &auth).await {
+        warn!("{} - User {} tried to schedule the games of tournament {} - routes/schedule/create_schedule()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    if data.match_duration < 1 || data.break_duration < 0 || data.courts < 1 || data.min_rest < 0 {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The games need a duration and at least one court".into(),
+                },
+            }),
+        ));
+    }
+
+    let tournament = match connection
+        .run(move |c| tournaments::table.find(id).first::<Tournament>(c))
+        .await
+    {
+        Ok(tournament) => tournament,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    // a running tournament is not scheduled in the past
+    let config = ScheduleConfig {
+        start: data
+            .start
+            .unwrap_or_else(|| tournament.date.max(Local::now().naive_local())),
+        match_duration: data.match_duration as i64,
+        break_duration: data.break_duration as i64,
+        courts: data.courts,
+        min_rest: data.min_rest as i64,
+    };
+    let settings = data.0;
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                // the calendars need the end of the games, the next rounds are placed with the same settings
+                diesel::update(tournaments::table.find(id))
+                    .set((
+                        tournaments::match_duration.eq(Some(settings.match_duration)),
+                        tournaments::break_duration.eq(Some(settings.break_duration)),
+                        tournaments::courts.eq(Some(settings.courts)),
+                        tournaments::min_rest.eq(Some(settings.min_rest)),
+                    ))
+                    .execute(c)?;
+
+                schedule_games(c, &tournament, &config, true)?;
+
+                games::table
+                    .filter(games::fk_tournaments.eq(id))
+                    .order((games::scheduled_at, games::court))
+                    .load::<Game>(c)
+            })
+        })
+        .await
+    {
+        Ok(games) => {
+            info!(
+                "{} - User {} scheduled the games of tournament {} - routes/schedule/create_schedule()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                auth.user.id,
+                id
+            );
+            Ok(Json(games))
+        }
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// place the open games of a tournament around the games already played or scheduled,
+// `reschedule` moves the open games already scheduled too, to call in a transaction
+pub fn schedule_games(
+    c: &mut diesel::MysqlConnection,
+    tournament: &Tournament,
+    config: &ScheduleConfig,
+    reschedule: bool,
+) -> QueryResult<()> {
+    let games = games::table
+        .filter(games::fk_tournaments.eq(tournament.id))
+        .order(games::id)
+        .load::<Game>(c)?;
+
+    let first_round = games
+        .iter()
+        .filter(|g| g.bracket == Bracket::Upper as i32 && g.phase == 1)
+        .count();
+    let layout = Layout::new(first_round, tournament.is_double_elim)
+        .with_third_place(tournament.has_third_place);
+
+    // the elimination phase starts once the qualification games are over
+    let elim_offset = games
+        .iter()
+        .filter(|g| g.phase < 1)
+        .map(|g| g.round + 1)
+        .max()
+        .unwrap_or(0);
+    let wave = |g: &Game| match g.slot() {
+        Some(slot) => elim_offset + scheduler::elimination_wave(&layout, slot),
+        None => g.round,
+    };
+
+    let is_placed = |g: &Game| g.status == 0 && (reschedule || g.scheduled_at.is_none());
+    let (placed, kept): (Vec<&Game>, Vec<&Game>) = games
+        .iter()
+        .filter(|g| !g.is_bye())
+        .partition(|g| is_placed(g));
+
+    let booked: Vec<Booking> = kept
+        .iter()
+        .filter_map(|g| {
+            Some(Booking {
+                team1: g.fk_team1,
+                team2: g.fk_team2,
+                start: g.scheduled_at?,
+                court: g.court.unwrap_or(0),
+                wave: wave(g),
+            })
+        })
+        .collect();
+    let fixtures: Vec<Fixture> = placed
+        .iter()
+        .map(|g| Fixture {
+            id: g.id,
+            team1: g.fk_team1,
+            team2: g.fk_team2,
+            wave: wave(g),
+        })
+        .collect();
+
+    for slot in scheduler::schedule(config, &booked, &fixtures) {
+        diesel::update(games::table.find(slot.game_id))
+            .set((
+                games::scheduled_at.eq(Some(slot.start)),
+                games::court.eq(Some(slot.court)),
+            ))
+            .execute(c)?;
+    }
+
+    Ok(())
+}
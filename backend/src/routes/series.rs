@@ -0,0 +1,392 @@
+use crate::bracket::Bracket;
+use crate::events::{EventKind, Events};
+use crate::models::game::Game;
+use crate::models::series::*;
+use crate::models::tournament::Tournament;
+use crate::routes::auth::ApiAuth;
+use crate::schema::{game_sets, games, series_formats, tournaments};
+use crate::series::{self, SeriesError, SetScore};
+use crate::{ErrorBody, ErrorResponse, MysqlConnection};
+use chrono::Local;
+use diesel::prelude::*;
+use log::{info, warn};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::State;
+
+use super::game::is_owner_game;
+use super::tournoix::is_owner;
+
+// longest series a round can be played in
+const MAX_BEST_OF: i32 = 9;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AddSeriesFormat {
+    pub bracket: i32,
+    pub phase: i32,
+    pub best_of: i32,
+}
+
+async fn load_tournament(
+    connection: &MysqlConnection,
+    id: i32,
+) -> Result<Tournament, (Status, Json<ErrorResponse>)> {
+    match connection
+        .run(move |c| tournaments::table.find(id).first::<Tournament>(c))
+        .await
+    {
+        Ok(tournament) => Ok(tournament),
+        Err(_) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Tournament not found".into(),
+                },
+            }),
+        )),
+    }
+}
+
+// series length of each elimination round, rounds without one are played in a single game
+#[get("/tournoix/<id>/series")]
+pub async fn get_series_formats(
+    connection: MysqlConnection,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<SeriesFormat>>, (Status, Json<ErrorResponse>)> {
+    let tournament = load_tournament(&connection, id).await?;
+
+    if !tournament.user_has_rights(&connection, auth.user).await {
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    connection
+        .run(move |c| {
+            series_formats::table
+                .filter(series_formats::fk_tournaments.eq(id))
+                .load::<SeriesFormat>(c)
+        })
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!("{}", e);
+            (
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            )
+        })
+}
+
+// replace the series length of every round
+#[put("/tournoix/<id>/series", data = "<data>")]
+pub async fn update_series_formats(
+    connection: MysqlConnection,
+    data: Json<Vec<AddSeriesFormat>>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<SeriesFormat>>, (Status, Json<ErrorResponse>)> {
+    if !is_owner(&connection, id, &auth).await {
+        warn!("{} - User {} tried to change the series of tournament {} - routes/series/update_series_formats()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let is_valid = data.iter().all(|format| {
+        Bracket::from_i32(format.bracket).is_some()
+            && format.phase >= 1
+            && (1..=MAX_BEST_OF).contains(&format.best_of)
+    });
+
+    if !is_valid {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: format!(
+                        "A round is played in a series of 1 to {} games",
+                        MAX_BEST_OF
+                    ),
+                },
+            }),
+        ));
+    }
+
+    let formats: Vec<NewSeriesFormat> = data
+        .iter()
+        .map(|format| NewSeriesFormat {
+            fk_tournaments: id,
+            bracket: format.bracket,
+            phase: format.phase,
+            best_of: format.best_of,
+        })
+        .collect();
+
+    connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::delete(series_formats::table.filter(series_formats::fk_tournaments.eq(id)))
+                    .execute(c)?;
+
+                diesel::insert_into(series_formats::table)
+                    .values(formats)
+                    .execute(c)?;
+
+                series_formats::table
+                    .filter(series_formats::fk_tournaments.eq(id))
+                    .load::<SeriesFormat>(c)
+            })
+        })
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!("{}", e);
+            (
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            )
+        })
+}
+
+// sets played in the games of a tournament
+#[get("/tournoix/<id>/sets")]
+pub async fn get_tournament_sets(
+    connection: MysqlConnection,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<GameSet>>, (Status, Json<ErrorResponse>)> {
+    let tournament = load_tournament(&connection, id).await?;
+
+    if !tournament.user_has_rights(&connection, auth.user).await {
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    connection
+        .run(move |c| {
+            game_sets::table
+                .inner_join(games::table.on(game_sets::fk_games.eq(games::id)))
+                .filter(games::fk_tournaments.eq(id))
+                .select(game_sets::all_columns)
+                .order((game_sets::fk_games, game_sets::number))
+                .load::<GameSet>(c)
+        })
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!("{}", e);
+            (
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            )
+        })
+}
+
+// replace the sets of a game, the score of the game becomes the number of sets won
+#[put("/games/<id>/sets", data = "<data>")]
+pub async fn update_game_sets(
+    connection: MysqlConnection,
+    events: &State<Events>,
+    data: Json<Vec<SetScore>>,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<GameSet>>, (Status, Json<ErrorResponse>)> {
+    if !is_owner_game(&connection, id, &auth).await {
+        warn!("{} - User {} tried to change the sets of game {} - routes/series/update_game_sets()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbiden".into(),
+                    description: "Access Forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let game = match connection
+        .run(move |c| games::table.find(id).first::<Game>(c))
+        .await
+    {
+        Ok(game) => game,
+        Err(_) => {
+            return Err((
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 404,
+                        reason: "Not Found".into(),
+                        description: "Game not found".into(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    if game.status == 2 {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The game is already closed".into(),
+                },
+            }),
+        ));
+    }
+
+    let best_of = game_best_of(&connection, &game).await;
+    let sets = data.0;
+
+    if let Err(e) = series::check_sets(best_of, &sets) {
+        let description = match e {
+            SeriesError::TooManySets => format!("The game is played in {} sets at most", best_of),
+            SeriesError::AlreadyDecided => "A team already won the series".to_string(),
+        };
+
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "invalid_sets".into(),
+                    description,
+                },
+            }),
+        ));
+    }
+
+    let (score1, score2) = series::series_score(&sets);
+    let new_sets: Vec<NewGameSet> = sets
+        .iter()
+        .enumerate()
+        .map(|(index, set)| NewGameSet {
+            fk_games: id,
+            number: index as i32 + 1,
+            score1: set.score1,
+            score2: set.score2,
+        })
+        .collect();
+
+    let game_sets = connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::delete(game_sets::table.filter(game_sets::fk_games.eq(id))).execute(c)?;
+
+                diesel::insert_into(game_sets::table)
+                    .values(new_sets)
+                    .execute(c)?;
+
+                diesel::update(games::table.find(id))
+                    .set((games::score1.eq(score1), games::score2.eq(score2)))
+                    .execute(c)?;
+
+                game_sets::table
+                    .filter(game_sets::fk_games.eq(id))
+                    .order(game_sets::number)
+                    .load::<GameSet>(c)
+            })
+        })
+        .await
+        .map_err(|e| {
+            warn!("{}", e);
+            (
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internel Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            )
+        })?;
+
+    info!("{} - User {} updated the sets of game {} - routes/series/update_game_sets()", Local::now().format("%d/%m/%Y %H:%M"), auth.user.id, id);
+    events.send(game.fk_tournaments, EventKind::GameUpdated, Some(id));
+
+    Ok(Json(game_sets))
+}
+
+// series length of the round of a game, qualification games are played once
+pub async fn game_best_of(connection: &MysqlConnection, game: &Game) -> i32 {
+    if game.phase < 1 {
+        return 1;
+    }
+
+    let (tournament_id, bracket, phase) = (game.fk_tournaments, game.bracket, game.phase);
+
+    connection
+        .run(move |c| {
+            series_formats::table
+                .filter(series_formats::fk_tournaments.eq(tournament_id))
+                .filter(series_formats::bracket.eq(bracket))
+                .filter(series_formats::phase.eq(phase))
+                .select(series_formats::best_of)
+                .first::<i32>(c)
+        })
+        .await
+        .unwrap_or(1)
+}
+
+pub async fn load_game_sets(connection: &MysqlConnection, game_id: i32) -> Vec<SetScore> {
+    connection
+        .run(move |c| {
+            game_sets::table
+                .filter(game_sets::fk_games.eq(game_id))
+                .order(game_sets::number)
+                .load::<GameSet>(c)
+        })
+        .await
+        .map(|sets| sets.iter().map(|set| set.score()).collect())
+        .unwrap_or_default()
+}
@@ -160,14 +160,11 @@ pub async fn create_subsciption(
         Ok(_) => (), // User already has received nuts for this tournament
         Err(_) => {
             // add the nuts to the user
-            let nut = NewNut {
-                fk_users: auth.user.id,
-                fk_tournaments: tournament.id,
-                stock: 20,
-            };
+            let user_id = auth.user.id;
+            let tournament_id = tournament.id;
 
             match connection
-                .run(move |c| diesel::insert_into(nuts::table).values(nut).execute(c))
+                .run(move |c| c.transaction(|c| NewNut::grant(c, user_id, tournament_id)))
                 .await
             {
                 Ok(_) => (),
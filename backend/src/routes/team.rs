@@ -1,3 +1,4 @@
+use crate::events::{EventKind, Events};
 use crate::models::game::Game;
 use crate::models::subscription::Subscription;
 use crate::models::team::*;
@@ -10,8 +11,10 @@ use log::warn;
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
+use rocket::State;
 
 use super::auth::ApiAuth;
+use super::market::refund_champion_bets;
 use super::tournoix::tournament_is_started;
 
 // get all team from a tournament
@@ -78,6 +81,7 @@ pub struct AddTeam {
 #[post("/tournoix/<id>/teams", data = "<data>")]
 pub async fn create_team(
     connection: MysqlConnection,
+    events: &State<Events>,
     data: Json<AddTeam>,
     id: i32,
     auth: ApiAuth,
@@ -151,6 +155,7 @@ pub async fn create_team(
         .await
     {
         Ok(team) => {
+            events.send(id, EventKind::TeamsChanged, None);
             return Ok(team);
         }
 
@@ -172,6 +177,7 @@ pub async fn create_team(
 #[patch("/teams/<id>", data = "<data>")]
 pub async fn update_team(
     connection: MysqlConnection,
+    events: &State<Events>,
     data: Json<PatchTeam>,
     id: i32,
     auth: ApiAuth,
@@ -241,6 +247,7 @@ pub async fn update_team(
                 .await
             {
                 Ok(team) => {
+                    events.send(tournament.id, EventKind::TeamsChanged, None);
                     return Ok(team);
                 }
 
@@ -276,6 +283,7 @@ pub async fn update_team(
 #[delete("/teams/<id>")]
 pub async fn delete_team(
     connection: MysqlConnection,
+    events: &State<Events>,
     id: i32,
     auth: ApiAuth,
 ) -> Result<Json<EmptyResponse>, (Status, Json<ErrorResponse>)> {
@@ -337,10 +345,18 @@ pub async fn delete_team(
             }
 
             match connection
-                .run(move |c| diesel::delete(teams::table.find(&id)).execute(c))
+                .run(move |c| {
+                    c.transaction(|c| {
+                        // the bets on the team to win the tournament are given back
+                        refund_champion_bets(c, id)?;
+
+                        diesel::delete(teams::table.find(&id)).execute(c)
+                    })
+                })
                 .await
             {
                 Ok(_) => {
+                    events.send(tournament.id, EventKind::TeamsChanged, None);
                     return Ok(Json(EmptyResponse()));
                 }
 
@@ -1,14 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use crate::bracket::{Bracket, Layout};
 use crate::models::game::Game;
 use crate::models::nut::{NewNut, Nut};
 use crate::models::subscription::{self, Subscription};
 use crate::models::team::Team;
-use crate::models::tournament::{NewTournament, PatchTournament, Tournament, Results, Score};
+use crate::models::tournament::{NewTournament, PatchTournament, Tournament, Results, Score, TeamResult};
 use crate::models::user::User;
+use crate::ranking::{self, EliminationResult};
 use crate::routes::auth::ApiAuth;
+use crate::routes::game::{group_standings, load_qualification, load_swiss_tournament, swiss_results, Phase};
 use crate::schema::bets::nb_nut;
 use crate::schema::{games, nuts, teams, tournaments, subscriptions, users};
+use crate::seeding;
+use crate::standings::{GameResult, Standing};
+use crate::swiss::{self, SwissStanding};
 use crate::{EmptyResponse, ErrorBody, ErrorResponse, MysqlConnection};
 use diesel::prelude::*;
 use diesel::result::Error;
@@ -171,11 +177,99 @@ pub async fn get_tournoix(
                         }
                     };
 
-                    // games_in_tournament
-                    // teams_in_tournament
+                    let results: Vec<GameResult> = games_in_tournament
+                        .iter()
+                        .filter(|g| g.status == 2 && !g.is_bye())
+                        .map(|g| GameResult {
+                            team1: g.fk_team1,
+                            team2: g.fk_team2,
+                            score1: g.score1,
+                            score2: g.score2,
+                        })
+                        .collect();
+                    let qualification_games: Vec<Game> = games_in_tournament
+                        .iter()
+                        .filter(|g| g.phase == Phase::Qualification as i32)
+                        .cloned()
+                        .collect();
+                    let elimination_games: Vec<&Game> = games_in_tournament
+                        .iter()
+                        .filter(|g| g.slot().is_some())
+                        .collect();
+
+                    // best qualification first, for the teams out of the elimination phase
+                    let mut qualification: Vec<i32> = if tournoi.is_swiss {
+                        let team_ids: Vec<i32> = teams_in_tournament.iter().map(|t| t.id).collect();
+
+                        swiss::standings(&team_ids, &swiss_results(&qualification_games), &tournoi.points())
+                            .iter()
+                            .map(|s| s.team_id)
+                            .collect()
+                    } else {
+                        let mut groups: BTreeMap<i32, Vec<Standing>> = BTreeMap::new();
+                        for team in teams_in_tournament.iter().filter(|t| t.group != 0) {
+                            groups.entry(team.group).or_insert_with(|| {
+                                group_standings(&tournoi, &teams_in_tournament, &qualification_games, team.group)
+                            });
+                        }
 
-                    // TODO
-                    let teams = vec![];
+                        seeding::qualifiers(&groups, teams_in_tournament.len(), 0, tournoi.id as u64)
+                            .iter()
+                            .map(|q| q.team_id)
+                            .collect()
+                    };
+                    // teams without group
+                    for team in teams_in_tournament.iter() {
+                        if !qualification.contains(&team.id) {
+                            qualification.push(team.id);
+                        }
+                    }
+
+                    let first_round = elimination_games
+                        .iter()
+                        .filter(|g| g.bracket == Bracket::Upper as i32 && g.phase == 1)
+                        .count();
+                    let layout = Layout::new(first_round, tournoi.is_double_elim)
+                        .with_third_place(tournoi.has_third_place);
+
+                    let mut entrants: Vec<i32> = elimination_games
+                        .iter()
+                        .flat_map(|g| [g.fk_team1, g.fk_team2])
+                        .collect();
+                    entrants.sort();
+                    entrants.dedup();
+
+                    let eliminations: Vec<EliminationResult> = elimination_games
+                        .iter()
+                        .filter(|g| g.status == 2 && !g.is_bye())
+                        .filter_map(|g| {
+                            Some(EliminationResult {
+                                slot: g.slot()?,
+                                winner: g.winner()?,
+                                loser: g.loser()?,
+                            })
+                        })
+                        .collect();
+
+                    let names: HashMap<i32, String> = teams_in_tournament
+                        .into_iter()
+                        .map(|t| (t.id, t.name))
+                        .collect();
+
+                    let teams: Vec<TeamResult> = ranking::ranking(&layout, &entrants, &eliminations, &qualification, &results)
+                        .into_iter()
+                        .map(|p| TeamResult {
+                            team_id: p.team_id,
+                            name: names.get(&p.team_id).cloned().unwrap_or_default(),
+                            place: p.place,
+                            played: p.played,
+                            won: p.won,
+                            drawn: p.drawn,
+                            lost: p.lost,
+                            scored: p.scored,
+                            conceded: p.conceded,
+                        })
+                        .collect();
 
                     Ok(Json(Results {
                         subscribers,
@@ -212,6 +306,59 @@ pub async fn get_tournoix(
         }
     }
 
+// standings of a qualification group, best team first
+#[get("/tournoix/<id>/groups/<group>/standings")]
+pub async fn get_group_standings(
+    connection: MysqlConnection,
+    id: i32,
+    group: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<Standing>>, (Status, Json<ErrorResponse>)> {
+    let (tournament, teams, games) = load_qualification(&connection, id).await?;
+
+    if !tournament.user_has_rights(&connection, auth.user).await {
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Access forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    Ok(Json(group_standings(&tournament, &teams, &games, group)))
+}
+
+// standings of a swiss tournament, best team first
+#[get("/tournoix/<id>/swiss/standings")]
+pub async fn get_swiss_standings(
+    connection: MysqlConnection,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<Vec<SwissStanding>>, (Status, Json<ErrorResponse>)> {
+    let (tournament, teams, games) = load_swiss_tournament(&connection, id).await?;
+
+    if !tournament.user_has_rights(&connection, auth.user).await {
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Access forbidden".into(),
+                },
+            }),
+        ));
+    }
+
+    let team_ids: Vec<i32> = teams.iter().map(|t| t.id).collect();
+
+    Ok(Json(swiss::standings(&team_ids, &swiss_results(&games), &tournament.points())))
+}
+
 #[get("/tournoix_by_code/<code>")]
     pub async fn get_tournoix_by_code(
         connection: MysqlConnection,
@@ -251,6 +398,16 @@ pub struct AddTournament {
     pub is_qualif: bool,
     pub is_elim: bool,
     pub is_closed: bool,
+    pub is_double_elim: bool,
+    pub has_bracket_reset: bool,
+    pub is_swiss: bool,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: i32,
+    pub points_draw: i32,
+    pub points_loss: i32,
+    pub qualified_per_group: i32,
+    pub wildcards: i32,
+    pub has_third_place: bool,
 }
 
 #[post("/tournoix", data = "<data>")]
@@ -318,6 +475,16 @@ pub async fn create_tournoix(
         is_qualif: add_tournoix.is_qualif,
         is_elim: add_tournoix.is_elim,
         is_closed: add_tournoix.is_closed,
+        is_double_elim: add_tournoix.is_double_elim,
+        has_bracket_reset: add_tournoix.has_bracket_reset,
+        is_swiss: add_tournoix.is_swiss,
+        swiss_rounds: add_tournoix.swiss_rounds,
+        points_win: add_tournoix.points_win,
+        points_draw: add_tournoix.points_draw,
+        points_loss: add_tournoix.points_loss,
+        qualified_per_group: add_tournoix.qualified_per_group,
+        wildcards: add_tournoix.wildcards,
+        has_third_place: add_tournoix.has_third_place,
     };
 
     match connection
@@ -346,14 +513,11 @@ pub async fn create_tournoix(
             );
 
             // Add nuts to tournament owner
-            let nut = NewNut {
-                fk_users: auth.user.id,
-                fk_tournaments: tournoix.id,
-                stock: 20,
-            };
+            let user_id = auth.user.id;
+            let tournament_id = tournoix.id;
 
             match connection
-                .run(move |c| diesel::insert_into(nuts::table).values(nut).execute(c))
+                .run(move |c| c.transaction(|c| NewNut::grant(c, user_id, tournament_id)))
                 .await
             {
                 Ok(_) => (),
@@ -434,7 +598,11 @@ pub async fn update_tournoix(
     if tournament_is_started(&connection, id).await
         && ((tournoix.is_elim.is_some() && tournoix.is_elim.unwrap() != actual_tournoix.is_elim)
             || (tournoix.is_qualif.is_some()
-                && tournoix.is_qualif.unwrap() != actual_tournoix.is_qualif))
+                && tournoix.is_qualif.unwrap() != actual_tournoix.is_qualif)
+            || (tournoix.is_double_elim.is_some()
+                && tournoix.is_double_elim.unwrap() != actual_tournoix.is_double_elim)
+            || (tournoix.is_swiss.is_some()
+                && tournoix.is_swiss.unwrap() != actual_tournoix.is_swiss))
     {
         return Err((
             Status::BadRequest,
@@ -448,6 +616,60 @@ pub async fn update_tournoix(
         ));
     }
 
+    // the layout of a generated bracket depends on the third place game
+    if tournoix.has_third_place.is_some_and(|third| third != actual_tournoix.has_third_place) {
+        let elim_games = match connection
+            .run(move |c| {
+                games::table
+                    .filter(games::fk_tournaments.eq(id))
+                    .filter(games::phase.gt(0))
+                    .count()
+                    .get_result::<i64>(c)
+            })
+            .await
+        {
+            Ok(count) => count,
+            Err(_e) => {
+                return Err((
+                    Status::InternalServerError,
+                    Json(ErrorResponse {
+                        error: ErrorBody {
+                            code: 500,
+                            reason: "Internal Server Error".into(),
+                            description: "An error occured".into(),
+                        },
+                    }),
+                ))
+            }
+        };
+
+        if elim_games > 0 {
+            return Err((
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 400,
+                        reason: "Bad Request".into(),
+                        description: "Can't change the third place game once the elimination phase is generated".into(),
+                    },
+                }),
+            ));
+        }
+    }
+
+    if tournoix.betting_lead_time.is_some_and(|lead_time| lead_time < 0) {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "Bets can't close after the start of the games".into(),
+                },
+            }),
+        ));
+    }
+
     match connection
         .run(move |c| {
             c.transaction(|c| {
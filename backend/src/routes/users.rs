@@ -1,9 +1,19 @@
-use crate::models::user::UserInfo;
-use crate::schema::users;
+use std::sync::Arc;
+
+use crate::crypto::{self, HashConfig};
+use crate::mailer::Mailer;
+use crate::models::token::TokenPurpose;
+use crate::models::user::{PatchUser, User, UserInfo};
+use crate::routes::auth::send_verification_mail;
+use crate::models::session::{Session, SessionInfo};
+use crate::schema::{sessions, tokens, users};
 use crate::{routes::auth::ApiAuth, ErrorBody};
 use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
 use rocket::http::Status;
 use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::State;
 use chrono::Local;
 
 use crate::{ErrorResponse, MysqlConnection};
@@ -31,7 +41,7 @@ pub async fn get_user(
     match connection
         .run(move |c| {
             users::table
-                .select((users::id, users::name, users::email))
+                .select((users::id, users::name, users::email, users::email_verified))
                 .find(id)
                 .first::<UserInfo>(c)
         })
@@ -59,3 +69,516 @@ pub async fn get_user(
 pub async fn get_current_user(auth: ApiAuth) -> Result<Json<UserInfo>, (Status, Json<ErrorResponse>)> {
     Ok(Json(auth.user))
 }
+
+// change the name or the email of the user, a new email has to be confirmed again
+#[patch("/users/@me", data = "<data>")]
+pub async fn update_current_user(
+    connection: MysqlConnection,
+    mailer: &State<Arc<dyn Mailer>>,
+    data: Json<PatchUser>,
+    auth: ApiAuth,
+) -> Result<Json<UserInfo>, (Status, Json<ErrorResponse>)> {
+    let patch = PatchUser {
+        name: data.name.as_ref().map(|n| n.trim().to_string()),
+        email: data.email.as_ref().map(|e| e.trim().to_string()),
+    };
+
+    if patch.name.as_ref().is_some_and(|n| n.is_empty())
+        || patch.email.as_ref().is_some_and(|e| !e.contains('@'))
+    {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Bad Request".into(),
+                    description: "The name can't be empty and the email must be valid".into(),
+                },
+            }),
+        ));
+    }
+
+    let user_id = auth.user.id;
+    let is_new_email = patch.email.as_ref().is_some_and(|e| *e != auth.user.email);
+
+    let user = match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                if let Some(new_email) = &patch.email {
+                    let used = users::table
+                        .filter(users::email.eq(new_email))
+                        .filter(users::id.ne(user_id))
+                        .count()
+                        .get_result::<i64>(c)?;
+
+                    if used > 0 {
+                        return Ok(None);
+                    }
+                }
+
+                if patch.name.is_some() || patch.email.is_some() {
+                    diesel::update(users::table.find(user_id))
+                        .set(&patch)
+                        .execute(c)?;
+                }
+
+                if is_new_email {
+                    diesel::update(users::table.find(user_id))
+                        .set(users::email_verified.eq(false))
+                        .execute(c)?;
+
+                    // the reset links went to the old address
+                    diesel::delete(
+                        tokens::table
+                            .filter(tokens::fk_users.eq(user_id))
+                            .filter(tokens::purpose.eq(TokenPurpose::PasswordReset as i32)),
+                    )
+                    .execute(c)?;
+                }
+
+                users::table
+                    .find(user_id)
+                    .select((users::id, users::name, users::email, users::email_verified))
+                    .first::<UserInfo>(c)
+                    .map(Some)
+            })
+        })
+        .await
+    {
+        Ok(Some(user)) => user,
+
+        // two users taking the same address at once, the unique index refuses the second one
+        Ok(None) | Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            return Err((
+                Status::Conflict,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 409,
+                        reason: "Conflict".into(),
+                        description: "Email already used".into(),
+                    },
+                }),
+            ))
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    info!(
+        "{} - User {} updated their profile - routes/users/update_current_user()",
+        Local::now().format("%d/%m/%Y %H:%M"),
+        user.id
+    );
+
+    if is_new_email {
+        send_verification_mail(&connection, mailer.inner().clone(), user.clone()).await;
+    }
+
+    Ok(Json(user))
+}
+
+#[derive(Deserialize, Clone, Serialize, Debug)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+// change the password of the user, the other sessions are logged out
+#[post("/users/@me/password", data = "<data>")]
+pub async fn change_password(
+    connection: MysqlConnection,
+    hash_config: &State<HashConfig>,
+    data: Json<ChangePasswordRequest>,
+    auth: ApiAuth,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+    let user = match connection
+        .run(move |c| users::table.find(user_id).first::<User>(c))
+        .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    if !crypto::verify_password(&user.password, &data.current_password) {
+        warn!(
+            "{} - User {} tried to change their password with an incorrect one - routes/users/change_password()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            user_id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Current password incorrect".into(),
+                },
+            }),
+        ));
+    }
+
+    // the rules broken are sent back for the UI to explain them
+    let violations = crypto::password_violations(&data.new_password);
+    if !violations.is_empty() {
+        return Err((
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 400,
+                    reason: "Weak Password".into(),
+                    description: violations
+                        .iter()
+                        .map(|rule| rule.code())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                },
+            }),
+        ));
+    }
+
+    let hashed_password = match crypto::hash_password(&data.new_password, hash_config) {
+        Ok(hashed_password) => hashed_password,
+        Err(_e) => {
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error has occured".to_string(),
+                    },
+                }),
+            ))
+        }
+    };
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::update(users::table.find(user_id))
+                    .set(users::password.eq(hashed_password))
+                    .execute(c)?;
+
+                // the session changing the password stays logged in, a pending reset link stops working
+                diesel::delete(
+                    sessions::table
+                        .filter(sessions::fk_users.eq(user_id))
+                        // no session id is 0, a request without session logs out all of them
+                        .filter(sessions::id.ne(auth.session.unwrap_or(0))),
+                )
+                .execute(c)?;
+                diesel::delete(
+                    tokens::table
+                        .filter(tokens::fk_users.eq(user_id))
+                        .filter(tokens::token.ne(auth.token))
+                        .filter(tokens::purpose.ne(TokenPurpose::EmailVerification as i32)),
+                )
+                .execute(c)
+            })
+        })
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "{} - User {} changed their password - routes/users/change_password()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user_id
+            );
+            Ok(Json("Password changed".into()))
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Serialize, Debug)]
+pub struct DeleteUserRequest {
+    pub password: String,
+}
+
+// name shown instead of the one of a deleted user
+const DELETED_USER_NAME: &str = "Utilisateur supprimé";
+
+// delete the account of the user
+//
+// The row is kept with its personal data wiped so the tournaments, the bets and
+// the nut history of the user stay consistent. The empty hash matches no
+// password, nobody can log in to the account again.
+#[delete("/users/@me", data = "<data>")]
+pub async fn delete_current_user(
+    connection: MysqlConnection,
+    data: Json<DeleteUserRequest>,
+    auth: ApiAuth,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+    let user = match connection
+        .run(move |c| users::table.find(user_id).first::<User>(c))
+        .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            warn!("{}", e);
+            return Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ));
+        }
+    };
+
+    if !crypto::verify_password(&user.password, &data.password) {
+        warn!(
+            "{} - User {} tried to delete their account with an incorrect password - routes/users/delete_current_user()",
+            Local::now().format("%d/%m/%Y %H:%M"),
+            user_id
+        );
+        return Err((
+            Status::Forbidden,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 403,
+                    reason: "Forbidden".into(),
+                    description: "Password incorrect".into(),
+                },
+            }),
+        ));
+    }
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::update(users::table.find(user_id))
+                    .set((
+                        users::name.eq(DELETED_USER_NAME),
+                        // unique, the old address is free to register again
+                        users::email.eq(format!("deleted-{}@tournoix.invalid", user_id)),
+                        users::password.eq(""),
+                        users::email_verified.eq(false),
+                    ))
+                    .execute(c)?;
+
+                diesel::delete(sessions::table.filter(sessions::fk_users.eq(user_id))).execute(c)?;
+                diesel::delete(tokens::table.filter(tokens::fk_users.eq(user_id))).execute(c)
+            })
+        })
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "{} - User {} deleted their account - routes/users/delete_current_user()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user_id
+            );
+            Ok(Json("Account deleted".into()))
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// devices the user is logged in on, latest used first
+#[get("/users/@me/sessions")]
+pub async fn get_sessions(
+    connection: MysqlConnection,
+    auth: ApiAuth,
+) -> Result<Json<Vec<SessionInfo>>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+
+    match connection
+        .run(move |c| {
+            sessions::table
+                .filter(sessions::fk_users.eq(user_id))
+                .filter(sessions::expiration_date.gt(Local::now().naive_local()))
+                .order(sessions::last_used_at.desc())
+                .load::<Session>(c)
+        })
+        .await
+    {
+        Ok(sessions) => Ok(Json(
+            sessions
+                .into_iter()
+                .map(|session| SessionInfo {
+                    id: session.id,
+                    user_agent: session.user_agent,
+                    created_at: session.created_at,
+                    last_used_at: session.last_used_at,
+                    is_current: Some(session.id) == auth.session,
+                })
+                .collect(),
+        )),
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// log out a device of the user
+#[delete("/users/@me/sessions/<id>")]
+pub async fn delete_session(
+    connection: MysqlConnection,
+    id: i32,
+    auth: ApiAuth,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+
+    match connection
+        .run(move |c| {
+            diesel::delete(
+                sessions::table
+                    .filter(sessions::id.eq(id))
+                    .filter(sessions::fk_users.eq(user_id)),
+            )
+            .execute(c)
+        })
+        .await
+    {
+        Ok(0) => Err((
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: ErrorBody {
+                    code: 404,
+                    reason: "Not Found".into(),
+                    description: "Session not found".into(),
+                },
+            }),
+        )),
+
+        Ok(_) => {
+            info!(
+                "{} - User {} logged out the session {} - routes/users/delete_session()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user_id,
+                id
+            );
+            Ok(Json("Session deleted".into()))
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+// log out every device of the user, the one of the request included
+#[delete("/users/@me/sessions")]
+pub async fn delete_all_sessions(
+    connection: MysqlConnection,
+    auth: ApiAuth,
+) -> Result<Json<String>, (Status, Json<ErrorResponse>)> {
+    let user_id = auth.user.id;
+
+    match connection
+        .run(move |c| {
+            c.transaction(|c| {
+                diesel::delete(sessions::table.filter(sessions::fk_users.eq(user_id))).execute(c)?;
+
+                // the tokens given before the sessions existed
+                diesel::delete(
+                    tokens::table
+                        .filter(tokens::fk_users.eq(user_id))
+                        .filter(tokens::purpose.eq(TokenPurpose::Session as i32)),
+                )
+                .execute(c)
+            })
+        })
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "{} - User {} logged out everywhere - routes/users/delete_all_sessions()",
+                Local::now().format("%d/%m/%Y %H:%M"),
+                user_id
+            );
+            Ok(Json("Sessions deleted".into()))
+        }
+
+        Err(e) => {
+            warn!("{}", e);
+            Err((
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: ErrorBody {
+                        code: 500,
+                        reason: "Internal Server Error".into(),
+                        description: "An error occured".into(),
+                    },
+                }),
+            ))
+        }
+    }
+}
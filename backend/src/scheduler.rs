@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::bracket::{Bracket, Layout, Slot};
+
+// Settings of a timetable, durations are in minutes
+#[derive(Clone, Copy, Debug)]
+pub struct ScheduleConfig {
+    pub start: NaiveDateTime,
+    pub match_duration: i64,
+    // time between two games on the same court
+    pub break_duration: i64,
+    pub courts: i32,
+    // time a team rests between two of its games
+    pub min_rest: i64,
+}
+
+// Game to place in the timetable
+#[derive(Clone, Copy, Debug)]
+pub struct Fixture {
+    pub id: i32,
+    pub team1: i32,
+    pub team2: i32,
+    // games of a wave start once every game of the previous waves is over
+    pub wave: i32,
+}
+
+// Game already in the timetable, played or kept where it is
+#[derive(Clone, Copy, Debug)]
+pub struct Booking {
+    pub team1: i32,
+    pub team2: i32,
+    pub start: NaiveDateTime,
+    // courts are numbered from 1
+    pub court: i32,
+    pub wave: i32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TimeSlot {
+    pub game_id: i32,
+    pub start: NaiveDateTime,
+    // courts are numbered from 1
+    pub court: i32,
+}
+
+/// Order in which the elimination rounds can be played
+///
+/// A lower bracket round waits for the upper round whose losers drop in, the
+/// grand final for both bracket finals and the third place game is played
+/// alongside the final.
+pub fn elimination_wave(layout: &Layout, slot: Slot) -> i32 {
+    match slot.bracket {
+        Bracket::Upper => slot.phase,
+        Bracket::Lower => slot.phase + 1,
+        Bracket::GrandFinal => 2 * layout.rounds + slot.phase - 1,
+        Bracket::ThirdPlace => layout.rounds,
+    }
+}
+
+/// Whether the bets on a game starting at `start` are closed at `now`
+///
+/// Bets close `lead_time` minutes before the game, at its start when the lead
+/// time is 0.
+pub fn is_betting_over(now: NaiveDateTime, start: NaiveDateTime, lead_time: i64) -> bool {
+    now >= start - Duration::minutes(lead_time)
+}
+
+/// Give a start time and a court to every fixture
+///
+/// Waves are played one after the other. Inside a wave the fixture that can
+/// start the earliest is placed first, on the court that frees up the
+/// earliest, so a team never plays two games at once and always rests at
+/// least `min_rest` minutes between them. The booked games keep their slot:
+/// their courts and teams are busy until they are over, and a wave starts
+/// after the booked games of the previous waves.
+pub fn schedule(
+    config: &ScheduleConfig,
+    booked: &[Booking],
+    fixtures: &[Fixture],
+) -> Vec<TimeSlot> {
+    let match_duration = Duration::minutes(config.match_duration);
+    let break_duration = Duration::minutes(config.break_duration);
+    let min_rest = Duration::minutes(config.min_rest);
+
+    let mut courts = vec![config.start; config.courts.max(1) as usize];
+    let mut teams: HashMap<i32, NaiveDateTime> = HashMap::new();
+    let mut slots = vec![];
+
+    for booking in booked {
+        let end = booking.start + match_duration;
+
+        if let Some(free) = courts.get_mut((booking.court - 1).max(0) as usize) {
+            *free = (*free).max(end + break_duration);
+        }
+        for team in [booking.team1, booking.team2] {
+            let ready = teams.entry(team).or_insert(end + min_rest);
+            *ready = (*ready).max(end + min_rest);
+        }
+    }
+
+    let mut waves: Vec<i32> = fixtures.iter().map(|f| f.wave).collect();
+    waves.sort();
+    waves.dedup();
+
+    // end of the last game placed so far
+    let mut last_end: Option<NaiveDateTime> = None;
+
+    for wave in waves.iter() {
+        let previous_end = booked
+            .iter()
+            .filter(|b| b.wave < *wave)
+            .map(|b| b.start + match_duration)
+            .chain(last_end)
+            .max();
+        let barrier = match previous_end {
+            Some(end) => config.start.max(end + break_duration),
+            None => config.start,
+        };
+        let mut remaining: Vec<&Fixture> = fixtures.iter().filter(|f| f.wave == *wave).collect();
+
+        while !remaining.is_empty() {
+            // earliest start of each fixture and the court it would be played on
+            let (position, start, court) = remaining
+                .iter()
+                .enumerate()
+                .map(|(position, fixture)| {
+                    let ready = [
+                        barrier,
+                        *teams.get(&fixture.team1).unwrap_or(&config.start),
+                        *teams.get(&fixture.team2).unwrap_or(&config.start),
+                    ]
+                    .into_iter()
+                    .max()
+                    .unwrap();
+
+                    let (court, free) = courts
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, free)| (**free).max(ready))
+                        .unwrap();
+
+                    (position, (*free).max(ready), court)
+                })
+                .min_by_key(|(position, start, _)| (*start, *position))
+                .unwrap();
+
+            let fixture = remaining.remove(position);
+            let end = start + match_duration;
+
+            courts[court] = end + break_duration;
+            teams.insert(fixture.team1, end + min_rest);
+            teams.insert(fixture.team2, end + min_rest);
+            last_end = Some(last_end.map_or(end, |last_end| last_end.max(end)));
+
+            slots.push(TimeSlot {
+                game_id: fixture.id,
+                start,
+                court: court as i32 + 1,
+            });
+        }
+    }
+
+    slots
+}
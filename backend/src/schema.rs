@@ -7,6 +7,17 @@ diesel::table! {
         fk_teams -> Integer,
         fk_users -> Integer,
         nb_nut -> Integer,
+        payout -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    game_sets (id) {
+        id -> Integer,
+        fk_games -> Integer,
+        number -> Integer,
+        score1 -> Integer,
+        score2 -> Integer,
     }
 }
 
@@ -22,6 +33,41 @@ diesel::table! {
         place -> Integer,
         status -> Integer,
         has_gained_nut -> Bool,
+        bracket -> Integer,
+        round -> Integer,
+        tiebreak -> Integer,
+        tiebreak_winner -> Integer,
+        scheduled_at -> Nullable<Datetime>,
+        court -> Nullable<Integer>,
+        updated_at -> Datetime,
+    }
+}
+
+diesel::table! {
+    market_bets (id) {
+        id -> Integer,
+        market -> Integer,
+        fk_tournaments -> Integer,
+        fk_users -> Integer,
+        fk_games -> Nullable<Integer>,
+        fk_teams -> Nullable<Integer>,
+        score1 -> Nullable<Integer>,
+        score2 -> Nullable<Integer>,
+        nb_nut -> Integer,
+        payout -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    nut_transactions (id) {
+        id -> Integer,
+        fk_nuts -> Integer,
+        kind -> Integer,
+        amount -> Integer,
+        balance -> Integer,
+        fk_games -> Nullable<Integer>,
+        reason -> Nullable<Varchar>,
+        created_at -> Datetime,
     }
 }
 
@@ -34,6 +80,40 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    seasons (id) {
+        id -> Integer,
+        #[max_length = 255]
+        name -> Varchar,
+        start_date -> Datetime,
+        end_date -> Datetime,
+    }
+}
+
+diesel::table! {
+    series_formats (id) {
+        id -> Integer,
+        fk_tournaments -> Integer,
+        bracket -> Integer,
+        phase -> Integer,
+        best_of -> Integer,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Integer,
+        fk_users -> Integer,
+        #[max_length = 255]
+        refresh_token -> Varchar,
+        #[max_length = 255]
+        user_agent -> Nullable<Varchar>,
+        created_at -> Datetime,
+        last_used_at -> Datetime,
+        expiration_date -> Datetime,
+    }
+}
+
 diesel::table! {
     subscriptions (id) {
         id -> Integer,
@@ -59,6 +139,8 @@ diesel::table! {
         fk_users -> Integer,
         created_at -> Datetime,
         expiration_date -> Datetime,
+        purpose -> Integer,
+        fk_sessions -> Nullable<Integer>,
     }
 }
 
@@ -80,6 +162,21 @@ diesel::table! {
         is_qualif -> Bool,
         is_elim -> Bool,
         is_closed -> Bool,
+        is_double_elim -> Bool,
+        has_bracket_reset -> Bool,
+        is_swiss -> Bool,
+        swiss_rounds -> Nullable<Integer>,
+        points_win -> Integer,
+        points_draw -> Integer,
+        points_loss -> Integer,
+        qualified_per_group -> Integer,
+        wildcards -> Integer,
+        has_third_place -> Bool,
+        match_duration -> Nullable<Integer>,
+        betting_lead_time -> Integer,
+        break_duration -> Nullable<Integer>,
+        courts -> Nullable<Integer>,
+        min_rest -> Nullable<Integer>,
     }
 }
 
@@ -92,25 +189,42 @@ diesel::table! {
         email -> Varchar,
         #[max_length = 255]
         password -> Varchar,
+        email_verified -> Bool,
     }
 }
 
 diesel::joinable!(bets -> games (fk_games));
 diesel::joinable!(bets -> teams (fk_teams));
 diesel::joinable!(bets -> users (fk_users));
+diesel::joinable!(game_sets -> games (fk_games));
 diesel::joinable!(games -> tournaments (fk_tournaments));
+diesel::joinable!(market_bets -> games (fk_games));
+diesel::joinable!(market_bets -> teams (fk_teams));
+diesel::joinable!(market_bets -> tournaments (fk_tournaments));
+diesel::joinable!(market_bets -> users (fk_users));
+diesel::joinable!(nut_transactions -> games (fk_games));
+diesel::joinable!(nut_transactions -> nuts (fk_nuts));
 diesel::joinable!(nuts -> tournaments (fk_tournaments));
 diesel::joinable!(nuts -> users (fk_users));
+diesel::joinable!(series_formats -> tournaments (fk_tournaments));
+diesel::joinable!(sessions -> users (fk_users));
 diesel::joinable!(subscriptions -> tournaments (fk_tournaments));
 diesel::joinable!(subscriptions -> users (fk_users));
 diesel::joinable!(teams -> tournaments (fk_tournaments));
+diesel::joinable!(tokens -> sessions (fk_sessions));
 diesel::joinable!(tokens -> users (fk_users));
 diesel::joinable!(tournaments -> users (fk_users));
 
 diesel::allow_tables_to_appear_in_same_query!(
     bets,
+    game_sets,
     games,
+    market_bets,
+    nut_transactions,
     nuts,
+    seasons,
+    series_formats,
+    sessions,
     subscriptions,
     teams,
     tokens,
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::standings::Standing;
+
+// A team sent to the elimination phase
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Qualifier {
+    pub team_id: i32,
+    pub group: i32,
+    // place of the team in its group, 1 for the winner
+    pub rank: usize,
+}
+
+/// Teams qualified for the elimination phase, best seed first
+///
+/// The `per_group` first teams of each group qualify, then the `wildcards` best
+/// teams among the next placed ones. Teams with the same rank are seeded by
+/// points, score difference and scored points, still tied teams by a draw
+/// seeded with `seed`.
+pub fn qualifiers(
+    groups: &BTreeMap<i32, Vec<Standing>>,
+    per_group: usize,
+    wildcards: usize,
+    seed: u64,
+) -> Vec<Qualifier> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut candidates: Vec<(Qualifier, &Standing, u64)> = vec![];
+
+    for (group, standings) in groups {
+        for (index, standing) in standings.iter().take(per_group + 1).enumerate() {
+            candidates.push((
+                Qualifier {
+                    team_id: standing.team_id,
+                    group: *group,
+                    rank: index + 1,
+                },
+                standing,
+                rng.gen(),
+            ));
+        }
+    }
+
+    candidates.sort_by(|(a, a_standing, a_draw), (b, b_standing, b_draw)| {
+        a.rank
+            .cmp(&b.rank)
+            .then(b_standing.points.cmp(&a_standing.points))
+            .then(b_standing.score_diff.cmp(&a_standing.score_diff))
+            .then(b_standing.scored.cmp(&a_standing.scored))
+            .then(a_draw.cmp(b_draw))
+    });
+
+    let mut qualified: Vec<Qualifier> = vec![];
+    let mut nb_wildcards = 0;
+
+    for (qualifier, _, _) in candidates {
+        if qualifier.rank <= per_group {
+            qualified.push(qualifier);
+        } else if nb_wildcards < wildcards {
+            qualified.push(qualifier);
+            nb_wildcards += 1;
+        }
+    }
+
+    qualified
+}
+
+// Position of each seed in a bracket of the given size, seed 0 plays the last seed
+pub fn bracket_positions(size: usize) -> Vec<usize> {
+    let mut positions = vec![0];
+
+    while positions.len() < size.max(1) {
+        let next_size = positions.len() * 2;
+        positions = positions
+            .iter()
+            .flat_map(|seed| [*seed, next_size - 1 - seed])
+            .collect();
+    }
+
+    positions
+}
+
+/// First round of the elimination phase
+///
+/// Seeds are placed so the best teams meet as late as possible. When the number
+/// of teams is not a power of two the best seeds get a bye (no opponent). Teams
+/// from the same group are then kept apart in the first round by swapping
+/// opponents between games.
+pub fn first_round(seeds: &[Qualifier]) -> Vec<(i32, Option<i32>)> {
+    let size = seeds.len().max(2).next_power_of_two();
+    let positions = bracket_positions(size);
+
+    let mut games: Vec<(Option<&Qualifier>, Option<&Qualifier>)> = positions
+        .chunks(2)
+        .map(|pair| (seeds.get(pair[0]), seeds.get(pair[1])))
+        .collect();
+
+    // group 0 is used for teams that are not in any group
+    let same_group = |a: Option<&Qualifier>, b: Option<&Qualifier>| match (a, b) {
+        (Some(a), Some(b)) => a.group != 0 && a.group == b.group,
+        _ => false,
+    };
+
+    for i in 0..games.len() {
+        if !same_group(games[i].0, games[i].1) {
+            continue;
+        }
+
+        // find the closest game where both opponents can be exchanged
+        let mut others: Vec<usize> = (0..games.len()).filter(|j| *j != i).collect();
+        others.sort_by_key(|j| (*j as i64 - i as i64).abs());
+
+        for j in others {
+            if games[j].1.is_none()
+                || same_group(games[i].0, games[j].1)
+                || same_group(games[j].0, games[i].1)
+            {
+                continue;
+            }
+
+            let opponent = games[i].1;
+            games[i].1 = games[j].1;
+            games[j].1 = opponent;
+            break;
+        }
+    }
+
+    games
+        .into_iter()
+        .filter_map(|(team1, team2)| match (team1, team2) {
+            (Some(team1), team2) => Some((team1.team_id, team2.map(|t| t.team_id))),
+            (None, Some(team2)) => Some((team2.team_id, None)),
+            (None, None) => None,
+        })
+        .collect()
+}
+
+/// Seeds of the teams of a first round, best seed first
+///
+/// Reverse of `first_round` for teams without group: the team of a bye takes the
+/// best seed of its game.
+pub fn seeds_from_first_round(games: &[(i32, Option<i32>)]) -> Vec<i32> {
+    let positions = bracket_positions(games.len() * 2);
+    let mut seeds: Vec<(usize, i32)> = vec![];
+
+    for (place, (team1, team2)) in games.iter().enumerate() {
+        let (seed1, seed2) = (positions[place * 2], positions[place * 2 + 1]);
+
+        match team2 {
+            Some(team2) => {
+                seeds.push((seed1, *team1));
+                seeds.push((seed2, *team2));
+            }
+            None => seeds.push((seed1.min(seed2), *team1)),
+        }
+    }
+
+    seeds.sort();
+    seeds.into_iter().map(|(_, team)| team).collect()
+}
@@ -0,0 +1,60 @@
+use rocket::serde::{Deserialize, Serialize};
+
+// Score of one map/set of a series
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SetScore {
+    pub score1: i32,
+    pub score2: i32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SeriesError {
+    // more sets than the series length
+    TooManySets,
+    // a set was played after a team had already won the series
+    AlreadyDecided,
+}
+
+// sets a team needs to win the series
+pub fn wins_needed(best_of: i32) -> i32 {
+    best_of.max(1) / 2 + 1
+}
+
+// sets won by each team, a drawn set counts for nobody
+pub fn series_score(sets: &[SetScore]) -> (i32, i32) {
+    sets.iter().fold((0, 0), |(won1, won2), set| {
+        if set.score1 > set.score2 {
+            (won1 + 1, won2)
+        } else if set.score2 > set.score1 {
+            (won1, won2 + 1)
+        } else {
+            (won1, won2)
+        }
+    })
+}
+
+/// Whether the series can be closed
+///
+/// A series is over once a team won enough sets, or when every set has been
+/// played without a winner (the draw then needs a tiebreak).
+pub fn is_over(best_of: i32, sets: &[SetScore]) -> bool {
+    let (won1, won2) = series_score(sets);
+    let needed = wins_needed(best_of);
+
+    won1 >= needed || won2 >= needed || sets.len() as i32 >= best_of.max(1)
+}
+
+// check the results of a series, sets are in the order they were played
+pub fn check_sets(best_of: i32, sets: &[SetScore]) -> Result<(), SeriesError> {
+    if sets.len() as i32 > best_of.max(1) {
+        return Err(SeriesError::TooManySets);
+    }
+
+    for played in 1..sets.len() {
+        if is_over(best_of, &sets[..played]) {
+            return Err(SeriesError::AlreadyDecided);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rocket::serde::{Deserialize, Serialize};
+
+// Points given for each game result, configured on the tournament
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Points {
+    pub win: i32,
+    pub draw: i32,
+    pub loss: i32,
+}
+
+impl Default for Points {
+    fn default() -> Points {
+        Points {
+            win: 3,
+            draw: 1,
+            loss: 0,
+        }
+    }
+}
+
+impl Points {
+    pub fn for_scores(&self, scored: i32, conceded: i32) -> i32 {
+        match scored.cmp(&conceded) {
+            Ordering::Greater => self.win,
+            Ordering::Equal => self.draw,
+            Ordering::Less => self.loss,
+        }
+    }
+}
+
+// Score of a closed game
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GameResult {
+    pub team1: i32,
+    pub team2: i32,
+    pub score1: i32,
+    pub score2: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Standing {
+    pub team_id: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub scored: i32,
+    pub conceded: i32,
+    pub score_diff: i32,
+    pub points: i32,
+}
+
+impl Standing {
+    fn new(team_id: i32) -> Standing {
+        Standing {
+            team_id,
+            played: 0,
+            won: 0,
+            drawn: 0,
+            lost: 0,
+            scored: 0,
+            conceded: 0,
+            score_diff: 0,
+            points: 0,
+        }
+    }
+
+    fn add_result(&mut self, scored: i32, conceded: i32, points: &Points) {
+        self.played += 1;
+        self.scored += scored;
+        self.conceded += conceded;
+        self.score_diff = self.scored - self.conceded;
+        self.points += points.for_scores(scored, conceded);
+
+        match scored.cmp(&conceded) {
+            Ordering::Greater => self.won += 1,
+            Ordering::Equal => self.drawn += 1,
+            Ordering::Less => self.lost += 1,
+        }
+    }
+}
+
+fn table(teams: &[i32], results: &[GameResult], points: &Points) -> HashMap<i32, Standing> {
+    let mut standings: HashMap<i32, Standing> = teams
+        .iter()
+        .map(|team| (*team, Standing::new(*team)))
+        .collect();
+
+    for result in results {
+        // both teams need to be in the table, byes and games against other groups are ignored
+        if result.team1 == result.team2
+            || !standings.contains_key(&result.team1)
+            || !standings.contains_key(&result.team2)
+        {
+            continue;
+        }
+
+        if let Some(standing) = standings.get_mut(&result.team1) {
+            standing.add_result(result.score1, result.score2, points);
+        }
+        if let Some(standing) = standings.get_mut(&result.team2) {
+            standing.add_result(result.score2, result.score1, points);
+        }
+    }
+
+    standings
+}
+
+/// Standings of a group, best team first
+///
+/// Teams are ranked by points, then by the points of the games played between
+/// the tied teams only, then by score difference, then by scored points. Teams
+/// still tied are ordered by a draw seeded with `seed`, so the same group always
+/// gets the same order.
+pub fn standings(teams: &[i32], results: &[GameResult], points: &Points, seed: u64) -> Vec<Standing> {
+    let overall = table(teams, results, points);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut sorted_teams: Vec<i32> = teams.to_vec();
+    sorted_teams.sort();
+    let draw: HashMap<i32, u64> = sorted_teams.iter().map(|team| (*team, rng.gen())).collect();
+
+    // Head to head table between the teams tied on points
+    let mut head_to_head: HashMap<i32, i32> = HashMap::new();
+    let mut tied: HashMap<i32, Vec<i32>> = HashMap::new();
+    for standing in overall.values() {
+        tied.entry(standing.points).or_default().push(standing.team_id);
+    }
+    for teams in tied.values().filter(|teams| teams.len() > 1) {
+        for (team, standing) in table(teams, results, points) {
+            head_to_head.insert(team, standing.points);
+        }
+    }
+
+    let mut standings: Vec<Standing> = overall.into_values().collect();
+    standings.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(head_to_head.get(&b.team_id).cmp(&head_to_head.get(&a.team_id)))
+            .then(b.score_diff.cmp(&a.score_diff))
+            .then(b.scored.cmp(&a.scored))
+            .then(draw[&a.team_id].cmp(&draw[&b.team_id]))
+    });
+
+    standings
+}
@@ -0,0 +1,397 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::standings::Points;
+
+// Result of a closed game of a swiss tournament, a bye when both teams are the same
+#[derive(Clone, Copy, Debug)]
+pub struct SwissResult {
+    pub team1: i32,
+    pub team2: i32,
+    pub score1: i32,
+    pub score2: i32,
+}
+
+impl SwissResult {
+    pub fn is_bye(&self) -> bool {
+        self.team1 == self.team2
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SwissStanding {
+    pub team_id: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub points: i32,
+    // sum of the points of all the opponents
+    pub buchholz: i32,
+    // buchholz without the weakest opponent
+    pub buchholz_cut: i32,
+    pub score_diff: i32,
+    pub had_bye: bool,
+    pub opponents: Vec<i32>,
+}
+
+impl SwissStanding {
+    fn new(team_id: i32) -> SwissStanding {
+        SwissStanding {
+            team_id,
+            played: 0,
+            won: 0,
+            drawn: 0,
+            lost: 0,
+            points: 0,
+            buchholz: 0,
+            buchholz_cut: 0,
+            score_diff: 0,
+            had_bye: false,
+            opponents: vec![],
+        }
+    }
+
+    fn add_result(&mut self, opponent: i32, scored: i32, conceded: i32, points: &Points) {
+        self.played += 1;
+        self.score_diff += scored - conceded;
+        self.points += points.for_scores(scored, conceded);
+        self.opponents.push(opponent);
+
+        match scored.cmp(&conceded) {
+            Ordering::Greater => self.won += 1,
+            Ordering::Equal => self.drawn += 1,
+            Ordering::Less => self.lost += 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SwissPairing {
+    pub games: Vec<(i32, i32)>,
+    pub bye: Option<i32>,
+}
+
+// Standings of a swiss tournament, best team first
+pub fn standings(teams: &[i32], results: &[SwissResult], points: &Points) -> Vec<SwissStanding> {
+    let mut standings: HashMap<i32, SwissStanding> = teams
+        .iter()
+        .map(|team| (*team, SwissStanding::new(*team)))
+        .collect();
+
+    for result in results {
+        if result.is_bye() {
+            // A bye counts as a win without opponent
+            if let Some(standing) = standings.get_mut(&result.team1) {
+                standing.played += 1;
+                standing.won += 1;
+                standing.points += points.win;
+                standing.had_bye = true;
+            }
+            continue;
+        }
+
+        if let Some(standing) = standings.get_mut(&result.team1) {
+            standing.add_result(result.team2, result.score1, result.score2, points);
+        }
+        if let Some(standing) = standings.get_mut(&result.team2) {
+            standing.add_result(result.team1, result.score2, result.score1, points);
+        }
+    }
+
+    let team_points: HashMap<i32, i32> = standings
+        .values()
+        .map(|standing| (standing.team_id, standing.points))
+        .collect();
+
+    let mut standings: Vec<SwissStanding> = standings.into_values().collect();
+    for standing in standings.iter_mut() {
+        let mut opponents_points: Vec<i32> = standing
+            .opponents
+            .iter()
+            .map(|opponent| *team_points.get(opponent).unwrap_or(&0))
+            .collect();
+        opponents_points.sort();
+
+        standing.buchholz = opponents_points.iter().sum();
+        standing.buchholz_cut = opponents_points.iter().skip(1).sum();
+    }
+
+    standings.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(b.buchholz.cmp(&a.buchholz))
+            .then(b.buchholz_cut.cmp(&a.buchholz_cut))
+            .then(b.score_diff.cmp(&a.score_diff))
+            .then(a.team_id.cmp(&b.team_id))
+    });
+
+    standings
+}
+
+// steps of the backtracking search before falling back to the matching algorithm
+const MAX_SEARCH_STEPS: usize = 100_000;
+
+/// Pair the teams for the next round
+///
+/// Teams are paired from the top of the standings with the closest team they
+/// haven't played yet, backtracking when the end of the table can't be paired.
+/// The search is bounded: past `MAX_SEARCH_STEPS` any pairing without a rematch
+/// is taken, found by a maximum matching. With an odd number of teams the
+/// lowest ranked team without a bye sits out, once every team had one the
+/// lowest ranked team gets another. Returns none when every pairing would
+/// repeat a game.
+pub fn pair_round(standings: &[SwissStanding]) -> Option<SwissPairing> {
+    let played: HashSet<(i32, i32)> = standings
+        .iter()
+        .flat_map(|standing| {
+            standing
+                .opponents
+                .iter()
+                .map(move |opponent| (standing.team_id, *opponent))
+        })
+        .collect();
+
+    if standings.len().is_multiple_of(2) {
+        let teams: Vec<i32> = standings.iter().map(|s| s.team_id).collect();
+
+        return pair_teams(&teams, &played).map(|games| SwissPairing { games, bye: None });
+    }
+
+    // the teams without a bye first, a second bye only when none can sit out
+    let candidates = standings
+        .iter()
+        .rev()
+        .filter(|s| !s.had_bye)
+        .chain(standings.iter().rev().filter(|s| s.had_bye));
+
+    for candidate in candidates {
+        let teams: Vec<i32> = standings
+            .iter()
+            .map(|s| s.team_id)
+            .filter(|team| *team != candidate.team_id)
+            .collect();
+
+        if let Some(games) = pair_teams(&teams, &played) {
+            return Some(SwissPairing {
+                games,
+                bye: Some(candidate.team_id),
+            });
+        }
+    }
+
+    None
+}
+
+// pairing of the teams in the order of the standings, none when there is none
+fn pair_teams(teams: &[i32], played: &HashSet<(i32, i32)>) -> Option<Vec<(i32, i32)>> {
+    let mut remaining = teams.to_vec();
+    let mut games = vec![];
+    let mut steps = 0;
+
+    match search(&mut remaining, played, &mut games, &mut steps) {
+        Some(true) => Some(games),
+        Some(false) => None,
+        // too long, the matching tells if a pairing exists
+        None => perfect_matching(teams, played),
+    }
+}
+
+// backtracking search, none once the steps are exhausted
+fn search(
+    remaining: &mut Vec<i32>,
+    played: &HashSet<(i32, i32)>,
+    games: &mut Vec<(i32, i32)>,
+    steps: &mut usize,
+) -> Option<bool> {
+    if remaining.is_empty() {
+        return Some(true);
+    }
+
+    *steps += 1;
+    if *steps > MAX_SEARCH_STEPS {
+        return None;
+    }
+
+    let team = remaining.remove(0);
+
+    for i in 0..remaining.len() {
+        let opponent = remaining[i];
+        if played.contains(&(team, opponent)) {
+            continue;
+        }
+
+        remaining.remove(i);
+        games.push((team, opponent));
+
+        match search(remaining, played, games, steps) {
+            Some(true) => return Some(true),
+            Some(false) => {}
+            None => return None,
+        }
+
+        games.pop();
+        remaining.insert(i, opponent);
+    }
+
+    remaining.insert(0, team);
+    Some(false)
+}
+
+// no vertex, for the arrays of the matching
+const NONE: usize = usize::MAX;
+
+// Pair every team with a team it hasn't played, none when it can't be done.
+// Edmonds' blossom algorithm on the graph of the games left to play, in O(n³).
+// A team without augmenting path can't be matched later, so it stops there
+fn perfect_matching(teams: &[i32], played: &HashSet<(i32, i32)>) -> Option<Vec<(i32, i32)>> {
+    let n = teams.len();
+    let adjacent: Vec<Vec<usize>> = (0..n)
+        .map(|a| {
+            (0..n)
+                .filter(|b| a != *b && !played.contains(&(teams[a], teams[*b])))
+                .collect()
+        })
+        .collect();
+
+    let mut mate = vec![NONE; n];
+
+    for root in 0..n {
+        if mate[root] != NONE {
+            continue;
+        }
+
+        let (mut vertex, parent) = augmenting_path(&adjacent, &mate, root)?;
+
+        // flip the path, every team on it gets a new opponent
+        while vertex != NONE {
+            let next = mate[parent[vertex]];
+            mate[vertex] = parent[vertex];
+            mate[parent[vertex]] = vertex;
+            vertex = next;
+        }
+    }
+
+    let mut games: Vec<(i32, i32)> = (0..n)
+        .filter(|a| *a < mate[*a])
+        .map(|a| (teams[a], teams[mate[a]]))
+        .collect();
+    // same order as the standings
+    games.sort_by_key(|(team, _)| teams.iter().position(|t| t == team));
+
+    Some(games)
+}
+
+// unmatched vertex reached from the root with the parents of the path, none without one
+fn augmenting_path(
+    adjacent: &[Vec<usize>],
+    mate: &[usize],
+    root: usize,
+) -> Option<(usize, Vec<usize>)> {
+    let n = adjacent.len();
+    let mut used = vec![false; n];
+    let mut parent = vec![NONE; n];
+    let mut base: Vec<usize> = (0..n).collect();
+
+    used[root] = true;
+    let mut queue = VecDeque::from([root]);
+
+    while let Some(vertex) = queue.pop_front() {
+        for &to in &adjacent[vertex] {
+            if base[vertex] == base[to] || mate[vertex] == to {
+                continue;
+            }
+
+            if to == root || (mate[to] != NONE && parent[mate[to]] != NONE) {
+                // odd cycle, contracted in a blossom
+                let current_base = lowest_common_ancestor(mate, &base, &parent, vertex, to);
+                let mut blossom = vec![false; n];
+                mark_path(
+                    mate,
+                    &base,
+                    &mut parent,
+                    &mut blossom,
+                    vertex,
+                    current_base,
+                    to,
+                );
+                mark_path(
+                    mate,
+                    &base,
+                    &mut parent,
+                    &mut blossom,
+                    to,
+                    current_base,
+                    vertex,
+                );
+
+                for i in 0..n {
+                    if blossom[base[i]] {
+                        base[i] = current_base;
+                        if !used[i] {
+                            used[i] = true;
+                            queue.push_back(i);
+                        }
+                    }
+                }
+            } else if parent[to] == NONE {
+                parent[to] = vertex;
+                if mate[to] == NONE {
+                    return Some((to, parent));
+                }
+
+                used[mate[to]] = true;
+                queue.push_back(mate[to]);
+            }
+        }
+    }
+
+    None
+}
+
+fn lowest_common_ancestor(
+    mate: &[usize],
+    base: &[usize],
+    parent: &[usize],
+    mut a: usize,
+    mut b: usize,
+) -> usize {
+    let mut used = vec![false; mate.len()];
+
+    loop {
+        a = base[a];
+        used[a] = true;
+        if mate[a] == NONE {
+            break;
+        }
+        a = parent[mate[a]];
+    }
+
+    loop {
+        b = base[b];
+        if used[b] {
+            return b;
+        }
+        b = parent[mate[b]];
+    }
+}
+
+fn mark_path(
+    mate: &[usize],
+    base: &[usize],
+    parent: &mut [usize],
+    blossom: &mut [bool],
+    mut vertex: usize,
+    current_base: usize,
+    mut child: usize,
+) {
+    while base[vertex] != current_base {
+        blossom[base[vertex]] = true;
+        blossom[base[mate[vertex]]] = true;
+        parent[vertex] = child;
+        child = mate[vertex];
+        vertex = parent[mate[vertex]];
+    }
+}
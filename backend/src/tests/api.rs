@@ -68,7 +68,7 @@ fn successful_login_logoff_request(){
 
     // Save token from response
     // "{\"token\":\"f35e9be6-db56-4e0a-8d26-82abb507e828\",\"expiration_date\":\"2023-09-08T02:41:49.201130\"}"
-    let token = response.into_string().unwrap().split(":").nth(1).unwrap().split(",").nth(0).unwrap().replace("\"", "");
+    let token = response.into_string().unwrap().split(":").nth(1).unwrap().split(",").next().unwrap().replace("\"", "");
     
 
     let headers = Header::new("Authorization", format!("Bearer {}", token));
@@ -182,7 +182,7 @@ fn get_user_info_request() {
 
     // Save token from response
     // "{\"token\":\"f35e9be6-db56-4e0a-8d26-82abb507e828\",\"expiration_date\":\"2023-09-08T02:41:49.201130\"}"
-    let token = response.into_string().unwrap().split(":").nth(1).unwrap().split(",").nth(0).unwrap().replace("\"", "");
+    let token = response.into_string().unwrap().split(":").nth(1).unwrap().split(",").next().unwrap().replace("\"", "");
     
 
     let headers = Header::new("Authorization", format!("Bearer {}", token));
@@ -200,4 +200,539 @@ fn get_user_info_request() {
         .dispatch();
 
     assert_eq!(response.status(), Status::Forbidden);
-}
\ No newline at end of file
+}
+#[test]
+#[serial]
+fn forgot_password_for_unknown_email_request() {
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "nobody@tournoix.com";
+    let c = client();
+
+    let json_forgot_request = format!("{{\"email\":\"{}\"}}", TEST_USER_EMAIL);
+
+    let response = c.post("/api/auth/forgot_password")
+        .header(ContentType::JSON)
+        .body(json_forgot_request)
+        .dispatch();
+
+    // the answer doesn't tell whether the email has an account
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+#[serial]
+fn unsuccessful_reset_password_request() {
+    use rocket::http::{Status, ContentType};
+
+    let c = client();
+
+    let json_reset_request = format!("{{\"token\":\"{}\",\"password\":\"{}\"}}", "14b08dc3-192e-441f-bb06-ad2bf09960eb", "Password123!");
+
+    let response = c.post("/api/auth/reset_password")
+        .header(ContentType::JSON)
+        .body(json_reset_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+#[serial]
+fn weak_password_register_request() {
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "john.doe.weak@tournoix.com";
+    const TEST_USER_PASSWORD: &str ="password";
+    const TEST_USER_NAME: &str = "John Doe Weak";
+
+    let c = client();
+
+    let json_register_request = format!("{{\"email\":\"{}\",\"password\":\"{}\",\"name\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD, TEST_USER_NAME);
+
+    let response = c.post("/api/auth/register")
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.into_string().unwrap().contains("no_uppercase,no_digit,no_special"));
+}
+
+#[test]
+#[serial]
+fn change_password_and_delete_account_request() {
+    use rocket::http::Header;
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "john.doe5@tournoix.com";
+    const TEST_USER_PASSWORD: &str ="Password123!5";
+    const TEST_USER_NEW_PASSWORD: &str ="Password123!6";
+    const TEST_USER_NAME: &str = "John Doe 5";
+
+    let c = client();
+
+    let json_register_request = format!("{{\"email\":\"{}\",\"password\":\"{}\",\"name\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD, TEST_USER_NAME);
+
+    c.post("/api/auth/register")
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    let json_login_request = format!("{{\"email\":\"{}\",\"password\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD);
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let token = response.into_string().unwrap().split(":").nth(1).unwrap().split(",").next().unwrap().replace("\"", "");
+    let headers = Header::new("Authorization", format!("Bearer {}", token));
+
+    // the current password is asked
+    let json_password_request = format!("{{\"current_password\":\"{}\",\"new_password\":\"{}\"}}", TEST_USER_NEW_PASSWORD, TEST_USER_NEW_PASSWORD);
+
+    let response = c.post("/api/users/@me/password")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(json_password_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let json_password_request = format!("{{\"current_password\":\"{}\",\"new_password\":\"{}\"}}", TEST_USER_PASSWORD, TEST_USER_NEW_PASSWORD);
+
+    let response = c.post("/api/users/@me/password")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(json_password_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let json_delete_request = format!("{{\"password\":\"{}\"}}", TEST_USER_NEW_PASSWORD);
+
+    let response = c.delete("/api/users/@me")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(json_delete_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    // the account can't be used anymore
+    let response = c.get("/api/users/@me")
+        .header(ContentType::JSON)
+        .header(headers)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let json_login_request = format!("{{\"email\":\"{}\",\"password\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_NEW_PASSWORD);
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+#[serial]
+fn refresh_and_sessions_request() {
+    use rocket::http::Header;
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "john.doe7@tournoix.com";
+    const TEST_USER_PASSWORD: &str ="Password123!7";
+    const TEST_USER_NAME: &str = "John Doe 7";
+
+    let c = client();
+
+    let json_register_request = format!("{{\"email\":\"{}\",\"password\":\"{}\",\"name\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD, TEST_USER_NAME);
+
+    c.post("/api/auth/register")
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    let json_login_request = format!("{{\"email\":\"{}\",\"password\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD);
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let login: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let refresh_token = login["refresh_token"].as_str().unwrap().to_string();
+
+    let json_refresh_request = format!("{{\"refresh_token\":\"{}\"}}", refresh_token);
+
+    let response = c.post("/api/auth/refresh")
+        .header(ContentType::JSON)
+        .body(json_refresh_request.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let refreshed: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_ne!(refreshed["refresh_token"], login["refresh_token"]);
+
+    // a refresh token works once
+    let response = c.post("/api/auth/refresh")
+        .header(ContentType::JSON)
+        .body(json_refresh_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // the access token given at login was replaced
+    let response = c.get("/api/users/@me")
+        .header(Header::new("Authorization", format!("Bearer {}", login["token"].as_str().unwrap())))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let headers = Header::new("Authorization", format!("Bearer {}", refreshed["token"].as_str().unwrap()));
+
+    let response = c.get("/api/users/@me/sessions")
+        .header(headers.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let sessions: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(sessions.as_array().unwrap().len(), 1);
+    assert_eq!(sessions[0]["is_current"], true);
+
+    let response = c.delete("/api/users/@me/sessions")
+        .header(headers.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = c.get("/api/users/@me")
+        .header(headers)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+#[serial]
+fn login_lockout_request() {
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "john.doe8@tournoix.com";
+    const TEST_USER_PASSWORD: &str ="WrongPassword123!8";
+
+    let c = client();
+
+    let json_login_request = format!("{{\"email\":\"{}\",\"password\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD);
+
+    // the default config locks the account at the fifth failure
+    for _ in 0..5 {
+        let response = c.post("/api/auth/login")
+            .header(ContentType::JSON)
+            .body(json_login_request.clone())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::TooManyRequests);
+
+    let error: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(error["error"]["code"], 429);
+}
+
+#[test]
+#[serial]
+fn register_rate_limit_request() {
+    use rocket::http::{Status, ContentType};
+
+    let c = client();
+    let remote: std::net::SocketAddr = "192.0.2.1:4000".parse().unwrap();
+
+    // weak passwords, nothing is created
+    let json_register_request = "{\"email\":\"john.doe9@tournoix.com\",\"password\":\"weak\",\"name\":\"John Doe 9\"}";
+
+    for _ in 0..5 {
+        let response = c.post("/api/auth/register")
+            .remote(remote)
+            .header(ContentType::JSON)
+            .body(json_register_request)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    let response = c.post("/api/auth/register")
+        .remote(remote)
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::TooManyRequests);
+
+    let error: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(error["error"]["reason"], "Too Many Requests");
+
+    // another address has its own bucket
+    let response = c.post("/api/auth/register")
+        .remote("192.0.2.2:4000".parse().unwrap())
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+#[serial]
+fn reseed_refunds_bets_request() {
+    use rocket::http::Header;
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "john.doe10@tournoix.com";
+    const TEST_USER_PASSWORD: &str ="Password123!10";
+    const TEST_USER_NAME: &str = "John Doe 10";
+
+    let c = client();
+
+    let json_register_request = format!("{{\"email\":\"{}\",\"password\":\"{}\",\"name\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD, TEST_USER_NAME);
+
+    c.post("/api/auth/register")
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    let json_login_request = format!("{{\"email\":\"{}\",\"password\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD);
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request)
+        .dispatch();
+
+    let login: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let headers = Header::new("Authorization", format!("Bearer {}", login["token"].as_str().unwrap()));
+
+    let json_tournament_request = "{\"name\":\"Reseed\",\"description\":null,\"date\":\"2030-01-01T10:00:00\",\"location\":null,\"size_group\":null,\"is_qualif\":false,\"is_elim\":true,\"is_closed\":false,\"is_double_elim\":false,\"has_bracket_reset\":false,\"is_swiss\":false,\"swiss_rounds\":null,\"points_win\":3,\"points_draw\":1,\"points_loss\":0,\"qualified_per_group\":0,\"wildcards\":0,\"has_third_place\":false}";
+
+    let response = c.post("/api/tournoix")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(json_tournament_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let tournament: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let id = tournament["id"].as_i64().unwrap();
+
+    for team in 1..=4 {
+        let response = c.post(format!("/api/tournoix/{}/teams", id))
+            .header(ContentType::JSON)
+            .header(headers.clone())
+            .body(format!("{{\"name\":\"Team {}\",\"group\":0}}", team))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    c.post("/api/users/@me/subscription")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(format!("{{\"code\":\"{}\"}}", tournament["code"].as_str().unwrap()))
+        .dispatch();
+
+    let response = c.post(format!("/api/tournoix/{}/elim", id))
+        .header(headers.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = c.get(format!("/api/tournoix/{}/games", id))
+        .header(headers.clone())
+        .dispatch();
+
+    let games: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let game = games.as_array().unwrap().iter().find(|g| g["phase"] == 1 && g["status"] == 0).unwrap().clone();
+
+    let response = c.get(format!("/api/tournoix/{}/nut", id))
+        .header(headers.clone())
+        .dispatch();
+
+    let nut: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let stock = nut["stock"].as_i64().unwrap();
+
+    let response = c.post(format!("/api/game/{}/bet", game["id"]))
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(format!("{{\"team_id\":{},\"nut\":10}}", game["team1"]["id"]))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = c.get(format!("/api/tournoix/{}/elim/seeding", id))
+        .header(headers.clone())
+        .dispatch();
+
+    let seeding: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let mut seeds: Vec<i64> = seeding["seeds"].as_array().unwrap().iter().map(|s| s.as_i64().unwrap()).collect();
+    seeds.reverse();
+
+    let response = c.put(format!("/api/tournoix/{}/elim/seeding", id))
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(format!("{{\"seeds\":{:?}}}", seeds))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    // the game is replaced and the stake given back
+    let response = c.get(format!("/api/game/{}/bet", game["id"]))
+        .header(headers.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = c.get(format!("/api/tournoix/{}/nut", id))
+        .header(headers)
+        .dispatch();
+
+    let nut: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(nut["stock"].as_i64().unwrap(), stock);
+}
+
+#[test]
+#[serial]
+fn update_game_needs_auth_request() {
+    use rocket::http::{Status, ContentType};
+
+    let c = client();
+
+    let response = c.patch("/api/games/1")
+        .header(ContentType::JSON)
+        .body("{\"score1\":3,\"score2\":0}")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+
+#[test]
+#[serial]
+fn bet_on_game_teams_only_request() {
+    use rocket::http::Header;
+    use rocket::http::{Status, ContentType};
+
+    const TEST_USER_EMAIL: &str = "john.doe11@tournoix.com";
+    const TEST_USER_PASSWORD: &str ="Password123!11";
+    const TEST_USER_NAME: &str = "John Doe 11";
+
+    let c = client();
+
+    let json_register_request = format!("{{\"email\":\"{}\",\"password\":\"{}\",\"name\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD, TEST_USER_NAME);
+
+    c.post("/api/auth/register")
+        .header(ContentType::JSON)
+        .body(json_register_request)
+        .dispatch();
+
+    let json_login_request = format!("{{\"email\":\"{}\",\"password\":\"{}\"}}", TEST_USER_EMAIL, TEST_USER_PASSWORD);
+
+    let response = c.post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(json_login_request)
+        .dispatch();
+
+    let login: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let headers = Header::new("Authorization", format!("Bearer {}", login["token"].as_str().unwrap()));
+
+    let json_tournament_request = "{\"name\":\"Bets\",\"description\":null,\"date\":\"2030-01-01T10:00:00\",\"location\":null,\"size_group\":null,\"is_qualif\":false,\"is_elim\":true,\"is_closed\":false,\"is_double_elim\":false,\"has_bracket_reset\":false,\"is_swiss\":false,\"swiss_rounds\":null,\"points_win\":3,\"points_draw\":1,\"points_loss\":0,\"qualified_per_group\":0,\"wildcards\":0,\"has_third_place\":false}";
+
+    let response = c.post("/api/tournoix")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(json_tournament_request)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let tournament: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let id = tournament["id"].as_i64().unwrap();
+
+    for team in 1..=4 {
+        let response = c.post(format!("/api/tournoix/{}/teams", id))
+            .header(ContentType::JSON)
+            .header(headers.clone())
+            .body(format!("{{\"name\":\"Team {}\",\"group\":0}}", team))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    c.post("/api/users/@me/subscription")
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(format!("{{\"code\":\"{}\"}}", tournament["code"].as_str().unwrap()))
+        .dispatch();
+
+    let response = c.post(format!("/api/tournoix/{}/elim", id))
+        .header(headers.clone())
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = c.get(format!("/api/tournoix/{}/games", id))
+        .header(headers.clone())
+        .dispatch();
+
+    let games: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let game = games.as_array().unwrap().iter().find(|g| g["phase"] == 1 && g["status"] == 0).unwrap().clone();
+
+    // a team of another game
+    let other = games.as_array().unwrap().iter().find(|g| g["phase"] == 1 && g["id"] != game["id"]).unwrap().clone();
+
+    let response = c.post(format!("/api/game/{}/bet", game["id"]))
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(format!("{{\"team_id\":{},\"nut\":10}}", other["team1"]["id"]))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = c.post(format!("/api/game/{}/bet", game["id"]))
+        .header(ContentType::JSON)
+        .header(headers.clone())
+        .body(format!("{{\"team_id\":{},\"nut\":0}}", game["team1"]["id"]))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = c.post(format!("/api/game/{}/bet", game["id"]))
+        .header(ContentType::JSON)
+        .header(headers)
+        .body(format!("{{\"team_id\":{},\"nut\":10}}", game["team2"]["id"]))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+}
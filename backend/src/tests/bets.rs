@@ -0,0 +1,240 @@
+use rocket::futures::future::join_all;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::{Client, LocalRequest};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use super::super::rocket;
+
+// nuts given to a user joining a tournament
+const STOCK: i64 = 20;
+
+async fn client() -> Client {
+    Client::tracked(rocket()).await.expect("valid rocket instance")
+}
+
+async fn login(c: &Client, email: &str) -> Header<'static> {
+    let credentials = json!({ "email": email, "password": "Password123!", "name": email });
+
+    // the user is already there after the first run
+    c.post("/api/auth/register")
+        .header(ContentType::JSON)
+        .body(credentials.to_string())
+        .dispatch()
+        .await;
+
+    let response = c
+        .post("/api/auth/login")
+        .header(ContentType::JSON)
+        .body(credentials.to_string())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    Header::new("Authorization", format!("Bearer {}", body["token"].as_str().unwrap()))
+}
+
+// a tournament with one group of 4 teams, returns its id and its games
+async fn tournament(c: &Client, owner: &Header<'static>) -> (i64, Vec<Value>) {
+    let response = c
+        .post("/api/tournoix")
+        .header(ContentType::JSON)
+        .header(owner.clone())
+        .body(
+            json!({
+                "name": "Concurrence",
+                "description": null,
+                "date": "2023-09-20T09:00:00",
+                "location": null,
+                "size_group": 4,
+                "is_qualif": true,
+                "is_elim": false,
+                "is_closed": false,
+                "is_double_elim": false,
+                "has_bracket_reset": false,
+                "is_swiss": false,
+                "swiss_rounds": null,
+                "points_win": 3,
+                "points_draw": 1,
+                "points_loss": 0,
+                "qualified_per_group": 1,
+                "wildcards": 0,
+                "has_third_place": false
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let tournament: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    let id = tournament["id"].as_i64().unwrap();
+
+    for name in ["A", "B", "C", "D"] {
+        let response = c
+            .post(format!("/api/tournoix/{}/teams", id))
+            .header(ContentType::JSON)
+            .header(owner.clone())
+            .body(json!({ "name": name, "group": 1 }).to_string())
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    let response = c
+        .post(format!("/api/tournoix/{}/qualif", id))
+        .header(owner.clone())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let games: Vec<Value> = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+    (id, games)
+}
+
+fn bet<'c>(c: &'c Client, user: &Header<'static>, game: &Value, nut: i64) -> LocalRequest<'c> {
+    c.post(format!("/api/game/{}/bet", game["id"]))
+        .header(ContentType::JSON)
+        .header(user.clone())
+        .body(json!({ "team_id": game["fk_team1"], "nut": nut }).to_string())
+}
+
+// nuts on the bets of every game
+async fn staked(c: &Client, user: &Header<'static>, games: &[Value]) -> i64 {
+    let mut staked = 0;
+
+    for game in games {
+        let response = c
+            .get(format!("/api/game/{}/bet", game["id"]))
+            .header(user.clone())
+            .dispatch()
+            .await;
+        let bets: Vec<Value> = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+        staked += bets.iter().map(|b| b["nb_nut"].as_i64().unwrap()).sum::<i64>();
+    }
+
+    staked
+}
+
+async fn stock(c: &Client, user: &Header<'static>, tournament_id: i64) -> i64 {
+    let response = c
+        .get(format!("/api/tournoix/{}/nut", tournament_id))
+        .header(user.clone())
+        .dispatch()
+        .await;
+    let nut: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+    nut["stock"].as_i64().unwrap()
+}
+
+#[rocket::async_test]
+#[serial]
+async fn parallel_bets_never_overspend_the_stock() {
+    let c = client().await;
+    let owner = login(&c, "bets.owner@tournoix.com").await;
+    let (id, games) = tournament(&c, &owner).await;
+
+    // 8 nuts on each of the 6 games at once, only 2 bets fit in the stock
+    let responses = join_all(games.iter().map(|game| bet(&c, &owner, game, 8).dispatch())).await;
+
+    let placed: Vec<&Value> = responses
+        .iter()
+        .zip(games.iter())
+        .filter(|(response, _)| response.status() == Status::Ok)
+        .map(|(_, game)| game)
+        .collect();
+    assert_eq!(placed.len(), 2);
+    assert_eq!(stock(&c, &owner, id).await, STOCK - 16);
+
+    // changing the same bet many times at once keeps the stock and the bets in sync
+    join_all((0..=12).map(|nut| {
+        c.patch(format!("/api/game/{}/bet", placed[0]["id"]))
+            .header(ContentType::JSON)
+            .header(owner.clone())
+            .body(json!({ "team_id": placed[0]["fk_team1"], "nut": nut }).to_string())
+            .dispatch()
+    }))
+    .await;
+
+    let stock = stock(&c, &owner, id).await;
+    assert!(stock >= 0);
+    assert_eq!(stock + staked(&c, &owner, &games).await, STOCK);
+
+    // every change of the stock is in the ledger
+    let response = c
+        .get(format!("/api/tournoix/{}/nut/history", id))
+        .header(owner.clone())
+        .dispatch()
+        .await;
+    let history: Vec<Value> = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(history.iter().map(|t| t["amount"].as_i64().unwrap()).sum::<i64>(), stock);
+    assert_eq!(history[0]["balance"].as_i64().unwrap(), stock);
+}
+
+#[rocket::async_test]
+#[serial]
+async fn parallel_closes_pay_the_bets_once() {
+    let c = client().await;
+    let owner = login(&c, "bets.owner2@tournoix.com").await;
+    let (id, games) = tournament(&c, &owner).await;
+    let game = &games[0];
+
+    let response = bet(&c, &owner, game, 10).dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+
+    c.patch(format!("/api/games/{}/closeBetting", game["id"])).header(owner.clone()).dispatch().await;
+
+    let responses = join_all((0..5).map(|_| {
+        c.post(format!("/api/games/{}/close", game["id"]))
+            .header(owner.clone())
+            .dispatch()
+    }))
+    .await;
+
+    assert_eq!(responses.iter().filter(|r| r.status() == Status::Ok).count(), 1);
+    // a draw gives the stake back, once
+    assert_eq!(stock(&c, &owner, id).await, STOCK);
+}
+
+#[rocket::async_test]
+#[serial]
+async fn exact_score_bets_are_settled_with_the_game() {
+    let c = client().await;
+    let owner = login(&c, "bets.owner3@tournoix.com").await;
+    let (id, games) = tournament(&c, &owner).await;
+    let game = &games[0];
+
+    for (score1, score2) in [(0, 0), (1, 0)] {
+        let response = c
+            .post(format!("/api/game/{}/score_bet", game["id"]))
+            .header(ContentType::JSON)
+            .header(owner.clone())
+            .body(json!({ "score1": score1, "score2": score2, "nut": 5 }).to_string())
+            .dispatch()
+            .await;
+
+        // a single score bet by game
+        let expected = if score1 == 0 { Status::Ok } else { Status::BadRequest };
+        assert_eq!(response.status(), expected);
+    }
+    assert_eq!(stock(&c, &owner, id).await, STOCK - 5);
+
+    c.patch(format!("/api/games/{}/closeBetting", game["id"])).header(owner.clone()).dispatch().await;
+    let response = c
+        .post(format!("/api/games/{}/close", game["id"]))
+        .header(owner.clone())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    // the game ended 0 - 0, the only bet found the score
+    let response = c
+        .get(format!("/api/game/{}/score_bet", game["id"]))
+        .header(owner.clone())
+        .dispatch()
+        .await;
+    let market: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(market["is_open"], json!(false));
+    assert_eq!(market["bets"][0]["payout"], json!(5));
+    assert_eq!(stock(&c, &owner, id).await, STOCK);
+}
@@ -0,0 +1,146 @@
+use proptest::prelude::*;
+
+use crate::betting::{odds, preview, settle, settle_exact_score, Payout, ScoreStake, Stake};
+
+fn stake(user_id: i32, team_id: i32, nuts: i32) -> Stake {
+    Stake {
+        user_id,
+        team_id,
+        nuts,
+    }
+}
+
+fn nuts(payouts: &[Payout]) -> Vec<i32> {
+    payouts.iter().map(|p| p.nuts).collect()
+}
+
+// bets of up to 20 users on team 1 or 2
+fn stakes() -> impl Strategy<Value = Vec<Stake>> {
+    prop::collection::vec((1..=2, 1..=10_000), 0..20).prop_map(|bets| {
+        bets.into_iter()
+            .enumerate()
+            .map(|(user, (team, nuts))| stake(user as i32 + 1, team, nuts))
+            .collect()
+    })
+}
+
+#[test]
+fn losing_bets_are_shared_by_stake() {
+    let payouts = settle(
+        Some(1),
+        &[stake(1, 1, 10), stake(2, 1, 30), stake(3, 2, 20)],
+    );
+
+    assert_eq!(nuts(&payouts), vec![15, 45, 0]);
+}
+
+#[test]
+fn leftover_nuts_go_to_largest_remainders() {
+    // 10 nuts shared in 3 equal parts, the first bet gets the extra nut
+    let payouts = settle(
+        Some(1),
+        &[stake(1, 1, 5), stake(2, 1, 5), stake(3, 1, 5), stake(4, 2, 10)],
+    );
+
+    assert_eq!(nuts(&payouts), vec![9, 8, 8, 0]);
+}
+
+#[test]
+fn stakes_are_refunded_without_winning_bet() {
+    let stakes = [stake(1, 2, 10), stake(2, 2, 5)];
+
+    assert_eq!(nuts(&settle(Some(1), &stakes)), vec![10, 5]);
+    assert_eq!(nuts(&settle(None, &stakes)), vec![10, 5]);
+}
+
+#[test]
+fn odds_are_the_pot_over_the_team_stakes() {
+    let odds = odds(&[1, 2, 3], &[stake(1, 1, 10), stake(2, 1, 30), stake(3, 2, 20)]);
+
+    assert_eq!(
+        odds.iter().map(|o| (o.team_id, o.staked, o.bettors)).collect::<Vec<_>>(),
+        vec![(1, 40, 2), (2, 20, 1), (3, 0, 0)]
+    );
+    assert_eq!(odds[0].odds, Some(1.5));
+    assert_eq!(odds[1].odds, Some(3.0));
+    assert_eq!(odds[2].odds, None);
+}
+
+#[test]
+fn preview_replaces_the_bet_of_the_user() {
+    let stakes = [stake(1, 1, 5), stake(2, 1, 5), stake(3, 2, 10)];
+
+    // a new bet of 5 on team 1 shares the 10 nuts with the two others
+    assert_eq!(preview(&stakes, stake(4, 1, 5)).nuts, 8);
+    // moving the bet of user 1 to team 2 shares the 5 nuts of user 2, the bet keeps its place and the extra nut
+    assert_eq!(preview(&stakes, stake(1, 2, 10)).nuts, 13);
+}
+
+#[test]
+fn exact_scores_share_the_other_predictions() {
+    let score = |user_id, score, nuts| ScoreStake {
+        user_id,
+        score,
+        nuts,
+    };
+    let stakes = [score(1, (2, 1), 10), score(2, (1, 1), 6), score(3, (2, 1), 5), score(4, (0, 3), 9)];
+
+    assert_eq!(nuts(&settle_exact_score(Some((2, 1)), &stakes)), vec![20, 0, 10, 0]);
+    // a reversed score is another prediction
+    assert_eq!(nuts(&settle_exact_score(Some((1, 2)), &stakes)), vec![10, 6, 5, 9]);
+    assert_eq!(nuts(&settle_exact_score(None, &stakes)), vec![10, 6, 5, 9]);
+}
+
+proptest! {
+    #[test]
+    fn nuts_are_conserved(stakes in stakes(), winner in prop::option::of(1..=2)) {
+        let payouts = settle(winner, &stakes);
+
+        let pot: i32 = stakes.iter().map(|s| s.nuts).sum();
+        prop_assert_eq!(payouts.iter().map(|p| p.nuts).sum::<i32>(), pot);
+    }
+
+    #[test]
+    fn winning_bets_never_lose_nuts(stakes in stakes(), winner in 1..=2) {
+        let payouts = settle(Some(winner), &stakes);
+        let someone_won = stakes.iter().any(|s| s.team_id == winner);
+
+        for (stake, payout) in stakes.iter().zip(payouts.iter()) {
+            prop_assert_eq!(payout.user_id, stake.user_id);
+            if !someone_won || stake.team_id == winner {
+                prop_assert!(payout.gain(stake) >= 0);
+            } else {
+                prop_assert_eq!(payout.nuts, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn parts_are_within_one_nut_of_the_exact_share(stakes in stakes(), winner in 1..=2) {
+        let payouts = settle(Some(winner), &stakes);
+
+        let winning: i64 = stakes.iter().filter(|s| s.team_id == winner).map(|s| s.nuts as i64).sum();
+        let losing: i64 = stakes.iter().filter(|s| s.team_id != winner).map(|s| s.nuts as i64).sum();
+        prop_assume!(winning > 0);
+
+        for (stake, payout) in stakes.iter().zip(payouts.iter()).filter(|(s, _)| s.team_id == winner) {
+            let exact = losing * stake.nuts as i64;
+            let part = payout.gain(stake) as i64 * winning;
+            prop_assert!(part <= exact + winning && part + winning > exact);
+        }
+    }
+
+    #[test]
+    fn preview_is_the_settled_payout(stakes in stakes(), user in 1..=25, team in 1..=2, nuts in 1..=10_000) {
+        let payout = preview(&stakes, stake(user, team, nuts));
+
+        let mut placed: Vec<Stake> = stakes.iter().copied().filter(|s| s.user_id != user).collect();
+        placed.push(stake(user, team, nuts));
+        let settled = settle(Some(team), &placed);
+        let settled = settled.iter().find(|p| p.user_id == user).unwrap();
+
+        // leftover nuts may go to another bet depending on its place, never more than one
+        prop_assert_eq!(payout.user_id, user);
+        prop_assert!((payout.nuts - settled.nuts).abs() <= 1);
+    }
+}
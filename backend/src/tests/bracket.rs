@@ -0,0 +1,141 @@
+use crate::bracket::{Bracket, Layout, Outcome, Slot};
+
+fn all_slots(layout: &Layout) -> Vec<Slot> {
+    let mut slots = vec![];
+
+    for phase in 1..=layout.rounds {
+        for place in 0..layout.games_in_round(Bracket::Upper, phase) {
+            slots.push(Slot::new(Bracket::Upper, phase, place));
+        }
+    }
+
+    if layout.double_elim {
+        for phase in 1..=layout.lower_rounds() {
+            for place in 0..layout.games_in_round(Bracket::Lower, phase) {
+                slots.push(Slot::new(Bracket::Lower, phase, place));
+            }
+        }
+        slots.push(Slot::new(Bracket::GrandFinal, 1, 0));
+    }
+
+    if layout.third_place {
+        slots.push(Slot::new(Bracket::ThirdPlace, 1, 0));
+    }
+
+    slots
+}
+
+#[test]
+fn layout_rounds_from_first_round() {
+    assert_eq!(Layout::new(1, false).rounds, 1);
+    assert_eq!(Layout::new(2, false).rounds, 2);
+    assert_eq!(Layout::new(4, false).rounds, 3);
+    assert_eq!(Layout::new(64, true).rounds, 7);
+}
+
+#[test]
+fn single_elim_winner_goes_up() {
+    let layout = Layout::new(4, false);
+
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Upper, 1, 3), Outcome::Winner),
+        Some(Slot::new(Bracket::Upper, 2, 1))
+    );
+    assert_eq!(layout.next(Slot::new(Bracket::Upper, 1, 3), Outcome::Loser), None);
+    assert_eq!(layout.next(Slot::new(Bracket::Upper, 3, 0), Outcome::Winner), None);
+}
+
+#[test]
+fn semi_finals_losers_play_for_third_place() {
+    let layout = Layout::new(4, false).with_third_place(true);
+
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Upper, 2, 1), Outcome::Loser),
+        Some(Slot::new(Bracket::ThirdPlace, 1, 0))
+    );
+    assert_eq!(layout.next(Slot::new(Bracket::Upper, 1, 0), Outcome::Loser), None);
+    assert_eq!(layout.next(Slot::new(Bracket::Upper, 3, 0), Outcome::Loser), None);
+
+    // no semi-finals with two teams, and the lower bracket ranks the losers of a double elimination
+    assert!(!Layout::new(1, false).with_third_place(true).third_place);
+    assert!(!Layout::new(4, true).with_third_place(true).third_place);
+}
+
+#[test]
+fn double_elim_losers_drop_in_lower_bracket() {
+    let layout = Layout::new(4, true);
+
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Upper, 1, 3), Outcome::Loser),
+        Some(Slot::new(Bracket::Lower, 1, 1))
+    );
+    // Second round losers are dropped in reversed order
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Upper, 2, 0), Outcome::Loser),
+        Some(Slot::new(Bracket::Lower, 2, 1))
+    );
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Upper, 3, 0), Outcome::Loser),
+        Some(Slot::new(Bracket::Lower, 4, 0))
+    );
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Lower, 4, 0), Outcome::Winner),
+        Some(Slot::new(Bracket::GrandFinal, 1, 0))
+    );
+}
+
+#[test]
+fn double_elim_with_two_teams_plays_a_grand_final() {
+    let layout = Layout::new(1, true);
+
+    assert_eq!(layout.lower_rounds(), 0);
+    assert_eq!(
+        layout.next(Slot::new(Bracket::Upper, 1, 0), Outcome::Loser),
+        Some(Slot::new(Bracket::GrandFinal, 1, 0))
+    );
+}
+
+#[test]
+fn every_game_is_fed_by_the_games_it_feeds() {
+    for first_round in [1, 2, 4, 8, 16, 32, 64] {
+        for (double_elim, third_place) in [(false, false), (false, true), (true, false)] {
+            let layout = Layout::new(first_round, double_elim).with_third_place(third_place);
+            let slots = all_slots(&layout);
+
+            for slot in slots.iter() {
+                for outcome in [Outcome::Winner, Outcome::Loser] {
+                    if let Some(next) = layout.next(*slot, outcome) {
+                        assert!(slots.contains(&next), "{:?} is out of the layout", next);
+
+                        let feeds = layout.feeds(next).expect("a reached game must be fed");
+                        assert!(feeds
+                            .iter()
+                            .any(|feed| feed.slot == *slot && feed.outcome == outcome));
+                    }
+                }
+
+                if let Some(feeds) = layout.feeds(*slot) {
+                    for feed in feeds {
+                        assert_eq!(layout.next(feed.slot, feed.outcome), Some(*slot));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn double_elim_eliminates_everyone_but_one_team() {
+    for first_round in [1, 2, 4, 8, 16] {
+        let layout = Layout::new(first_round, true);
+        let teams = 2 * first_round;
+
+        // Every game eliminates its loser once it is the team second loss
+        let eliminations = all_slots(&layout)
+            .iter()
+            .filter(|slot| layout.next(**slot, Outcome::Loser).is_none())
+            .count();
+
+        assert_eq!(eliminations, teams - 1);
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::calendar::{escape, fold, render, sequence, Event};
+
+fn date(hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2023, 9, 20)
+        .unwrap()
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+}
+
+#[test]
+fn text_values_are_escaped() {
+    assert_eq!(escape("Lausanne, Suisse"), "Lausanne\\, Suisse");
+    assert_eq!(escape(r"a;b\c"), r"a\;b\\c");
+    assert_eq!(escape("Score\r\n2 - 1"), "Score\\n2 - 1");
+}
+
+#[test]
+fn long_lines_are_folded() {
+    let line = format!("SUMMARY:{}", "é".repeat(60));
+    let folded = fold(&line);
+
+    for physical in folded.split("\r\n") {
+        assert!(physical.len() <= 75);
+    }
+    // unfolding gives the line back
+    assert_eq!(folded.replace("\r\n ", ""), line);
+    assert_eq!(fold("SUMMARY:court"), "SUMMARY:court");
+}
+
+#[test]
+fn calendar_has_one_event_per_game() {
+    let event = Event {
+        uid: "game-1@tournoix".into(),
+        start: date(9),
+        end: date(9) + Duration::minutes(30),
+        summary: "Team A - Team B".into(),
+        location: Some("Terrain 1, Yverdon".into()),
+        description: "En attente".into(),
+        sequence: 0,
+    };
+
+    let calendar = render("Tournoi", date(8), &[event.clone(), event]);
+
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+    assert!(calendar.contains("DTSTART:20230920T090000\r\n"));
+    assert!(calendar.contains("DTEND:20230920T093000\r\n"));
+    assert!(calendar.contains("DTSTAMP:20230920T080000Z\r\n"));
+    assert!(calendar.contains("LOCATION:Terrain 1\\, Yverdon\r\n"));
+}
+
+#[test]
+fn sequence_is_raised_by_every_change() {
+    let scheduled = sequence(date(8));
+    let rescheduled = sequence(date(8) + Duration::seconds(1));
+
+    assert!(rescheduled > scheduled);
+    assert!(sequence(date(9)) > rescheduled);
+    // before the epoch it can't go below 0
+    assert_eq!(
+        sequence(
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        ),
+        0
+    );
+}
@@ -0,0 +1,70 @@
+use crate::crypto::{
+    hash_password, needs_rehash, password_violations, validate_password, verify_password,
+    HashConfig, PasswordRule,
+};
+
+// cheap enough for the tests
+const CONFIG: HashConfig = HashConfig {
+    mem_cost: 1024,
+    time_cost: 1,
+    lanes: 1,
+};
+
+#[test]
+fn strong_password_breaks_no_rule() {
+    assert!(password_violations("Password123!").is_empty());
+    assert!(validate_password("Password123!"));
+}
+
+#[test]
+fn every_broken_rule_is_reported() {
+    assert_eq!(
+        password_violations("abc"),
+        vec![
+            PasswordRule::TooShort,
+            PasswordRule::NoUppercase,
+            PasswordRule::NoDigit,
+            PasswordRule::NoSpecial,
+        ]
+    );
+    assert_eq!(
+        password_violations(&format!("Aa1!{}", "a".repeat(61))),
+        vec![PasswordRule::TooLong]
+    );
+    assert!(!validate_password("password123!"));
+}
+
+#[test]
+fn length_counts_characters() {
+    // 8 characters but more than 8 bytes
+    assert!(password_violations("Éé1!éééé").is_empty());
+}
+
+#[test]
+fn hash_matches_its_password_only() {
+    let hash = hash_password("Password123!", &CONFIG).unwrap();
+
+    assert!(verify_password(&hash, "Password123!"));
+    assert!(!verify_password(&hash, "Password123?"));
+}
+
+#[test]
+fn malformed_hash_matches_no_password() {
+    assert!(!verify_password("not a hash", "Password123!"));
+    assert!(!verify_password("", ""));
+}
+
+#[test]
+fn hash_made_with_other_parameters_needs_rehash() {
+    let hash = hash_password("Password123!", &CONFIG).unwrap();
+
+    assert!(!needs_rehash(&hash, &CONFIG));
+    assert!(needs_rehash(
+        &hash,
+        &HashConfig {
+            time_cost: 2,
+            ..CONFIG
+        }
+    ));
+    assert!(needs_rehash("not a hash", &CONFIG));
+}
@@ -0,0 +1,17 @@
+use crate::events::{EventKind, Events};
+
+#[test]
+fn streams_get_the_events_sent_after_they_subscribed() {
+    let events = Events::new();
+    // nobody listens, the event is dropped
+    events.send(1, EventKind::TeamsChanged, None);
+
+    let mut receiver = events.subscribe();
+    events.send(1, EventKind::GameClosed, Some(4));
+
+    let event = receiver.try_recv().unwrap();
+    assert_eq!(event.tournament_id, 1);
+    assert_eq!(event.kind, EventKind::GameClosed);
+    assert_eq!(event.game_id, Some(4));
+    assert!(receiver.try_recv().is_err());
+}
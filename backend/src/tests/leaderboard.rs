@@ -0,0 +1,85 @@
+use crate::leaderboard::{leaderboard, Totals};
+
+fn totals(user_id: i32, won: i32, lost: i32, staked: i32, net: i32) -> Totals {
+    Totals {
+        user_id,
+        bets: won + lost,
+        won,
+        lost,
+        staked,
+        net,
+    }
+}
+
+#[test]
+fn records_keep_the_totals_of_each_user() {
+    let records = leaderboard(&[
+        Totals {
+            user_id: 1,
+            bets: 3,
+            won: 1,
+            lost: 1,
+            staked: 35,
+            net: -5,
+        },
+        totals(2, 0, 1, 10, -10),
+    ]);
+
+    let first = records.iter().find(|r| r.user_id == 1).unwrap();
+    assert_eq!(first.bets, 3);
+    assert_eq!(first.won, 1);
+    assert_eq!(first.lost, 1);
+    assert_eq!(first.staked, 35);
+    assert_eq!(first.net, -5);
+    assert_eq!(first.accuracy, Some(0.5));
+    assert_eq!(first.roi, Some(-5.0 / 35.0));
+}
+
+#[test]
+fn only_refunds_give_no_accuracy() {
+    let records = leaderboard(&[Totals {
+        user_id: 1,
+        bets: 1,
+        won: 0,
+        lost: 0,
+        staked: 10,
+        net: 0,
+    }]);
+
+    assert_eq!(records[0].accuracy, None);
+    assert_eq!(records[0].roi, Some(0.0));
+}
+
+#[test]
+fn best_net_comes_first() {
+    let records = leaderboard(&[
+        totals(1, 0, 1, 10, -10),
+        totals(2, 1, 0, 10, 20),
+        totals(3, 1, 0, 10, 5),
+    ]);
+
+    let users: Vec<i32> = records.iter().map(|r| r.user_id).collect();
+    assert_eq!(users, vec![2, 3, 1]);
+    let ranks: Vec<i32> = records.iter().map(|r| r.rank).collect();
+    assert_eq!(ranks, vec![1, 2, 3]);
+}
+
+#[test]
+fn ties_share_their_rank_and_are_broken_by_roi() {
+    // same net, user 2 risked less to get it
+    let records = leaderboard(&[
+        totals(1, 1, 0, 100, 10),
+        totals(2, 1, 0, 10, 10),
+        totals(3, 0, 1, 10, -10),
+    ]);
+
+    let users: Vec<i32> = records.iter().map(|r| r.user_id).collect();
+    assert_eq!(users, vec![2, 1, 3]);
+    let ranks: Vec<i32> = records.iter().map(|r| r.rank).collect();
+    assert_eq!(ranks, vec![1, 1, 3]);
+}
+
+#[test]
+fn no_bet_gives_an_empty_leaderboard() {
+    assert!(leaderboard(&[]).is_empty());
+}
@@ -0,0 +1,57 @@
+use std::fs;
+
+use crate::mailer::{reset_mail, verification_mail, FileMailer, Mail, Mailer};
+
+#[test]
+fn verification_mail_links_to_the_token() {
+    let mail = verification_mail("john.doe@tournoix.com", "John", "https://tournoix.ch", "abc");
+
+    assert_eq!(mail.to, "john.doe@tournoix.com");
+    assert!(mail.body.contains("https://tournoix.ch/verify/abc"));
+}
+
+#[test]
+fn reset_mail_links_to_the_token() {
+    let mail = reset_mail("john.doe@tournoix.com", "John", "https://tournoix.ch", "abc");
+
+    assert_eq!(mail.to, "john.doe@tournoix.com");
+    assert!(mail.body.contains("https://tournoix.ch/reset-password/abc"));
+}
+
+#[test]
+fn file_mailer_writes_every_mail() {
+    let dir = std::env::temp_dir().join(format!("tournoix-mails-{}", crate::crypto::generate_token()));
+    let mailer = FileMailer {
+        dir: Some(dir.clone()),
+    };
+    let mail = Mail {
+        to: "john.doe@tournoix.com".into(),
+        subject: "Sujet".into(),
+        body: "Contenu".into(),
+    };
+
+    mailer.send(&mail).unwrap();
+    mailer.send(&mail).unwrap();
+
+    let files: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|f| fs::read_to_string(f.unwrap().path()).unwrap())
+        .collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|f| f.contains("Subject: Sujet") && f.contains("Contenu")));
+}
+
+#[test]
+fn file_mailer_without_directory_only_logs() {
+    let mailer = FileMailer { dir: None };
+
+    assert!(mailer
+        .send(&Mail {
+            to: "john.doe@tournoix.com".into(),
+            subject: "Sujet".into(),
+            body: "Contenu".into(),
+        })
+        .is_ok());
+}
@@ -1 +1,31 @@
 pub mod api;
+#[cfg(test)]
+pub mod bracket;
+#[cfg(test)]
+pub mod swiss;
+#[cfg(test)]
+pub mod standings;
+#[cfg(test)]
+pub mod seeding;
+#[cfg(test)]
+pub mod series;
+#[cfg(test)]
+pub mod scheduler;
+#[cfg(test)]
+pub mod calendar;
+#[cfg(test)]
+pub mod events;
+#[cfg(test)]
+pub mod ranking;
+#[cfg(test)]
+pub mod betting;
+#[cfg(test)]
+pub mod bets;
+#[cfg(test)]
+pub mod leaderboard;
+#[cfg(test)]
+pub mod mailer;
+#[cfg(test)]
+pub mod crypto;
+#[cfg(test)]
+pub mod rate_limit;
@@ -0,0 +1,88 @@
+use crate::bracket::{Bracket, Layout, Slot};
+use crate::ranking::{ranking, EliminationResult};
+use crate::standings::GameResult;
+
+fn elimination(bracket: Bracket, phase: i32, place: i32, winner: i32, loser: i32) -> EliminationResult {
+    EliminationResult {
+        slot: Slot::new(bracket, phase, place),
+        winner,
+        loser,
+    }
+}
+
+fn places(ranking: &[crate::ranking::Placement]) -> Vec<(i32, i32)> {
+    ranking.iter().map(|p| (p.team_id, p.place)).collect()
+}
+
+#[test]
+fn semi_finalists_share_the_third_place() {
+    // 1 beats 4, 3 beats 2, 1 wins the final, 5 and 6 didn't qualify
+    let layout = Layout::new(2, false);
+    let eliminations = [
+        elimination(Bracket::Upper, 1, 0, 1, 4),
+        elimination(Bracket::Upper, 1, 1, 3, 2),
+        elimination(Bracket::Upper, 2, 0, 1, 3),
+    ];
+
+    let ranking = ranking(&layout, &[1, 4, 3, 2], &eliminations, &[1, 2, 3, 4, 6, 5], &[]);
+
+    assert_eq!(
+        places(&ranking),
+        vec![(1, 1), (3, 2), (2, 3), (4, 3), (6, 5), (5, 6)]
+    );
+}
+
+#[test]
+fn third_place_game_splits_the_semi_finalists() {
+    let layout = Layout::new(2, false).with_third_place(true);
+    let eliminations = [
+        elimination(Bracket::Upper, 1, 0, 1, 4),
+        elimination(Bracket::Upper, 1, 1, 3, 2),
+        elimination(Bracket::ThirdPlace, 2, 0, 4, 2),
+    ];
+
+    // the final is still to play
+    let ranking = ranking(&layout, &[1, 4, 3, 2], &eliminations, &[1, 2, 3, 4], &[]);
+
+    assert_eq!(places(&ranking), vec![(1, 1), (3, 1), (4, 3), (2, 4)]);
+}
+
+#[test]
+fn double_elimination_ranks_by_lower_bracket_round() {
+    // 4 teams: upper 1-4 and 2-3, lower 4-3, upper final 1-2, lower final 2-4, grand final 1-2
+    let layout = Layout::new(2, true);
+    let eliminations = [
+        elimination(Bracket::Upper, 1, 0, 1, 4),
+        elimination(Bracket::Upper, 1, 1, 2, 3),
+        elimination(Bracket::Lower, 1, 0, 4, 3),
+        elimination(Bracket::Upper, 2, 0, 1, 2),
+        elimination(Bracket::Lower, 2, 0, 2, 4),
+        elimination(Bracket::GrandFinal, 1, 0, 2, 1),
+        elimination(Bracket::GrandFinal, 2, 0, 1, 2),
+    ];
+
+    let ranking = ranking(&layout, &[1, 4, 2, 3], &eliminations, &[], &[]);
+
+    assert_eq!(places(&ranking), vec![(1, 1), (2, 2), (4, 3), (3, 4)]);
+}
+
+#[test]
+fn record_counts_every_game_but_byes() {
+    let layout = Layout::new(1, false);
+    let eliminations = [elimination(Bracket::Upper, 1, 0, 2, 1)];
+    let results = [
+        GameResult { team1: 1, team2: 2, score1: 3, score2: 3 },
+        GameResult { team1: 1, team2: 1, score1: 0, score2: 0 },
+        GameResult { team1: 2, team2: 1, score1: 5, score2: 2 },
+    ];
+
+    let ranking = ranking(&layout, &[1, 2], &eliminations, &[1, 2], &results);
+    let runner_up = &ranking[1];
+
+    assert_eq!(runner_up.team_id, 1);
+    assert_eq!(
+        (runner_up.played, runner_up.won, runner_up.drawn, runner_up.lost),
+        (2, 0, 1, 1)
+    );
+    assert_eq!((runner_up.scored, runner_up.conceded), (5, 8));
+}
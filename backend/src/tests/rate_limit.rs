@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant};
+
+use crate::rate_limit::{
+    lockout_duration, seconds, Bucket, BucketConfig, LockoutConfig, MemoryStore, RateLimitStore,
+};
+
+const BUCKET: BucketConfig = BucketConfig {
+    capacity: 3,
+    refill_seconds: 10,
+};
+
+const LOCKOUT: LockoutConfig = LockoutConfig {
+    max_failures: 3,
+    base_seconds: 30,
+    max_seconds: 300,
+    forget_seconds: 3600,
+};
+
+#[test]
+fn bucket_allows_a_burst_then_asks_to_wait() {
+    let now = Instant::now();
+    let mut bucket = Bucket::new(&BUCKET, now);
+
+    assert!(bucket.take(now).is_ok());
+    assert!(bucket.take(now).is_ok());
+    assert!(bucket.take(now).is_ok());
+    assert_eq!(bucket.take(now), Err(Duration::from_secs(10)));
+}
+
+#[test]
+fn bucket_refills_with_time() {
+    let now = Instant::now();
+    let mut bucket = Bucket::new(&BUCKET, now);
+    for _ in 0..3 {
+        bucket.take(now).unwrap();
+    }
+
+    // half a request back
+    let later = now + Duration::from_secs(5);
+    assert_eq!(bucket.take(later), Err(Duration::from_secs(5)));
+
+    let later = now + Duration::from_secs(10);
+    assert!(bucket.take(later).is_ok());
+    assert!(bucket.take(later).is_err());
+}
+
+#[test]
+fn bucket_never_exceeds_its_capacity() {
+    let now = Instant::now();
+    let mut bucket = Bucket::new(&BUCKET, now);
+    bucket.take(now).unwrap();
+
+    let later = now + Duration::from_secs(3600);
+    for _ in 0..3 {
+        assert!(bucket.take(later).is_ok());
+    }
+    assert!(bucket.take(later).is_err());
+}
+
+#[test]
+fn lockout_doubles_up_to_the_maximum() {
+    assert_eq!(lockout_duration(0, &LOCKOUT), None);
+    assert_eq!(lockout_duration(2, &LOCKOUT), None);
+    assert_eq!(lockout_duration(3, &LOCKOUT), Some(Duration::from_secs(30)));
+    assert_eq!(lockout_duration(4, &LOCKOUT), Some(Duration::from_secs(60)));
+    assert_eq!(lockout_duration(5, &LOCKOUT), Some(Duration::from_secs(120)));
+    assert_eq!(lockout_duration(7, &LOCKOUT), Some(Duration::from_secs(300)));
+    assert_eq!(lockout_duration(u32::MAX, &LOCKOUT), Some(Duration::from_secs(300)));
+}
+
+#[test]
+fn seconds_are_rounded_up() {
+    assert_eq!(seconds(Duration::from_secs(5)), 5);
+    assert_eq!(seconds(Duration::from_millis(5001)), 6);
+    assert_eq!(seconds(Duration::ZERO), 1);
+}
+
+#[test]
+fn memory_store_keeps_a_bucket_per_key() {
+    let store = MemoryStore::default();
+    let now = Instant::now();
+
+    for _ in 0..3 {
+        assert!(store.take("login:ip:1.1.1.1", &BUCKET, now).is_ok());
+    }
+    assert!(store.take("login:ip:1.1.1.1", &BUCKET, now).is_err());
+    assert!(store.take("login:ip:2.2.2.2", &BUCKET, now).is_ok());
+}
+
+#[test]
+fn memory_store_locks_after_failures() {
+    let store = MemoryStore::default();
+    let now = Instant::now();
+
+    assert_eq!(store.record_failure("account", &LOCKOUT, now), None);
+    assert_eq!(store.record_failure("account", &LOCKOUT, now), None);
+    assert_eq!(store.locked("account", now), None);
+
+    assert_eq!(
+        store.record_failure("account", &LOCKOUT, now),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(
+        store.locked("account", now + Duration::from_secs(10)),
+        Some(Duration::from_secs(20))
+    );
+    assert_eq!(store.locked("account", now + Duration::from_secs(30)), None);
+    assert_eq!(store.locked("other", now), None);
+
+    // each new failure locks longer
+    let later = now + Duration::from_secs(30);
+    assert_eq!(
+        store.record_failure("account", &LOCKOUT, later),
+        Some(Duration::from_secs(60))
+    );
+}
+
+#[test]
+fn memory_store_forgets_failures() {
+    let store = MemoryStore::default();
+    let now = Instant::now();
+
+    for _ in 0..3 {
+        store.record_failure("account", &LOCKOUT, now);
+    }
+    store.clear_failures("account");
+    assert_eq!(store.locked("account", now), None);
+    assert_eq!(store.record_failure("account", &LOCKOUT, now), None);
+
+    store.record_failure("account", &LOCKOUT, now);
+    let later = now + Duration::from_secs(3600);
+    assert_eq!(store.record_failure("account", &LOCKOUT, later), None);
+}
+
+#[test]
+fn memory_store_drops_the_least_recently_used_bucket() {
+    let store = MemoryStore::with_capacity(2);
+    let now = Instant::now();
+
+    for _ in 0..3 {
+        store.take("a", &BUCKET, now).unwrap();
+        store.take("b", &BUCKET, now).unwrap();
+    }
+    // a used last, b is dropped for c
+    assert!(store.take("a", &BUCKET, now).is_err());
+    assert!(store.take("c", &BUCKET, now).is_ok());
+
+    assert!(store.take("a", &BUCKET, now).is_err());
+    assert!(store.take("b", &BUCKET, now).is_ok());
+}
+
+#[test]
+fn memory_store_drops_the_oldest_failures() {
+    let store = MemoryStore::with_capacity(2);
+    let now = Instant::now();
+
+    store.record_failure("a", &LOCKOUT, now);
+    store.record_failure("a", &LOCKOUT, now);
+    store.record_failure("b", &LOCKOUT, now);
+    store.record_failure("c", &LOCKOUT, now);
+
+    // the two failures of a are forgotten, a third one doesn't lock it
+    assert_eq!(store.record_failure("a", &LOCKOUT, now), None);
+}
+
+#[test]
+fn memory_store_keeps_long_keys() {
+    let store = MemoryStore::default();
+    let now = Instant::now();
+    let key = format!("login:account:{}", "a".repeat(100_000));
+
+    for _ in 0..3 {
+        assert!(store.take(&key, &BUCKET, now).is_ok());
+    }
+    assert!(store.take(&key, &BUCKET, now).is_err());
+}
+
+#[test]
+fn memory_store_keeps_a_locked_account() {
+    let store = MemoryStore::with_capacity(2);
+    let now = Instant::now();
+
+    for _ in 0..3 {
+        store.record_failure("victim", &LOCKOUT, now);
+    }
+    for other in 0..100 {
+        store.record_failure(&format!("other{}", other), &LOCKOUT, now);
+    }
+
+    assert_eq!(store.locked("victim", now), Some(Duration::from_secs(30)));
+
+    // the lockout is over, the account can be dropped
+    let later = now + Duration::from_secs(30);
+    store.record_failure("other", &LOCKOUT, later);
+    store.record_failure("another", &LOCKOUT, later);
+    assert_eq!(store.locked("victim", now), None);
+}
+
+#[test]
+fn memory_store_skips_failures_once_every_account_is_locked() {
+    let store = MemoryStore::with_capacity(1);
+    let now = Instant::now();
+
+    for _ in 0..3 {
+        store.record_failure("victim", &LOCKOUT, now);
+    }
+
+    assert_eq!(store.record_failure("other", &LOCKOUT, now), None);
+    assert_eq!(store.locked("victim", now), Some(Duration::from_secs(30)));
+}
@@ -0,0 +1,191 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::bracket::{Bracket, Layout, Slot};
+use crate::scheduler::{
+    elimination_wave, is_betting_over, schedule, Booking, Fixture, ScheduleConfig,
+};
+
+fn start() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2023, 9, 20)
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap()
+}
+
+fn config(courts: i32, min_rest: i64) -> ScheduleConfig {
+    ScheduleConfig {
+        start: start(),
+        match_duration: 30,
+        break_duration: 10,
+        courts,
+        min_rest,
+    }
+}
+
+fn fixture(id: i32, team1: i32, team2: i32, wave: i32) -> Fixture {
+    Fixture {
+        id,
+        team1,
+        team2,
+        wave,
+    }
+}
+
+fn booking(team1: i32, team2: i32, start: NaiveDateTime, court: i32, wave: i32) -> Booking {
+    Booking {
+        team1,
+        team2,
+        start,
+        court,
+        wave,
+    }
+}
+
+#[test]
+fn games_are_played_in_parallel_on_every_court() {
+    let slots = schedule(
+        &config(2, 0),
+        &[],
+        &[
+            fixture(1, 1, 2, 0),
+            fixture(2, 3, 4, 0),
+            fixture(3, 5, 6, 0),
+        ],
+    );
+
+    assert_eq!(slots[0].start, start());
+    assert_eq!(slots[1].start, start());
+    assert_ne!(slots[0].court, slots[1].court);
+    // the third game waits for a court and its changeover
+    assert_eq!(slots[2].start, start() + Duration::minutes(40));
+}
+
+#[test]
+fn teams_never_overlap_and_rest_between_games() {
+    // round robin of 6 teams
+    let mut fixtures = vec![];
+    for team1 in 1..=6 {
+        for team2 in team1 + 1..=6 {
+            fixtures.push(fixture(fixtures.len() as i32, team1, team2, 0));
+        }
+    }
+
+    let slots = schedule(&config(3, 20), &[], &fixtures);
+    assert_eq!(slots.len(), fixtures.len());
+
+    for team in 1..=6 {
+        let mut starts: Vec<NaiveDateTime> = slots
+            .iter()
+            .filter(|s| {
+                let f = fixtures.iter().find(|f| f.id == s.game_id).unwrap();
+                f.team1 == team || f.team2 == team
+            })
+            .map(|s| s.start)
+            .collect();
+        starts.sort();
+
+        for pair in starts.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::minutes(30 + 20));
+        }
+    }
+
+    for court in 1..=3 {
+        let mut starts: Vec<NaiveDateTime> = slots
+            .iter()
+            .filter(|s| s.court == court)
+            .map(|s| s.start)
+            .collect();
+        starts.sort();
+
+        for pair in starts.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::minutes(30 + 10));
+        }
+    }
+}
+
+#[test]
+fn next_wave_waits_for_the_previous_one() {
+    let slots = schedule(
+        &config(4, 0),
+        &[],
+        &[fixture(1, 1, 2, 0), fixture(2, 3, 4, 1)],
+    );
+
+    assert_eq!(slots[1].start, start() + Duration::minutes(40));
+}
+
+#[test]
+fn rerun_after_the_first_round_keeps_the_played_games() {
+    // round 1 was played from 9:00 on both courts, the second pair of games at 9:40
+    let booked = [
+        booking(1, 2, start(), 1, 1),
+        booking(3, 4, start(), 2, 1),
+        booking(5, 6, start() + Duration::minutes(40), 1, 1),
+        booking(7, 8, start() + Duration::minutes(40), 2, 1),
+    ];
+
+    // the new run starts earlier than the games already played
+    let slots = schedule(
+        &config(2, 0),
+        &booked,
+        &[fixture(1, 1, 3, 2), fixture(2, 5, 7, 2)],
+    );
+
+    // round 2 waits for the end of round 1 and its break
+    let round_1_end = start() + Duration::minutes(40 + 30);
+    for slot in slots.iter() {
+        assert_eq!(slot.start, round_1_end + Duration::minutes(10));
+    }
+    assert_ne!(slots[0].court, slots[1].court);
+}
+
+#[test]
+fn booked_games_keep_their_court_and_teams_busy() {
+    // a game of the same wave is still on court 1, teams 1 and 2 are playing it
+    let booked = [booking(1, 2, start(), 1, 0)];
+
+    let slots = schedule(
+        &config(2, 20),
+        &booked,
+        &[fixture(1, 3, 4, 0), fixture(2, 1, 5, 0)],
+    );
+
+    assert_eq!(slots[0].start, start());
+    assert_eq!(slots[0].court, 2);
+    // team 1 rests after the booked game
+    assert_eq!(slots[1].start, start() + Duration::minutes(30 + 20));
+}
+
+#[test]
+fn elimination_rounds_follow_the_bracket() {
+    let layout = Layout::new(4, true);
+
+    assert_eq!(
+        elimination_wave(&layout, Slot::new(Bracket::Upper, 1, 0)),
+        1
+    );
+    assert_eq!(
+        elimination_wave(&layout, Slot::new(Bracket::Lower, 1, 0)),
+        2
+    );
+    // the lower final is played after the upper final
+    assert!(
+        elimination_wave(&layout, Slot::new(Bracket::Lower, layout.lower_rounds(), 0))
+            > elimination_wave(&layout, Slot::new(Bracket::Upper, layout.rounds, 0))
+    );
+    assert_eq!(
+        elimination_wave(&layout, Slot::new(Bracket::GrandFinal, 1, 0)),
+        elimination_wave(&layout, Slot::new(Bracket::Lower, layout.lower_rounds(), 0)) + 1
+    );
+}
+
+#[test]
+fn bets_close_ahead_of_the_game() {
+    let game = start() + Duration::minutes(60);
+
+    assert!(!is_betting_over(start() + Duration::minutes(49), game, 10));
+    assert!(is_betting_over(start() + Duration::minutes(50), game, 10));
+    // without lead time the bets are open until the game starts
+    assert!(!is_betting_over(start() + Duration::minutes(59), game, 0));
+    assert!(is_betting_over(game, game, 0));
+}
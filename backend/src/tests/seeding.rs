@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::seeding::{bracket_positions, first_round, qualifiers, seeds_from_first_round, Qualifier};
+use crate::standings::Standing;
+
+fn standing(team_id: i32, points: i32, score_diff: i32) -> Standing {
+    Standing {
+        team_id,
+        played: 3,
+        won: 0,
+        drawn: 0,
+        lost: 0,
+        scored: score_diff.max(0),
+        conceded: (-score_diff).max(0),
+        score_diff,
+        points,
+    }
+}
+
+// groups of four teams, team ids are group * 10 + place
+fn groups(nb_groups: i32) -> BTreeMap<i32, Vec<Standing>> {
+    (1..=nb_groups)
+        .map(|group| {
+            let standings = (1..=4)
+                .map(|place| standing(group * 10 + place, 12 - 3 * place - group, 4 - place))
+                .collect();
+            (group, standings)
+        })
+        .collect()
+}
+
+fn qualifier(team_id: i32, group: i32) -> Qualifier {
+    Qualifier {
+        team_id,
+        group,
+        rank: 1,
+    }
+}
+
+#[test]
+fn positions_keep_best_seeds_apart() {
+    assert_eq!(bracket_positions(2), vec![0, 1]);
+    assert_eq!(bracket_positions(4), vec![0, 3, 1, 2]);
+    assert_eq!(bracket_positions(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+}
+
+#[test]
+fn top_k_of_each_group_qualify_by_rank() {
+    let seeds = qualifiers(&groups(3), 2, 0, 0);
+    let ids: Vec<i32> = seeds.iter().map(|q| q.team_id).collect();
+
+    // the first groups have more points at every place
+    assert_eq!(ids, vec![11, 21, 31, 12, 22, 32]);
+    assert!(seeds.iter().all(|q| q.rank <= 2));
+}
+
+#[test]
+fn best_next_placed_teams_get_wildcards() {
+    let seeds = qualifiers(&groups(3), 2, 2, 0);
+    let ids: Vec<i32> = seeds.iter().map(|q| q.team_id).collect();
+
+    assert_eq!(seeds.len(), 8);
+    assert_eq!(&ids[6..], &[13, 23]);
+    assert!(!ids.contains(&33));
+}
+
+#[test]
+fn best_seeds_get_the_byes() {
+    let seeds: Vec<Qualifier> = (1..=5).map(|id| qualifier(id, id)).collect();
+    let games = first_round(&seeds);
+
+    assert_eq!(games.len(), 4);
+    assert_eq!(games[0], (1, None));
+    assert_eq!(games.iter().filter(|(_, team2)| team2.is_none()).count(), 3);
+    assert!(games.contains(&(4, Some(5))));
+}
+
+#[test]
+fn same_group_teams_are_kept_apart() {
+    for nb_groups in 2..=8 {
+        let seeds = qualifiers(&groups(nb_groups), 2, 0, nb_groups as u64);
+        let group_of: HashMap<i32, i32> = seeds.iter().map(|q| (q.team_id, q.group)).collect();
+
+        let games = first_round(&seeds);
+        let mut teams: Vec<i32> = vec![];
+
+        for (team1, team2) in games.iter() {
+            teams.push(*team1);
+            if let Some(team2) = team2 {
+                teams.push(*team2);
+                assert_ne!(group_of[team1], group_of[team2], "{} groups", nb_groups);
+            }
+        }
+
+        teams.sort();
+        let mut expected: Vec<i32> = seeds.iter().map(|q| q.team_id).collect();
+        expected.sort();
+        assert_eq!(teams, expected);
+    }
+}
+
+#[test]
+fn teams_without_group_can_meet() {
+    let seeds: Vec<Qualifier> = (1..=4).map(|id| qualifier(id, 0)).collect();
+
+    assert_eq!(first_round(&seeds), vec![(1, Some(4)), (2, Some(3))]);
+}
+
+#[test]
+fn any_number_of_teams_fills_the_first_round() {
+    for nb_teams in 2..=128 {
+        let seeds: Vec<Qualifier> = (1..=nb_teams).map(|id| qualifier(id, 0)).collect();
+        let games = first_round(&seeds);
+        let size = (nb_teams as usize).next_power_of_two();
+
+        assert_eq!(games.len(), size / 2);
+        assert_eq!(
+            games.iter().filter(|(_, team2)| team2.is_none()).count(),
+            size - nb_teams as usize
+        );
+
+        // byes go to the best seeds
+        for (team1, team2) in games.iter() {
+            if team2.is_none() {
+                assert!(*team1 <= (size - nb_teams as usize) as i32);
+            }
+        }
+    }
+}
+
+#[test]
+fn seeds_are_read_back_from_the_first_round() {
+    for nb_teams in 2..=40 {
+        let seeds: Vec<Qualifier> = (1..=nb_teams).map(|id| qualifier(id, 0)).collect();
+        let games = first_round(&seeds);
+
+        assert_eq!(seeds_from_first_round(&games), (1..=nb_teams).collect::<Vec<i32>>());
+    }
+
+    // teams moved apart by their group give a seeding that keeps the same games
+    let seeds = qualifiers(&groups(3), 2, 0, 0);
+    let games = first_round(&seeds);
+    let read: Vec<Qualifier> = seeds_from_first_round(&games)
+        .into_iter()
+        .map(|id| qualifier(id, 0))
+        .collect();
+
+    assert_eq!(first_round(&read), games);
+}
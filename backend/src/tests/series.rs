@@ -0,0 +1,45 @@
+use crate::series::{check_sets, is_over, series_score, wins_needed, SeriesError, SetScore};
+
+fn set(score1: i32, score2: i32) -> SetScore {
+    SetScore { score1, score2 }
+}
+
+#[test]
+fn wins_needed_for_series_length() {
+    assert_eq!(wins_needed(1), 1);
+    assert_eq!(wins_needed(3), 2);
+    assert_eq!(wins_needed(5), 3);
+    // an even series can end on a draw
+    assert_eq!(wins_needed(2), 2);
+}
+
+#[test]
+fn series_score_counts_sets_won() {
+    let sets = [set(16, 10), set(8, 16), set(2, 2), set(16, 14)];
+
+    assert_eq!(series_score(&sets), (2, 1));
+    assert_eq!(series_score(&[]), (0, 0));
+}
+
+#[test]
+fn series_is_over_once_a_team_won_enough_sets() {
+    assert!(!is_over(3, &[set(1, 0)]));
+    assert!(is_over(3, &[set(1, 0), set(2, 0)]));
+    assert!(!is_over(5, &[set(1, 0), set(0, 1), set(1, 0)]));
+    assert!(is_over(5, &[set(1, 0), set(0, 1), set(1, 0), set(0, 1), set(0, 1)]));
+    // every set played, the draw is decided by a tiebreak
+    assert!(is_over(2, &[set(1, 0), set(0, 1)]));
+}
+
+#[test]
+fn sets_after_the_series_is_decided_are_refused() {
+    assert_eq!(check_sets(3, &[set(1, 0), set(0, 1), set(1, 0)]), Ok(()));
+    assert_eq!(
+        check_sets(3, &[set(1, 0), set(1, 0), set(0, 1)]),
+        Err(SeriesError::AlreadyDecided)
+    );
+    assert_eq!(
+        check_sets(1, &[set(1, 1), set(1, 0)]),
+        Err(SeriesError::TooManySets)
+    );
+}
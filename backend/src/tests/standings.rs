@@ -0,0 +1,117 @@
+use crate::standings::{standings, GameResult, Points};
+
+fn result(team1: i32, team2: i32, score1: i32, score2: i32) -> GameResult {
+    GameResult {
+        team1,
+        team2,
+        score1,
+        score2,
+    }
+}
+
+fn order(teams: &[i32], results: &[GameResult], points: &Points, seed: u64) -> Vec<i32> {
+    standings(teams, results, points, seed)
+        .iter()
+        .map(|s| s.team_id)
+        .collect()
+}
+
+#[test]
+fn draws_give_points() {
+    let table = standings(
+        &[1, 2, 3],
+        &[result(1, 2, 1, 1), result(1, 3, 2, 0), result(2, 3, 0, 0)],
+        &Points::default(),
+        0,
+    );
+
+    assert_eq!(table[0].team_id, 1);
+    assert_eq!((table[0].won, table[0].drawn, table[0].lost), (1, 1, 0));
+    assert_eq!(table[0].points, 4);
+    assert_eq!(table[1].team_id, 2);
+    assert_eq!(table[1].points, 2);
+    assert_eq!(table[2].points, 1);
+}
+
+#[test]
+fn points_are_configurable() {
+    let results = [result(1, 2, 1, 0), result(2, 3, 1, 1), result(3, 1, 1, 1)];
+
+    let table = standings(&[1, 2, 3], &results, &Points::default(), 0);
+    let points: Vec<i32> = table.iter().map(|s| s.points).collect();
+    assert_eq!(points, vec![4, 2, 1]);
+
+    // one point for showing up
+    let points = Points {
+        win: 2,
+        draw: 1,
+        loss: 1,
+    };
+    let table = standings(&[1, 2, 3], &results, &points, 0);
+    let points: Vec<i32> = table.iter().map(|s| s.points).collect();
+    assert_eq!(table[0].team_id, 1);
+    assert_eq!(points, vec![3, 2, 2]);
+}
+
+#[test]
+fn head_to_head_comes_before_score_difference() {
+    // 1 and 2 have 6 points, 2 has the best difference but lost against 1
+    // 3 and 4 have 3 points and the same difference, 4 won their game
+    let results = [
+        result(1, 2, 1, 0),
+        result(2, 3, 5, 0),
+        result(2, 4, 5, 0),
+        result(1, 3, 0, 1),
+        result(1, 4, 1, 0),
+        result(3, 4, 0, 1),
+    ];
+
+    assert_eq!(order(&[1, 2, 3, 4], &results, &Points::default(), 0), vec![1, 2, 4, 3]);
+}
+
+#[test]
+fn score_difference_then_scored() {
+    // everybody won one game, head to head can't split them
+    let results = [
+        result(1, 2, 3, 0),
+        result(2, 3, 4, 2),
+        result(3, 1, 1, 0),
+    ];
+
+    let table = standings(&[1, 2, 3], &results, &Points::default(), 0);
+    let diffs: Vec<i32> = table.iter().map(|s| s.score_diff).collect();
+
+    assert_eq!(table[0].team_id, 1);
+    assert_eq!(diffs, vec![2, -1, -1]);
+    // 2 and 3 have the same difference, 2 scored more
+    assert_eq!(table[1].team_id, 2);
+    assert_eq!(table[1].scored, 4);
+}
+
+#[test]
+fn random_draw_is_stable_for_a_seed() {
+    let teams = [1, 2, 3, 4, 5, 6];
+
+    for seed in 0..20 {
+        let first = order(&teams, &[], &Points::default(), seed);
+        assert_eq!(first, order(&teams, &[], &Points::default(), seed));
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, teams.to_vec());
+    }
+
+    let draws: Vec<Vec<i32>> = (0..20)
+        .map(|seed| order(&teams, &[], &Points::default(), seed))
+        .collect();
+    assert!(draws.iter().any(|draw| *draw != draws[0]));
+}
+
+#[test]
+fn games_against_other_groups_are_ignored() {
+    let table = standings(&[1, 2], &[result(1, 2, 0, 1), result(1, 9, 5, 0)], &Points::default(), 0);
+
+    assert_eq!(table[0].team_id, 2);
+    assert_eq!(table[1].played, 1);
+    assert_eq!(table[1].scored, 0);
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::standings::Points;
+use crate::swiss::{pair_round, standings, SwissResult, SwissStanding};
+
+fn result(team1: i32, team2: i32, score1: i32, score2: i32) -> SwissResult {
+    SwissResult {
+        team1,
+        team2,
+        score1,
+        score2,
+    }
+}
+
+// play every game of the pairing, the lowest team id always wins
+fn play_round(teams: &[i32], results: &mut Vec<SwissResult>) -> bool {
+    let pairing = match pair_round(&standings(teams, results, &Points::default())) {
+        Some(pairing) => pairing,
+        None => return false,
+    };
+
+    for (team1, team2) in pairing.games {
+        if team1 < team2 {
+            results.push(result(team1, team2, 2, 0));
+        } else {
+            results.push(result(team1, team2, 0, 2));
+        }
+    }
+    if let Some(team) = pairing.bye {
+        results.push(result(team, team, 0, 0));
+    }
+
+    true
+}
+
+#[test]
+fn standings_use_points_then_buchholz() {
+    let teams = [1, 2, 3, 4];
+    let results = [
+        result(1, 2, 3, 0),
+        result(3, 4, 1, 1),
+        result(1, 3, 2, 1),
+        result(2, 4, 0, 2),
+    ];
+
+    let table = standings(&teams, &results, &Points::default());
+    let order: Vec<i32> = table.iter().map(|s| s.team_id).collect();
+
+    assert_eq!(order, vec![1, 4, 3, 2]);
+    assert_eq!(table[0].points, 6);
+    assert_eq!(table[0].buchholz, 1);
+    assert_eq!(table[1].points, 4);
+    assert_eq!(table[1].buchholz, 1);
+    assert_eq!(table[2].points, 1);
+    assert_eq!(table[2].buchholz, 10);
+    assert_eq!(table[2].buchholz_cut, 6);
+}
+
+#[test]
+fn first_round_pairs_the_table_in_order() {
+    let pairing = pair_round(&standings(&[1, 2, 3, 4], &[], &Points::default())).unwrap();
+
+    assert_eq!(pairing.games, vec![(1, 2), (3, 4)]);
+    assert_eq!(pairing.bye, None);
+}
+
+#[test]
+fn odd_teams_give_the_bye_to_the_last_team_without_one() {
+    let teams = [1, 2, 3, 4, 5];
+    let results = [result(1, 2, 1, 0), result(3, 4, 1, 0), result(5, 5, 0, 0)];
+
+    let table = standings(&teams, &results, &Points::default());
+    assert!(table.iter().find(|s| s.team_id == 5).unwrap().had_bye);
+
+    let pairing = pair_round(&table).unwrap();
+    assert_ne!(pairing.bye, Some(5));
+    assert_eq!(pairing.bye, Some(4));
+}
+
+#[test]
+fn second_bye_goes_to_the_last_team_once_all_had_one() {
+    let teams = [1, 2, 3];
+    let results = [result(1, 1, 0, 0), result(2, 2, 0, 0), result(3, 3, 0, 0)];
+
+    let pairing = pair_round(&standings(&teams, &results, &Points::default())).unwrap();
+    assert_eq!(pairing.games, vec![(1, 2)]);
+    assert_eq!(pairing.bye, Some(3));
+}
+
+#[test]
+fn pairing_never_repeats_a_game() {
+    let teams: Vec<i32> = (1..=8).collect();
+    let mut results = vec![];
+
+    // with 8 teams every team can meet all the others once
+    for _ in 0..7 {
+        assert!(play_round(&teams, &mut results));
+    }
+
+    let mut played = HashSet::new();
+    for r in results.iter() {
+        assert!(played.insert((r.team1.min(r.team2), r.team1.max(r.team2))));
+    }
+    assert_eq!(played.len(), 28);
+
+    assert!(pair_round(&standings(&teams, &results, &Points::default())).is_none());
+}
+
+#[test]
+fn every_team_gets_at_most_one_bye() {
+    let teams: Vec<i32> = (1..=41).collect();
+    let mut results = vec![];
+
+    for _ in 0..6 {
+        assert!(play_round(&teams, &mut results));
+    }
+
+    let byes: Vec<i32> = results
+        .iter()
+        .filter(|r| r.is_bye())
+        .map(|r| r.team1)
+        .collect();
+    let distinct: HashSet<i32> = byes.iter().cloned().collect();
+
+    assert_eq!(byes.len(), 6);
+    assert_eq!(distinct.len(), 6);
+}
+
+// late round standings of 40 teams, the last one has played every team but the given ones
+fn late_round(allowed: &[i32]) -> Vec<SwissStanding> {
+    (0..40)
+        .map(|team_id| {
+            let opponents = if team_id == 39 {
+                (0..39).filter(|team| !allowed.contains(team)).collect()
+            } else if allowed.contains(&team_id) {
+                vec![]
+            } else {
+                vec![39]
+            };
+
+            SwissStanding {
+                team_id,
+                played: opponents.len() as i32,
+                won: 0,
+                drawn: 0,
+                lost: 0,
+                points: 0,
+                buchholz: 0,
+                buchholz_cut: 0,
+                score_diff: 0,
+                had_bye: false,
+                opponents,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn nearly_infeasible_round_is_paired_in_bounded_time() {
+    let started = Instant::now();
+    let pairing = pair_round(&late_round(&[0])).unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(pairing.bye, None);
+    assert_eq!(pairing.games.len(), 20);
+    assert!(pairing.games.contains(&(0, 39)));
+
+    let teams: HashSet<i32> = pairing.games.iter().flat_map(|(a, b)| [*a, *b]).collect();
+    assert_eq!(teams.len(), 40);
+}
+
+#[test]
+fn infeasible_round_gives_up_in_bounded_time() {
+    let started = Instant::now();
+
+    assert_eq!(pair_round(&late_round(&[])), None);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
@@ -1,3 +1,4 @@
+use dotenv_codegen::dotenv;
 use reqwest::{header::HeaderMap, Method};
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +8,8 @@ use super::{api_call, ErrorResponse};
 pub struct TokenResponse {
     pub token: String,
     pub expiration_date: chrono::NaiveDateTime,
+    pub refresh_token: String,
+    pub refresh_expiration_date: chrono::NaiveDateTime,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -27,6 +30,30 @@ pub async fn login(login_request: LoginRequest) -> Result<TokenResponse, ErrorRe
     .await
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// Get a new session token with the refresh token
+/// The refresh token is replaced too
+///
+/// **Note**: Doesn't go through api_call, which calls it when the session token is expired
+pub async fn refresh(refresh_token: String) -> Result<TokenResponse, ()> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/auth/refresh", dotenv!("API_ENDPOINT")))
+        .header("Accept", "application/json")
+        .body(serde_json::to_string(&RefreshRequest { refresh_token }).unwrap())
+        .send()
+        .await
+        .map_err(|_e| ())?;
+
+    match response.error_for_status() {
+        Ok(r) => r.json::<TokenResponse>().await.map_err(|_e| ()),
+        Err(_e) => Err(()),
+    }
+}
+
 /// Logout to the API
 /// Will revoke the session token
 pub async fn logout() -> bool {
@@ -61,3 +88,64 @@ pub async fn register(
     )
     .await
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TokenRequest {
+    pub token: String,
+}
+
+/// Confirm the email of the user with the token received by mail
+pub async fn verify_email(token: String) -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::POST,
+        "auth/verify_email",
+        HeaderMap::new(),
+        serde_json::to_string(&TokenRequest { token }).unwrap(),
+    )
+    .await
+}
+
+/// Send a new confirmation mail to the user
+pub async fn resend_verification_email() -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::POST,
+        "auth/verification_email",
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+/// Ask for a mail with a link to choose a new password
+pub async fn forgot_password(email: String) -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::POST,
+        "auth/forgot_password",
+        HeaderMap::new(),
+        serde_json::to_string(&ForgotPasswordRequest { email }).unwrap(),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub password: String,
+}
+
+/// Choose a new password with the token received by mail
+/// The sessions of the user are revoked
+pub async fn reset_password(reset_request: ResetPasswordRequest) -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::POST,
+        "auth/reset_password",
+        HeaderMap::new(),
+        serde_json::to_string(&reset_request).unwrap(),
+    )
+    .await
+}
@@ -1,7 +1,7 @@
 use reqwest::{header::HeaderMap, Method};
 use serde::{Deserialize, Serialize};
 
-use super::{api_call, models::{GameWithTeams, Bet, Nut}, ErrorResponse};
+use super::{api_call, models::{GameWithTeams, Bet, Nut, GameOdds, MarketBet, MarketBets}, ErrorResponse};
 
 pub async fn get(game_id: i32) -> Result<GameWithTeams, ErrorResponse> {
     api_call::<GameWithTeams>(
@@ -13,6 +13,22 @@ pub async fn get(game_id: i32) -> Result<GameWithTeams, ErrorResponse> {
     .await
 }
 
+// `preview` is the team and the nuts of a bet to compute the payout of
+pub async fn get_odds(game_id: i32, preview: Option<(i32, i32)>) -> Result<GameOdds, ErrorResponse> {
+    let query = match preview {
+        Some((team_id, nut)) => format!("?team={}&nuts={}", team_id, nut),
+        None => String::new(),
+    };
+
+    api_call::<GameOdds>(
+        Method::GET,
+        &format!("game/{}/odds{}", game_id, query),
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BetData {
     pub nut: i32,
@@ -59,3 +75,40 @@ pub async fn get_user_bet_on_match(id_user: i32, id: i32) -> Result<Bet, ErrorRe
     )
     .await
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScoreBetData {
+    pub score1: i32,
+    pub score2: i32,
+    pub nut: i32,
+}
+
+pub async fn get_score_bets(game_id: i32) -> Result<MarketBets, ErrorResponse> {
+    api_call::<MarketBets>(
+        Method::GET,
+        &format!("game/{}/score_bet", game_id),
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
+
+pub async fn score_bet(game_id: i32, bet_request: ScoreBetData) -> Result<MarketBet, ErrorResponse> {
+    api_call::<MarketBet>(
+        Method::POST,
+        &format!("game/{}/score_bet", game_id),
+        HeaderMap::new(),
+        serde_json::to_string(&bet_request).unwrap(),
+    )
+    .await
+}
+
+pub async fn delete_score_bet(game_id: i32) -> Result<MarketBet, ErrorResponse> {
+    api_call::<MarketBet>(
+        Method::DELETE,
+        &format!("game/{}/score_bet", game_id),
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
@@ -1,6 +1,6 @@
 use reqwest::{Method, header::HeaderMap};
 
-use super::{models::{Game, GameUpdate}, api_call, ErrorResponse, EmptyResponse};
+use super::{models::{Game, GameSet, GameUpdate, SetScore}, api_call, ErrorResponse, EmptyResponse};
 
 pub async fn update(
     game_id: i32,
@@ -25,4 +25,17 @@ pub async fn close(
         String::new(),
     )
     .await
+}
+
+pub async fn update_sets(
+    game_id: i32,
+    sets: Vec<SetScore>,
+) -> Result<Vec<GameSet>, ErrorResponse> {
+    api_call::<Vec<GameSet>>(
+        Method::PUT,
+        &format!("games/{}/sets", game_id),
+        HeaderMap::new(),
+        serde_json::to_string(&sets).unwrap(),
+    )
+    .await
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use reqwest::{header::HeaderMap, Method};
+
+use super::{
+    api_call,
+    models::{Leaderboard, Season},
+    ErrorResponse,
+};
+
+pub async fn get_seasons() -> Result<Vec<Season>, ErrorResponse> {
+    api_call::<Vec<Season>>(Method::GET, "seasons", HeaderMap::new(), String::new()).await
+}
+
+// all tournaments without a season
+pub async fn get_leaderboard(season: Option<i32>) -> Result<Leaderboard, ErrorResponse> {
+    let query = match season {
+        Some(season) => format!("?season={}", season),
+        None => String::new(),
+    };
+
+    api_call::<Leaderboard>(
+        Method::GET,
+        &format!("leaderboard{}", query),
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
@@ -1,7 +1,7 @@
 use dotenv_codegen::dotenv;
 use reqwest::{
     header::{HeaderMap, HeaderValue},
-    Method,
+    Method, StatusCode,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
@@ -14,8 +14,10 @@ pub mod games;
 pub mod models;
 pub mod teams;
 pub mod tournoix;
+pub mod users;
 pub mod game;
 pub mod bets;
+pub mod leaderboard;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ErrorBody {
@@ -45,18 +47,19 @@ pub async fn api_call<T: DeserializeOwned>(
     headers: HeaderMap<HeaderValue>,
     body: String,
 ) -> Result<T, ErrorResponse> {
-    let client = reqwest::Client::new();
+    let mut response = send(method.clone(), route, headers.clone(), body.clone()).await;
 
-    let mut request = client
-        .request(method, format!("{}/{}", dotenv!("API_ENDPOINT"), route))
-        .header("Accept", "application/json");
-
-    // Add token to request if exists
-    if let Some(token) = UserInfo::get_token() {
-        request = request.header("Authorization", format!("bearer {}", token));
+    // An expired session token is replaced once with the refresh token
+    if let Ok(r) = &response {
+        if r.status() == StatusCode::UNAUTHORIZED
+            && !route.starts_with("auth/")
+            && UserInfo::refresh().await
+        {
+            response = send(method, route, headers, body).await;
+        }
     }
 
-    match request.headers(headers).body(body).send().await {
+    match response {
         Ok(r) => match r.error_for_status_ref() {
             Ok(_r) => Ok(r.json::<T>().await.unwrap()),
             Err(_e) => Err(r.json::<ErrorResponse>().await.unwrap()),
@@ -72,6 +75,26 @@ pub async fn api_call<T: DeserializeOwned>(
     }
 }
 
+async fn send(
+    method: Method,
+    route: &str,
+    headers: HeaderMap<HeaderValue>,
+    body: String,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let client = reqwest::Client::new();
+
+    let mut request = client
+        .request(method, format!("{}/{}", dotenv!("API_ENDPOINT"), route))
+        .header("Accept", "application/json");
+
+    // Add token to request if exists
+    if let Some(token) = UserInfo::get_token() {
+        request = request.header("Authorization", format!("bearer {}", token));
+    }
+
+    request.headers(headers).body(body).send().await
+}
+
 /// Get current logged User Info
 pub async fn me() -> Result<User, ErrorResponse> {
     api_call::<User>(Method::GET, "users/@me", HeaderMap::new(), "".to_string()).await
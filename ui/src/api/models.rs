@@ -1,4 +1,5 @@
 use chrono::TimeZone;
+use dotenv_codegen::dotenv;
 use reqwest::{header::HeaderMap, Method};
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,7 @@ pub struct User {
     pub id: usize,
     pub name: String,
     pub email: String,
+    pub email_verified: bool,
 }
 
 impl User {
@@ -57,6 +59,17 @@ pub struct Tournament {
     pub is_qualif: bool,
     pub is_elim: bool,
     pub is_closed: bool,
+    pub is_double_elim: bool,
+    pub has_bracket_reset: bool,
+    pub is_swiss: bool,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: i32,
+    pub points_draw: i32,
+    pub points_loss: i32,
+    pub qualified_per_group: i32,
+    pub wildcards: i32,
+    pub has_third_place: bool,
+    pub betting_lead_time: i32,
 }
 
 impl Tournament {
@@ -73,6 +86,17 @@ impl Tournament {
             is_qualif: None,
             is_elim: None,
             is_closed: Some(true),
+            is_double_elim: None,
+            has_bracket_reset: None,
+            is_swiss: None,
+            swiss_rounds: None,
+            points_win: None,
+            points_draw: None,
+            points_loss: None,
+            qualified_per_group: None,
+            wildcards: None,
+            has_third_place: None,
+            betting_lead_time: None,
         };
         super::tournoix::update(self.id, update_request).await
     }
@@ -104,6 +128,35 @@ impl Tournament {
         .await
     }
 
+    // calendar feed of the games, the join code replaces the login token
+    pub fn calendar_url(&self) -> String {
+        format!(
+            "{}/tournoix/{}/calendar.ics?code={}",
+            dotenv!("API_ENDPOINT"),
+            self.id,
+            self.code
+        )
+    }
+
+    // live changes of the tournament, read with an EventSource
+    pub fn events_url(&self) -> String {
+        format!(
+            "{}/tournoix/{}/events?code={}",
+            dotenv!("API_ENDPOINT"),
+            self.id,
+            self.code
+        )
+    }
+
+    pub fn team_calendar_url(&self, team_id: i32) -> String {
+        format!(
+            "{}/teams/{}/calendar.ics?code={}",
+            dotenv!("API_ENDPOINT"),
+            team_id,
+            self.code
+        )
+    }
+
     pub async fn get_teams(&self) -> Result<Vec<Team>, ErrorResponse> {
         api_call::<Vec<Team>>(
             Method::GET,
@@ -173,6 +226,186 @@ impl Tournament {
         )
         .await
     }
+
+    pub async fn generate_swiss_round(&self) -> Result<Vec<Game>, ErrorResponse> {
+        api_call::<Vec<Game>>(
+            Method::POST,
+            &format!("tournoix/{}/swiss/next", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn get_group_standings(&self, group: i32) -> Result<Vec<Standing>, ErrorResponse> {
+        api_call::<Vec<Standing>>(
+            Method::GET,
+            &format!("tournoix/{}/groups/{}/standings", self.id, group),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn get_swiss_standings(&self) -> Result<Vec<SwissStanding>, ErrorResponse> {
+        api_call::<Vec<SwissStanding>>(
+            Method::GET,
+            &format!("tournoix/{}/swiss/standings", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn get_elim_seeding(&self) -> Result<EliminationSeeding, ErrorResponse> {
+        api_call::<EliminationSeeding>(
+            Method::GET,
+            &format!("tournoix/{}/elim/seeding", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn update_elim_seeding(&self, seeds: Vec<i32>) -> Result<EmptyResponse, ErrorResponse> {
+        api_call::<EmptyResponse>(
+            Method::PUT,
+            &format!("tournoix/{}/elim/seeding", self.id),
+            HeaderMap::new(),
+            serde_json::to_string(&EliminationSeeding { seeds }).unwrap(),
+        )
+        .await
+    }
+
+    pub async fn get_series_formats(&self) -> Result<Vec<SeriesFormat>, ErrorResponse> {
+        api_call::<Vec<SeriesFormat>>(
+            Method::GET,
+            &format!("tournoix/{}/series", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn update_series_formats(
+        &self,
+        formats: Vec<AddSeriesFormat>,
+    ) -> Result<Vec<SeriesFormat>, ErrorResponse> {
+        api_call::<Vec<SeriesFormat>>(
+            Method::PUT,
+            &format!("tournoix/{}/series", self.id),
+            HeaderMap::new(),
+            serde_json::to_string(&formats).unwrap(),
+        )
+        .await
+    }
+
+    pub async fn create_schedule(
+        &self,
+        schedule_request: ScheduleRequest,
+    ) -> Result<Vec<Game>, ErrorResponse> {
+        api_call::<Vec<Game>>(
+            Method::POST,
+            &format!("tournoix/{}/schedule", self.id),
+            HeaderMap::new(),
+            serde_json::to_string(&schedule_request).unwrap(),
+        )
+        .await
+    }
+
+    pub async fn get_sets(&self) -> Result<Vec<GameSet>, ErrorResponse> {
+        api_call::<Vec<GameSet>>(
+            Method::GET,
+            &format!("tournoix/{}/sets", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn get_champion_bets(&self) -> Result<MarketBets, ErrorResponse> {
+        api_call::<MarketBets>(
+            Method::GET,
+            &format!("tournoix/{}/champion_bet", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn champion_bet(&self, bet: ChampionBetRequest) -> Result<MarketBet, ErrorResponse> {
+        api_call::<MarketBet>(
+            Method::POST,
+            &format!("tournoix/{}/champion_bet", self.id),
+            HeaderMap::new(),
+            serde_json::to_string(&bet).unwrap(),
+        )
+        .await
+    }
+
+    pub async fn delete_champion_bet(&self) -> Result<MarketBet, ErrorResponse> {
+        api_call::<MarketBet>(
+            Method::DELETE,
+            &format!("tournoix/{}/champion_bet", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn get_nut_history(&self) -> Result<Vec<NutTransaction>, ErrorResponse> {
+        api_call::<Vec<NutTransaction>>(
+            Method::GET,
+            &format!("tournoix/{}/nut/history", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+
+    pub async fn get_nut_audit(&self) -> Result<Vec<NutAudit>, ErrorResponse> {
+        api_call::<Vec<NutAudit>>(
+            Method::GET,
+            &format!("tournoix/{}/nut/audit", self.id),
+            HeaderMap::new(),
+            String::new(),
+        )
+        .await
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Standing {
+    pub team_id: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub scored: i32,
+    pub conceded: i32,
+    pub score_diff: i32,
+    pub points: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SwissStanding {
+    pub team_id: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub points: i32,
+    pub buchholz: i32,
+    pub buchholz_cut: i32,
+    pub score_diff: i32,
+    pub had_bye: bool,
+    pub opponents: Vec<i32>,
+}
+
+// Teams of the elimination phase, best seed first
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct EliminationSeeding {
+    pub seeds: Vec<i32>,
 }
 
 // ---- Team ----
@@ -226,6 +459,8 @@ pub struct Game {
     pub place: i32,
     pub status: i32,
     pub has_gained_nut: bool,
+    pub scheduled_at: Option<chrono::NaiveDateTime>,
+    pub court: Option<i32>,
     pub group: Option<i32>
 }
 
@@ -235,6 +470,8 @@ pub struct GameUpdate {
     pub score2: Option<i32>,
     pub phase: Option<i32>,
     pub status: Option<i32>,
+    pub tiebreak: Option<i32>,
+    pub tiebreak_winner: Option<i32>,
 }
 
 impl Game {
@@ -243,6 +480,18 @@ impl Game {
     }
 }
 
+// Side of the elimination tree a game belongs to
+pub const BRACKET_UPPER: i32 = 0;
+pub const BRACKET_LOWER: i32 = 1;
+pub const BRACKET_GRAND_FINAL: i32 = 2;
+pub const BRACKET_THIRD_PLACE: i32 = 3;
+
+// How a draw was decided
+pub const TIEBREAK_NONE: i32 = 0;
+pub const TIEBREAK_PENALTIES: i32 = 1;
+pub const TIEBREAK_OVERTIME: i32 = 2;
+pub const TIEBREAK_COIN_TOSS: i32 = 3;
+
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct GameWithTeams {
     pub id: i32,
@@ -254,9 +503,81 @@ pub struct GameWithTeams {
     pub place: i32,
     pub status: i32,
     pub has_gained_nut: bool,
+    pub bracket: i32,
+    pub round: i32,
+    pub tiebreak: i32,
+    pub tiebreak_winner: i32,
+    pub scheduled_at: Option<chrono::NaiveDateTime>,
+    pub court: Option<i32>,
     pub group: Option<i32>
 }
 
+// ---- Schedule ----
+
+// durations are in minutes, the timetable starts at the date of the tournament by default
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ScheduleRequest {
+    pub start: Option<chrono::NaiveDateTime>,
+    pub match_duration: i32,
+    pub break_duration: i32,
+    pub courts: i32,
+    pub min_rest: i32,
+}
+
+// ---- Series ----
+
+// Number of sets played in the games of an elimination round
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SeriesFormat {
+    pub id: i32,
+    pub fk_tournaments: i32,
+    pub bracket: i32,
+    pub phase: i32,
+    pub best_of: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct AddSeriesFormat {
+    pub bracket: i32,
+    pub phase: i32,
+    pub best_of: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct GameSet {
+    pub id: i32,
+    pub fk_games: i32,
+    pub number: i32,
+    pub score1: i32,
+    pub score2: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SetScore {
+    pub score1: i32,
+    pub score2: i32,
+}
+
+impl GameWithTeams {
+    // a team without opponent for the round
+    pub fn is_bye(&self) -> bool {
+        self.team1.id == self.team2.id
+    }
+
+    // none for a draw without tiebreak result
+    pub fn winner(&self) -> Option<i32> {
+        if self.score1 > self.score2 {
+            Some(self.team1.id)
+        } else if self.score2 > self.score1 {
+            Some(self.team2.id)
+        } else if self.tiebreak_winner == self.team1.id || self.tiebreak_winner == self.team2.id {
+            Some(self.tiebreak_winner)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Nut {
     pub id: i32,
@@ -265,6 +586,70 @@ pub struct Nut {
     pub stock: i32,
 }
 
+// Change of the stock of a player by the owner of the tournament
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PatchNut {
+    pub stock: i32,
+    pub reason: String,
+}
+
+// Why the stock of a nut changed
+pub const TRANSACTION_GRANT: i32 = 0;
+pub const TRANSACTION_STAKE: i32 = 1;
+pub const TRANSACTION_REFUND: i32 = 2;
+pub const TRANSACTION_PAYOUT: i32 = 3;
+pub const TRANSACTION_ADJUSTMENT: i32 = 4;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NutTransaction {
+    pub id: i32,
+    pub fk_nuts: i32,
+    pub kind: i32,
+    pub amount: i32,
+    pub balance: i32,
+    pub fk_games: Option<i32>,
+    pub reason: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NutTransaction {
+    pub fn label(&self) -> String {
+        match self.kind {
+            TRANSACTION_GRANT => "Noix de départ".to_string(),
+            // the bets on the champion are not on a game
+            TRANSACTION_STAKE if self.fk_games.is_none() => "Mise sur le vainqueur".to_string(),
+            TRANSACTION_PAYOUT if self.fk_games.is_none() => "Gain du pari sur le vainqueur".to_string(),
+            TRANSACTION_STAKE => "Mise".to_string(),
+            TRANSACTION_REFUND => "Remboursement".to_string(),
+            TRANSACTION_PAYOUT => "Gain d'un pari".to_string(),
+            _ => format!("Ajustement : {}", self.reason.clone().unwrap_or_default()),
+        }
+    }
+}
+
+// Ledger of a player, for the owner of the tournament
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NutAudit {
+    pub fk_nuts: i32,
+    pub fk_users: i32,
+    pub username: String,
+    pub stock: i32,
+    pub ledger_stock: i32,
+    pub transactions: Vec<NutTransaction>,
+}
+
+impl NutAudit {
+    pub async fn adjust(&self, patch: PatchNut) -> Result<Nut, ErrorResponse> {
+        api_call::<Nut>(
+            Method::PATCH,
+            &format!("nut/{}", self.fk_nuts),
+            HeaderMap::new(),
+            serde_json::to_string(&patch).unwrap(),
+        )
+        .await
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Bet {
     pub id: i32,
@@ -284,9 +669,120 @@ pub struct BetWithUser {
     pub username: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TeamOdds {
+    pub team_id: i32,
+    pub staked: i32,
+    pub bettors: i32,
+    pub odds: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BetPreview {
+    pub fk_teams: i32,
+    pub nb_nut: i32,
+    pub payout: i32,
+    pub gain: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameOdds {
+    pub fk_games: i32,
+    pub pot: i32,
+    pub teams: Vec<TeamOdds>,
+    pub preview: Option<BetPreview>,
+}
+
+impl GameOdds {
+    pub fn team(&self, team_id: i32) -> Option<&TeamOdds> {
+        self.teams.iter().find(|t| t.team_id == team_id)
+    }
+}
+
+pub const MARKET_EXACT_SCORE: i32 = 0;
+pub const MARKET_CHAMPION: i32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MarketBet {
+    pub id: i32,
+    pub market: i32,
+    pub fk_tournaments: i32,
+    pub fk_users: i32,
+    pub fk_games: Option<i32>,
+    pub fk_teams: Option<i32>,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub nb_nut: i32,
+    pub payout: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MarketBetWithUser {
+    pub id: i32,
+    pub market: i32,
+    pub fk_tournaments: i32,
+    pub fk_users: i32,
+    pub fk_games: Option<i32>,
+    pub fk_teams: Option<i32>,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub nb_nut: i32,
+    pub payout: Option<i32>,
+    pub username: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MarketBets {
+    pub is_open: bool,
+    pub pot: i32,
+    pub bets: Vec<MarketBetWithUser>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChampionBetRequest {
+    pub team_id: i32,
+    pub nut: i32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Subscription {
     pub id: i32,
     pub fk_users: i32,
     pub fk_tournaments: i32,
-}
\ No newline at end of file
+}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Season {
+    pub id: i32,
+    pub name: String,
+    pub start_date: chrono::NaiveDateTime,
+    pub end_date: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    pub username: String,
+    pub user_id: i32,
+    pub rank: i32,
+    pub bets: i32,
+    pub won: i32,
+    pub lost: i32,
+    pub staked: i32,
+    pub net: i32,
+    pub accuracy: Option<f64>,
+    pub roi: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Leaderboard {
+    pub season: Option<Season>,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub id: i32,
+    pub user_agent: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: chrono::NaiveDateTime,
+    pub is_current: bool,
+}
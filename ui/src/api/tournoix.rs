@@ -12,6 +12,16 @@ pub struct CreateTournoixRequest {
     pub is_qualif: bool,
     pub is_elim: bool,
     pub is_closed: bool,
+    pub is_double_elim: bool,
+    pub has_bracket_reset: bool,
+    pub is_swiss: bool,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: i32,
+    pub points_draw: i32,
+    pub points_loss: i32,
+    pub qualified_per_group: i32,
+    pub wildcards: i32,
+    pub has_third_place: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,10 +30,24 @@ pub struct Score {
     pub val: i32,
 }
 
+// Final place of a team with its record over the whole tournament
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TeamResult {
+    pub team_id: i32,
+    pub name: String,
+    pub place: i32,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub scored: i32,
+    pub conceded: i32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Results {
     pub subscribers: Vec<Score>,
-    pub teams: Vec<Score>,
+    pub teams: Vec<TeamResult>,
 }
 
 pub async fn is_tournoix_owner(tournoix_id: i32) -> Result<bool, ErrorResponse> {
@@ -114,6 +138,17 @@ pub struct UpdateTournoixRequest {
     pub is_qualif: Option<bool>,
     pub is_elim: Option<bool>,
     pub is_closed: Option<bool>,
+    pub is_double_elim: Option<bool>,
+    pub has_bracket_reset: Option<bool>,
+    pub is_swiss: Option<bool>,
+    pub swiss_rounds: Option<i32>,
+    pub points_win: Option<i32>,
+    pub points_draw: Option<i32>,
+    pub points_loss: Option<i32>,
+    pub qualified_per_group: Option<i32>,
+    pub wildcards: Option<i32>,
+    pub has_third_place: Option<bool>,
+    pub betting_lead_time: Option<i32>,
 }
 
 pub async fn update(
@@ -0,0 +1,93 @@
+use reqwest::{header::HeaderMap, Method};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    api_call,
+    models::{SessionInfo, User},
+    ErrorResponse,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateUserRequest {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Change the name or the email of the logged User
+/// A new email has to be confirmed again
+pub async fn update_me(update_request: UpdateUserRequest) -> Result<User, ErrorResponse> {
+    api_call::<User>(
+        Method::PATCH,
+        "users/@me",
+        HeaderMap::new(),
+        serde_json::to_string(&update_request).unwrap(),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Change the password of the logged User
+/// The other sessions are revoked
+pub async fn change_password(password_request: ChangePasswordRequest) -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::POST,
+        "users/@me/password",
+        HeaderMap::new(),
+        serde_json::to_string(&password_request).unwrap(),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeleteUserRequest {
+    pub password: String,
+}
+
+/// Delete the account of the logged User
+pub async fn delete_me(password: String) -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::DELETE,
+        "users/@me",
+        HeaderMap::new(),
+        serde_json::to_string(&DeleteUserRequest { password }).unwrap(),
+    )
+    .await
+}
+
+/// List the sessions of the logged User
+pub async fn get_sessions() -> Result<Vec<SessionInfo>, ErrorResponse> {
+    api_call::<Vec<SessionInfo>>(
+        Method::GET,
+        "users/@me/sessions",
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
+
+/// Log out one of the sessions of the logged User
+pub async fn delete_session(id: i32) -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::DELETE,
+        &format!("users/@me/sessions/{}", id),
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
+
+/// Log out every session of the logged User, the current one included
+pub async fn delete_all_sessions() -> Result<String, ErrorResponse> {
+    api_call::<String>(
+        Method::DELETE,
+        "users/@me/sessions",
+        HeaderMap::new(),
+        String::new(),
+    )
+    .await
+}
@@ -2,7 +2,7 @@ use yew::prelude::*;
 use yew_router::prelude::use_navigator;
 
 use crate::{
-    api::models::GameWithTeams, components::button::Button, routers::Route,
+    api::models::{GameWithTeams, BRACKET_THIRD_PLACE}, components::button::Button, routers::Route,
     utils::utils::team_color_wrapper,
 };
 
@@ -43,6 +43,9 @@ pub fn BetList(props: &BetListProps) -> Html {
                             html!{<div class="font-bebas rounded-t w-full mb-2 px-3 py-1 text-xs text-center text-white bg-orange-600">{"EN ATTENTE"}</div>}
                         }
                     }
+                    if _match.bracket == BRACKET_THIRD_PLACE {
+                        <div class="text-xs font-bold mb-1">{"Petite finale"}</div>
+                    }
                     <div class="gap-2 flex items-center">
                         <span style={team_color_wrapper(_match.team1.name.clone())} class="team-text-color pl-3">{_match.team1.name.clone()}</span>
                         <img class="drop-shadow h-4" src="/img/versus_smol.png"/>
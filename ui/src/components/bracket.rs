@@ -1,10 +1,12 @@
 use std::collections::BTreeMap;
 
 use crate::{
-    api::models::{GameWithTeams, Team, Tournament},
+    api::models::{
+        GameSet, GameWithTeams, Team, Tournament, BRACKET_GRAND_FINAL, BRACKET_LOWER, BRACKET_THIRD_PLACE,
+        BRACKET_UPPER,
+    },
     components::bracket_round::BracketRound,
 };
-use log::info;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
@@ -33,6 +35,52 @@ pub struct BracketProps {
     */
 }
 
+type Rounds = BTreeMap<i32, BTreeMap<i32, GameWithTeams>>;
+// series length by (bracket, phase)
+type Formats = BTreeMap<(i32, i32), i32>;
+type Sets = BTreeMap<i32, Vec<GameSet>>;
+
+// Game not created yet, waiting for the result of previous games
+fn placeholder_game(bracket: i32, phase: i32, place: i32) -> GameWithTeams {
+    GameWithTeams {
+        id: -1,
+        team1: Team {
+            id: -1,
+            name: "TBA".into(),
+            fk_tournaments: -1,
+            group: -1,
+        },
+        team2: Team {
+            id: -1,
+            name: "TBA".into(),
+            fk_tournaments: -1,
+            group: -1,
+        },
+        score1: 0,
+        score2: 0,
+        phase,
+        place,
+        status: 0,
+        has_gained_nut: false,
+        bracket,
+        round: 0,
+        tiebreak: 0,
+        tiebreak_winner: 0,
+        scheduled_at: None,
+        court: None,
+        group: Some(-1),
+    }
+}
+
+fn fill_round(rounds: &mut Rounds, bracket: i32, phase: i32, nb_games: i32) {
+    let round = rounds.entry(phase).or_insert_with(|| BTreeMap::new());
+    for place in 0..nb_games {
+        round
+            .entry(place)
+            .or_insert_with(|| placeholder_game(bracket, phase, place));
+    }
+}
+
 #[function_component]
 pub fn Bracket(props: &BracketProps) -> Html {
     let BracketProps {
@@ -41,93 +89,99 @@ pub fn Bracket(props: &BracketProps) -> Html {
         editable,
     } = props;
 
-    //let is_nb_valid = teams.len() > 0 && (teams[0].len() & (teams[0].len() - 1)) == 0;
-    let teams: UseStateHandle<BTreeMap<i32, BTreeMap<i32, GameWithTeams>>> =
-        use_state(|| BTreeMap::new());
-    let is_nb_valid = true;
+    let teams: UseStateHandle<Rounds> = use_state(|| BTreeMap::new());
+    let lower_teams: UseStateHandle<Rounds> = use_state(|| BTreeMap::new());
+    let finals: UseStateHandle<Rounds> = use_state(|| BTreeMap::new());
+    let third_place: UseStateHandle<Rounds> = use_state(|| BTreeMap::new());
+    let formats: UseStateHandle<Formats> = use_state(|| BTreeMap::new());
+    let sets: UseStateHandle<Sets> = use_state(|| BTreeMap::new());
     let trigger = use_state(|| false);
 
     {
         let tournament = tournament.clone();
         let teams = teams.clone();
+        let lower_teams = lower_teams.clone();
+        let finals = finals.clone();
+        let third_place = third_place.clone();
+        let formats = formats.clone();
+        let sets = sets.clone();
         // let loading = loading.clone();
 
         use_effect_with_deps(
             move |_| {
                 spawn_local(async move {
+                    if let Ok(saved) = tournament.get_series_formats().await {
+                        formats.set(
+                            saved
+                                .into_iter()
+                                .map(|f| ((f.bracket, f.phase), f.best_of))
+                                .collect(),
+                        );
+                    }
+
+                    if let Ok(game_sets) = tournament.get_sets().await {
+                        let mut new_sets: Sets = BTreeMap::new();
+                        for set in game_sets {
+                            new_sets.entry(set.fk_games).or_insert_with(|| vec![]).push(set);
+                        }
+                        sets.set(new_sets);
+                    }
+
                     if let Some(games) = tournament.get_matches().await.ok() {
-                        let mut new_teams: BTreeMap<i32, BTreeMap<i32, GameWithTeams>> =
-                            BTreeMap::new();
-                        // new_groups.insert(0, vec![]);
+                        let mut new_teams: Rounds = BTreeMap::new();
+                        let mut new_lower_teams: Rounds = BTreeMap::new();
+                        let mut new_finals: Rounds = BTreeMap::new();
+                        let mut new_third_place: Rounds = BTreeMap::new();
 
                         for game in games {
                             if game.phase < 1 {
                                 continue;
                             }
 
-                            new_teams
+                            let rounds = match game.bracket {
+                                BRACKET_LOWER => &mut new_lower_teams,
+                                BRACKET_GRAND_FINAL => &mut new_finals,
+                                BRACKET_THIRD_PLACE => &mut new_third_place,
+                                _ => &mut new_teams,
+                            };
+
+                            rounds
                                 .entry(game.phase)
                                 .or_insert_with(|| BTreeMap::new())
                                 .entry(game.place)
                                 .or_insert_with(|| game);
-
-                            /*
-                            if new_teams.contains_key(&game.phase) {
-                                new_teams.get_mut(&game.phase).unwrap().push(game);
-                            } else {
-                                new_teams.insert(game.phase, vec![game]);
-                            }
-                            */
                         }
 
                         if new_teams.len() == 0 {
                             return;
                         }
 
-                        let games_len = new_teams.get(&1).unwrap().len();
+                        let games_len = new_teams.get(&1).unwrap().len() as i32;
 
                         let mut phase_id = 2;
                         for i in (1..=games_len / 2).rev() {
-                            if !new_teams.contains_key(&phase_id) {
-                                new_teams.insert(phase_id, BTreeMap::new());
-                            }
+                            fill_round(&mut new_teams, BRACKET_UPPER, phase_id, i);
+                            phase_id += 1;
+                        }
 
-                            let phase = new_teams.get_mut(&phase_id).unwrap();
-                            for j in 0..i {
-                                if !phase.contains_key(&(j as i32)) {
-                                    info!("insert");
-                                    phase.insert(
-                                        j as i32,
-                                        GameWithTeams {
-                                            id: -1,
-                                            team1: Team {
-                                                id: -1,
-                                                name: "TBA".into(),
-                                                fk_tournaments: -1,
-                                                group: -1,
-                                            },
-                                            team2: Team {
-                                                id: -1,
-                                                name: "TBA".into(),
-                                                fk_tournaments: -1,
-                                                group: -1,
-                                            },
-                                            score1: 0,
-                                            score2: 0,
-                                            phase: phase_id,
-                                            place: j as i32,
-                                            status: 0,
-                                            has_gained_nut: false,
-                                            group: Some(-1),
-                                        },
-                                    );
-                                }
+                        if tournament.is_double_elim {
+                            // The lower bracket plays two rounds for each upper round after the first one
+                            let nb_rounds = phase_id - 1;
+                            for phase in 1..=2 * (nb_rounds - 1) {
+                                let nb_games = 1 << (nb_rounds - 1 - (phase + 1) / 2);
+                                fill_round(&mut new_lower_teams, BRACKET_LOWER, phase, nb_games);
                             }
 
-                            phase_id += 1;
+                            fill_round(&mut new_finals, BRACKET_GRAND_FINAL, 1, 1);
+                        } else if tournament.has_third_place && games_len > 1 {
+                            // The semi-finals losers play for the third place
+                            fill_round(&mut new_third_place, BRACKET_THIRD_PLACE, 1, 1);
                         }
 
                         teams.set(new_teams);
+                        lower_teams.set(new_lower_teams);
+                        finals.set(new_finals);
+                        third_place.set(new_third_place);
                         // loading.set(false);
                     }
                 });
@@ -140,26 +194,60 @@ pub fn Bracket(props: &BracketProps) -> Html {
         trigger.set(!*trigger);
     });
 
-    if !is_nb_valid {
-        html!(
-            <div>{"Number of teams is invalid. Must be a power of 2"}</div>
-        )
-    } else {
-        html! {
+    let best_of = |bracket: i32, phase: i32| *formats.get(&(bracket, phase)).unwrap_or(&1);
+
+    html! {
+        <>
+            if tournament.is_double_elim && teams.len() > 0 {
+                <h3>{"Tableau principal"}</h3>
+            }
             <div class={"bracket"}>
                 if teams.len() > 0 {
                     {teams.iter().map(|(round_id, games)| {
                         html!(
-                            <BracketRound round_id={round_id} games={games.clone()} editable={editable} on_game_update={on_game_update.clone()} />
+                            <BracketRound round_id={round_id} games={games.clone()} editable={editable} on_game_update={on_game_update.clone()} best_of={best_of(BRACKET_UPPER, *round_id)} sets={(*sets).clone()} />
                         )
                     }).collect::<Html>()}
-                    <div class="round">
-                        <div class={"ml-4 p-1 font-bold text-center bg-nutLighter"}>{"Winner"}</div>
-                    </div>
+                    if !tournament.is_double_elim {
+                        <div class="round">
+                            <div class={"ml-4 p-1 font-bold text-center bg-nutLighter"}>{"Winner"}</div>
+                        </div>
+                    }
                 } else {
                     {"Aucun matchs pour le moment"}
                 }
             </div>
-        }
+            if !tournament.is_double_elim && third_place.len() > 0 {
+                <h3>{"Match pour la troisième place"}</h3>
+                <div class={"bracket"}>
+                    {third_place.iter().map(|(round_id, games)| {
+                        html!(
+                            <BracketRound round_id={round_id} title={"Petite finale".to_string()} games={games.clone()} editable={editable} on_game_update={on_game_update.clone()} best_of={best_of(BRACKET_THIRD_PLACE, *round_id)} sets={(*sets).clone()} />
+                        )
+                    }).collect::<Html>()}
+                </div>
+            }
+            if tournament.is_double_elim && teams.len() > 0 {
+                <h3>{"Repêchage"}</h3>
+                <div class={"bracket"}>
+                    {lower_teams.iter().map(|(round_id, games)| {
+                        html!(
+                            <BracketRound round_id={round_id} title={format!("Repêchage {}", round_id)} games={games.clone()} editable={editable} on_game_update={on_game_update.clone()} best_of={best_of(BRACKET_LOWER, *round_id)} sets={(*sets).clone()} />
+                        )
+                    }).collect::<Html>()}
+                </div>
+                <h3>{"Grande finale"}</h3>
+                <div class={"bracket"}>
+                    {finals.iter().map(|(round_id, games)| {
+                        html!(
+                            <BracketRound round_id={round_id} title={if *round_id == 1 {"Grande finale".to_string()} else {"Finale rejouée".to_string()}} games={games.clone()} editable={editable} on_game_update={on_game_update.clone()} best_of={best_of(BRACKET_GRAND_FINAL, *round_id)} sets={(*sets).clone()} />
+                        )
+                    }).collect::<Html>()}
+                    <div class="round">
+                        <div class={"ml-4 p-1 font-bold text-center bg-nutLighter"}>{"Winner"}</div>
+                    </div>
+                </div>
+            }
+        </>
     }
 }
@@ -1,14 +1,17 @@
 use time::Duration;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
-use web_sys::{EventTarget, HtmlInputElement};
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew_notifications::use_notification;
 
 use crate::{
     api::{
         self,
-        models::{GameUpdate, GameWithTeams},
+        models::{
+            GameSet, GameUpdate, GameWithTeams, SetScore, TIEBREAK_COIN_TOSS, TIEBREAK_NONE, TIEBREAK_OVERTIME,
+            TIEBREAK_PENALTIES,
+        },
     },
     notification::{CustomNotification, NotifType},
     utils::utils::team_color_wrapper,
@@ -21,6 +24,19 @@ pub struct BracketMatchProps {
     pub editable: bool,
     #[prop_or_default]
     pub on_game_update: Callback<i32>,
+    #[prop_or(1)]
+    pub best_of: i32,
+    #[prop_or_default]
+    pub sets: Vec<GameSet>,
+}
+
+fn tiebreak_label(tiebreak: i32) -> &'static str {
+    match tiebreak {
+        TIEBREAK_PENALTIES => "Tirs au but",
+        TIEBREAK_OVERTIME => "Prolongation",
+        TIEBREAK_COIN_TOSS => "Pile ou face",
+        _ => "Départage",
+    }
 }
 
 #[function_component]
@@ -29,9 +45,94 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
         game,
         editable,
         on_game_update,
+        best_of,
+        sets,
     } = props;
 
     let notifs = use_notification::<CustomNotification>();
+    // the score of a series is the number of sets won, it is only edited through the sets
+    let is_series = *best_of > 1;
+    let set_scores: Vec<SetScore> = sets
+        .iter()
+        .map(|set| SetScore {
+            score1: set.score1,
+            score2: set.score2,
+        })
+        .collect();
+
+    let save_sets = {
+        let game_id = game.id;
+        let notifs = notifs.clone();
+        let on_game_update = on_game_update.clone();
+
+        move |new_sets: Vec<SetScore>| {
+            let notifs = notifs.clone();
+            let on_game_update = on_game_update.clone();
+
+            spawn_local(async move {
+                match api::games::update_sets(game_id, new_sets).await {
+                    Ok(_) => {
+                        on_game_update.emit(game_id);
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            format!("Erreur: {}", e.error.reason),
+                            e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+            });
+        }
+    };
+
+    let change_set = |index: usize, team1: bool| {
+        let set_scores = set_scores.clone();
+        let save_sets = save_sets.clone();
+
+        Callback::from(move |e: Event| {
+            if let Ok(val) = e
+                .target_unchecked_into::<HtmlInputElement>()
+                .value()
+                .parse::<i32>()
+            {
+                let mut new_sets = set_scores.clone();
+                if team1 {
+                    new_sets[index].score1 = val;
+                } else {
+                    new_sets[index].score2 = val;
+                }
+                save_sets(new_sets);
+            }
+        })
+    };
+
+    let on_click_add_set = {
+        let set_scores = set_scores.clone();
+        let save_sets = save_sets.clone();
+
+        Callback::from(move |_| {
+            let mut new_sets = set_scores.clone();
+            new_sets.push(SetScore {
+                score1: 0,
+                score2: 0,
+            });
+            save_sets(new_sets);
+        })
+    };
+
+    let on_click_remove_set = {
+        let set_scores = set_scores.clone();
+        let save_sets = save_sets.clone();
+
+        Callback::from(move |_| {
+            let mut new_sets = set_scores.clone();
+            new_sets.pop();
+            save_sets(new_sets);
+        })
+    };
 
     let change_score1 = {
         let game_id = game.id;
@@ -60,6 +161,8 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
                             score2: None,
                             phase: None,
                             status: None,
+                            tiebreak: None,
+                            tiebreak_winner: None,
                         },
                     )
                     .await
@@ -107,6 +210,8 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
                             score2: Some(val),
                             phase: None,
                             status: None,
+                            tiebreak: None,
+                            tiebreak_winner: None,
                         },
                     )
                     .await
@@ -127,6 +232,52 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
         })
     };
 
+    // a draw needs a tiebreak result before the game can be closed
+    let change_tiebreak = |winner: bool| {
+        let game_id = game.id;
+        let notifs = notifs.clone();
+        let on_game_update = on_game_update.clone();
+
+        Callback::from(move |e: Event| {
+            let value = e
+                .target_unchecked_into::<HtmlSelectElement>()
+                .value()
+                .parse::<i32>()
+                .unwrap_or(0);
+            let notifs = notifs.clone();
+            let on_game_update = on_game_update.clone();
+
+            spawn_local(async move {
+                match api::games::update(
+                    game_id,
+                    GameUpdate {
+                        score1: None,
+                        score2: None,
+                        phase: None,
+                        status: None,
+                        tiebreak: if winner { None } else { Some(value) },
+                        tiebreak_winner: if winner { Some(value) } else { None },
+                    },
+                )
+                .await
+                {
+                    Ok(_) => {
+                        on_game_update.emit(game_id);
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            format!("Erreur: {}", e.error.reason),
+                            e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+            });
+        })
+    };
+
     let on_click_start = {
         let game = game.clone();
         let notifs = notifs.clone();
@@ -145,6 +296,8 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
                         score2: None,
                         phase: None,
                         status: Some(1),
+                        tiebreak: None,
+                        tiebreak_winner: None,
                     },
                 )
                 .await
@@ -211,6 +364,8 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
                         score2: None,
                         phase: None,
                         status: Some(0),
+                        tiebreak: None,
+                        tiebreak_winner: None,
                     },
                 )
                 .await
@@ -232,12 +387,37 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
         })
     };
 
+    // the team has no opponent and goes directly to the next round
+    if game.is_bye() {
+        return html! {
+            <>
+                <li class={"game game-top"}>
+                    <div style={team_color_wrapper(game.team1.name.clone())} class={classes!("border-l-4", "team-border-color", "flex", "bg-nutLighter", "pl-2", "font-bold")}>
+                        <span>{game.team1.name.clone()}</span>
+                    </div>
+                </li>
+                <li class={"game game-spacer"}>
+                    <div class="font-bebas ml-auto px-3 text-xs rounded m-1 text-center text-white bg-green-600">{"EXEMPTÉ"}</div>
+                </li>
+                <li class={"game game-bottom"}>
+                    <div class={classes!("flex", "bg-nutLighter", "pl-2", "italic")}>
+                        <span>{"Pas d'adversaire"}</span>
+                    </div>
+                </li>
+            </>
+        };
+    }
+
+    let team1_won = game.winner() == Some(game.team1.id);
+    let team2_won = game.winner() == Some(game.team2.id);
+    let is_draw = game.score1 == game.score2;
+
     html! {
         <>
             <li class={"game game-top"}>
-                <div style={team_color_wrapper(game.team2.name.clone())} class={classes!("border-l-4", "team-border-color", "flex", "bg-nutLighter", "pl-2", if game.status == 2 && game.score1 > game.score2 {"font-bold"} else {""})}>
+                <div style={team_color_wrapper(game.team2.name.clone())} class={classes!("border-l-4", "team-border-color", "flex", "bg-nutLighter", "pl-2", if game.status == 2 && team1_won {"font-bold"} else {""})}>
                     <span>{game.team1.name.clone()}</span>
-                    <input type="number" disabled={!*editable} onchange={change_score1} value={game.score1.to_string()} class={classes!("game-input-score", if game.status == 2 {if game.score2 < game.score1 {"bg-green-300"} else {"bg-red-300"}} else {""})}/>
+                    <input type="number" disabled={!*editable || is_series} onchange={change_score1} value={game.score1.to_string()} class={classes!("game-input-score", if game.status == 2 {if team1_won {"bg-green-300"} else {"bg-red-300"}} else {""})}/>
                 </div>
             </li>
             <li class={"game game-spacer"}>
@@ -250,6 +430,57 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
                         html!{<div class="font-bebas ml-auto px-3 text-xs rounded m-1 text-center text-white bg-orange-600">{"EN ATTENTE"}</div>}
                     }
                 }
+                if is_series {
+                    <div class="text-xs text-center">
+                        {format!("BO{}", best_of)}
+                        if sets.len() > 0 {
+                            {" : "}
+                            {sets.iter().map(|set| format!("{}-{}", set.score1, set.score2)).collect::<Vec<String>>().join(" · ")}
+                        }
+                    </div>
+                }
+                if *editable && is_series && game.status == 1 {
+                    <div class="mt-1 flex flex-col gap-1 text-xs">
+                        {
+                            set_scores.iter().enumerate().map(|(index, set)| {
+                                html!{
+                                    <div class="flex gap-1 items-center">
+                                        <span>{format!("Manche {}", index + 1)}</span>
+                                        <input type="number" class="w-10" onchange={change_set(index, true)} value={set.score1.to_string()} />
+                                        <input type="number" class="w-10" onchange={change_set(index, false)} value={set.score2.to_string()} />
+                                    </div>
+                                }
+                            }).collect::<Html>()
+                        }
+                        <div class="flex gap-1">
+                            if (set_scores.len() as i32) < *best_of {
+                                <button class="font-bebas rounded bg-green-500 px-1 w-full text-white" onclick={on_click_add_set}>{"Ajouter une manche"}</button>
+                            }
+                            if set_scores.len() > 0 {
+                                <button class="font-bebas rounded bg-red-500 px-1 w-full text-white" onclick={on_click_remove_set}>{"Retirer"}</button>
+                            }
+                        </div>
+                    </div>
+                }
+                if game.status == 2 && is_draw && game.tiebreak != TIEBREAK_NONE {
+                    <div class="text-xs text-center">{tiebreak_label(game.tiebreak)}</div>
+                }
+                if *editable && game.status == 1 && is_draw {
+                    <div class="mt-1 flex flex-col gap-1 text-xs">
+                        <select onchange={change_tiebreak(false)}>
+                            {
+                                [TIEBREAK_NONE, TIEBREAK_PENALTIES, TIEBREAK_OVERTIME, TIEBREAK_COIN_TOSS].iter().map(|tiebreak| {
+                                    html!{<option value={tiebreak.to_string()} selected={game.tiebreak == *tiebreak}>{tiebreak_label(*tiebreak)}</option>}
+                                }).collect::<Html>()
+                            }
+                        </select>
+                        <select onchange={change_tiebreak(true)}>
+                            <option value="0" selected={game.winner().is_none()}>{"Vainqueur"}</option>
+                            <option value={game.team1.id.to_string()} selected={team1_won}>{game.team1.name.clone()}</option>
+                            <option value={game.team2.id.to_string()} selected={team2_won}>{game.team2.name.clone()}</option>
+                        </select>
+                    </div>
+                }
                 if *editable {
                     <div class="mt-1">
                         if game.status == 0 {
@@ -267,9 +498,9 @@ pub fn BracketMatch(props: &BracketMatchProps) -> Html {
                 }
             </li>
             <li class={"game game-bottom"}>
-                <div style={team_color_wrapper(game.team2.name.clone())} class={classes!("border-l-4", "team-border-color", "flex", "bg-nutLighter", "pl-2", if game.status == 2 && game.score2 > game.score1 {"font-bold"} else {""})}>
+                <div style={team_color_wrapper(game.team2.name.clone())} class={classes!("border-l-4", "team-border-color", "flex", "bg-nutLighter", "pl-2", if game.status == 2 && team2_won {"font-bold"} else {""})}>
                     <span>{game.team2.name.clone()}</span>
-                    <input type="number" disabled={!*editable} onchange={change_score2} value={game.score2.to_string()} class={classes!("game-input-score", if game.status == 2 {if game.score2 > game.score1 {"bg-green-300"} else {"bg-red-300"}} else {""})}/>
+                    <input type="number" disabled={!*editable || is_series} onchange={change_score2} value={game.score2.to_string()} class={classes!("game-input-score", if game.status == 2 {if team2_won {"bg-green-300"} else {"bg-red-300"}} else {""})}/>
                 </div>
             </li>
         </>
@@ -2,15 +2,26 @@ use std::collections::BTreeMap;
 
 use yew::prelude::*;
 
-use crate::{api::models::GameWithTeams, components::bracket_match::BracketMatch};
+use crate::{
+    api::models::{GameSet, GameWithTeams},
+    components::bracket_match::BracketMatch,
+};
 
 #[derive(PartialEq, Properties)]
 pub struct BracketRoundProps {
     pub games: BTreeMap<i32, GameWithTeams>,
     pub round_id: i32,
+    #[prop_or_default]
+    pub title: Option<String>,
     pub editable: bool,
     #[prop_or_default]
     pub on_game_update: Callback<i32>,
+    // number of sets played in the games of the round
+    #[prop_or(1)]
+    pub best_of: i32,
+    // sets of each game of the round, by game id
+    #[prop_or_default]
+    pub sets: BTreeMap<i32, Vec<GameSet>>,
 }
 
 #[function_component]
@@ -18,10 +29,13 @@ pub fn BracketRound(props: &BracketRoundProps) -> Html {
     let BracketRoundProps {
         games,
         round_id,
+        title,
         editable,
         on_game_update,
+        best_of,
+        sets,
     } = props;
-    let round_title = format!("Round {}", round_id);
+    let round_title = title.clone().unwrap_or(format!("Round {}", round_id));
 
     html! {
         <ul class={" round"}>
@@ -31,7 +45,7 @@ pub fn BracketRound(props: &BracketRoundProps) -> Html {
             </li>
             {games.iter().map(|(_place, game)| {
                 html!(
-                    <BracketMatch game={game.clone()} editable={editable} on_game_update={on_game_update} />
+                    <BracketMatch game={game.clone()} editable={editable} on_game_update={on_game_update} best_of={best_of} sets={sets.get(&game.id).cloned().unwrap_or_default()} />
                 )
             }).collect::<Html>()}
             <li class={"spacer"}>{"\u{00a0}"}</li>
@@ -0,0 +1,185 @@
+use time::Duration;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::{
+    api::models::{ChampionBetRequest, MarketBets, Team, Tournament},
+    components::{button::Button, user_provider::UserContext},
+    notification::{CustomNotification, NotifType},
+};
+
+#[derive(PartialEq, Properties)]
+pub struct ChampionBetsProps {
+    pub tournament: Tournament,
+    pub should_update: UseStateHandle<bool>,
+}
+
+#[function_component]
+pub fn ChampionBets(props: &ChampionBetsProps) -> Html {
+    let ChampionBetsProps {
+        tournament,
+        should_update,
+    } = props;
+    let notifs = use_notification::<CustomNotification>();
+    let user_info = use_context::<UserContext>().expect("Missing user context provider");
+
+    let market: UseStateHandle<Option<MarketBets>> = use_state(|| None);
+    let teams: UseStateHandle<Vec<Team>> = use_state(|| vec![]);
+    let trigger = use_state(|| false);
+    let team_ref = use_node_ref();
+    let nut_ref = use_node_ref();
+
+    {
+        let tournament = tournament.clone();
+        let market = market.clone();
+        let teams = teams.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    market.set(tournament.get_champion_bets().await.ok());
+
+                    if let Ok(new_teams) = tournament.get_teams().await {
+                        teams.set(new_teams);
+                    }
+                });
+            },
+            (should_update.clone(), trigger.clone()),
+        );
+    }
+
+    let on_bet = {
+        let tournament = tournament.clone();
+        let team_ref = team_ref.clone();
+        let nut_ref = nut_ref.clone();
+        let trigger = trigger.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |_| {
+            let team_id = team_ref
+                .cast::<HtmlSelectElement>()
+                .unwrap()
+                .value()
+                .parse::<i32>()
+                .unwrap_or(0);
+            let nut = nut_ref
+                .cast::<HtmlInputElement>()
+                .unwrap()
+                .value()
+                .parse::<i32>()
+                .unwrap_or(0);
+
+            let tournament = tournament.clone();
+            let trigger = trigger.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match tournament.champion_bet(ChampionBetRequest { team_id, nut }).await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Vous avez misé",
+                            &format!("Vous avez misé {} noix sur le vainqueur du tournoi", nut),
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            &format!("Erreur: {}", e.error.reason),
+                            &e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                };
+
+                trigger.set(!*trigger);
+            });
+        })
+    };
+
+    let on_cancel = {
+        let tournament = tournament.clone();
+        let trigger = trigger.clone();
+
+        Callback::from(move |_| {
+            let tournament = tournament.clone();
+            let trigger = trigger.clone();
+
+            spawn_local(async move {
+                let _ = tournament.delete_champion_bet().await;
+                trigger.set(!*trigger);
+            });
+        })
+    };
+
+    let market = match &*market {
+        Some(market) => market.clone(),
+        None => return html! {},
+    };
+
+    let user_id = user_info.user.as_ref().map(|u| u.id as i32);
+    let user_bet = market.bets.iter().find(|b| Some(b.fk_users) == user_id);
+    let team_name = |id: Option<i32>| {
+        teams
+            .iter()
+            .find(|t| Some(t.id) == id)
+            .map(|t| t.name.clone())
+            .unwrap_or_default()
+    };
+
+    html! {
+        <div class="mb-4">
+            <h3>{"Vainqueur du tournoi"}</h3>
+            <p class="discrete">{"Les mises sont fermées dès le début de la phase d'éliminations."}</p>
+            <table class="bg-nutLighter rounded text-center mt-2">
+                <thead>
+                    <tr>
+                        <th class="px-2 text-left">{"Équipe"}</th>
+                        <th class="px-2">{"Parieurs"}</th>
+                        <th class="px-2">{"Noix"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {
+                        teams.iter().map(|team| {
+                            let on_team = market.bets.iter().filter(|b| b.fk_teams == Some(team.id));
+
+                            html!{
+                                <tr>
+                                    <td class="px-2 text-left">{team.name.clone()}</td>
+                                    <td class="px-2">{on_team.clone().count()}</td>
+                                    <td class="px-2">{on_team.map(|b| b.nb_nut).sum::<i32>()}</td>
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </tbody>
+            </table>
+            if let Some(user_bet) = user_bet {
+                <p class="mt-2">{format!("Vous avez misé {} noix sur \"{}\"", user_bet.nb_nut, team_name(user_bet.fk_teams))}</p>
+                if let Some(payout) = user_bet.payout {
+                    <p>{format!("Vous avez récupéré {} noix.", payout)}</p>
+                } else if market.is_open {
+                    <Button class="px-3 py-2 hover:scale-110 mt-2" onclick={on_cancel}>{"Annuler la mise"}</Button>
+                }
+            } else if market.is_open {
+                <div class="flex flex-wrap items-center gap-2 mt-2">
+                    <select ref={team_ref}>
+                        {
+                            teams.iter().map(|team| {
+                                html!{<option value={team.id.to_string()}>{team.name.clone()}</option>}
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    <input ref={nut_ref} type="number" min="1" class="w-20 px-1" placeholder="Noix"/>
+                    <Button class="px-3 py-2 hover:scale-110" onclick={on_bet}>{"Miser"}</Button>
+                </div>
+            } else {
+                <p class="mt-2">{"Les mises sur le vainqueur sont fermées."}</p>
+            }
+        </div>
+    }
+}
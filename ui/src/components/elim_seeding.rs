@@ -0,0 +1,136 @@
+use time::Duration;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::{
+    api::models::{Team, Tournament},
+    components::{button::Button, group_drop::GroupDrop},
+    notification::{CustomNotification, NotifType},
+};
+
+#[derive(PartialEq, Properties)]
+pub struct ElimSeedingProps {
+    pub tournament: Tournament,
+    pub should_update: UseStateHandle<bool>,
+}
+
+#[function_component]
+pub fn ElimSeeding(props: &ElimSeedingProps) -> Html {
+    let ElimSeedingProps {
+        tournament,
+        should_update,
+    } = props;
+    let notifs = use_notification::<CustomNotification>();
+
+    let teams: UseStateHandle<Vec<Team>> = use_state(|| vec![]);
+    // team ids, best seed first
+    let seeds: UseStateHandle<Vec<i32>> = use_state(|| vec![]);
+    let trigger = use_state(|| false);
+
+    {
+        let tournament = tournament.clone();
+        let teams = teams.clone();
+        let seeds = seeds.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Some(t) = tournament.get_teams().await.ok() {
+                        teams.set(t);
+                    }
+
+                    // no seeding until the elimination games are generated
+                    match tournament.get_elim_seeding().await {
+                        Ok(seeding) => seeds.set(seeding.seeds),
+                        Err(_) => seeds.set(vec![]),
+                    }
+                });
+            },
+            should_update.clone(),
+        );
+    }
+
+    // a team dropped on a seed takes the place of the team that had it
+    let on_drop = {
+        let seeds = seeds.clone();
+
+        Callback::from(move |(team_id, seed): (i32, i32)| {
+            let mut new_seeds = (*seeds).clone();
+            let from = new_seeds.iter().position(|t| *t == team_id);
+            let to = (seed - 1) as usize;
+
+            if let Some(from) = from {
+                if to < new_seeds.len() {
+                    new_seeds.swap(from, to);
+                    seeds.set(new_seeds);
+                }
+            }
+        })
+    };
+
+    let on_save_click = {
+        let tournament = tournament.clone();
+        let seeds = seeds.clone();
+        let should_update = should_update.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |_| {
+            let tournament = tournament.clone();
+            let seeds = seeds.clone();
+            let should_update = should_update.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match tournament.update_elim_seeding((*seeds).clone()).await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Têtes de série enregistrées",
+                            "Les matches du premier tour ont été générés à nouveau",
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            &format!("Erreur: {}", e.error.reason),
+                            &e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                };
+
+                should_update.set(!*should_update);
+            });
+        })
+    };
+
+    html! {
+        if seeds.len() > 0 {
+            <div class="flex flex-col items-center p-3 w-full">
+                <h3>{"Têtes de série"}</h3>
+                <ul class="flex flex-wrap gap-3 w-full justify-center">
+                    {
+                        seeds.iter().enumerate().map(|(index, team_id)| {
+                            let seed_teams: Vec<Team> = teams.iter().filter(|t| t.id == *team_id).cloned().collect();
+
+                            html!{
+                                <GroupDrop
+                                    id={index as i32 + 1}
+                                    title={format!("Tête de série {}", index + 1)}
+                                    zone="seed-item"
+                                    teams={seed_teams}
+                                    update_trigger={trigger.clone()}
+                                    editable={true}
+                                    on_drop={on_drop.clone()}
+                                />
+                            }
+                        }).collect::<Html>()
+                    }
+                </ul>
+                <Button class="text-lg px-3 py-2 mt-3 hover:scale-110 bg-green-700" onclick={on_save_click}>{"Enregistrer les têtes de série"}</Button>
+            </div>
+        }
+    }
+}
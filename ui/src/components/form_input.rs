@@ -19,6 +19,8 @@ pub struct FormInputProps {
     pub value: AttrValue,
     #[prop_or_default]
     pub onchange: Callback<Event>,
+    #[prop_or_default]
+    pub oninput: Callback<InputEvent>,
 }
 
 #[function_component]
@@ -34,6 +36,7 @@ pub fn FormInput(props: &FormInputProps) -> Html {
         _ref,
         value,
         onchange,
+        oninput,
     } = props;
 
     html! {
@@ -43,6 +46,7 @@ pub fn FormInput(props: &FormInputProps) -> Html {
                 checked={*checked}
                 value={value}
                 onchange={onchange}
+                oninput={oninput}
             />
             <div>{label}</div>
         </label>
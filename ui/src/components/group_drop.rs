@@ -5,7 +5,7 @@ use yew_hooks::{use_drop_with_options, UseDropOptions};
 use crate::{
     api::{
         self,
-        models::{Team, TeamUpdate},
+        models::{Standing, Team, TeamUpdate},
     },
     components::{team_drag::TeamDrag, team_no_drag::TeamNoDrag},
 };
@@ -16,7 +16,18 @@ pub struct GroupDropProps {
     pub teams: Vec<Team>,
     pub update_trigger: UseStateHandle<bool>,
     #[prop_or_default]
-    pub editable: bool
+    pub editable: bool,
+    #[prop_or_default]
+    pub standings: Vec<Standing>,
+    // title shown instead of the group number
+    #[prop_or_default]
+    pub title: Option<String>,
+    // class of the drop zone, teams are only dropped on zones of the same class
+    #[prop_or(AttrValue::Static("group-item"))]
+    pub zone: AttrValue,
+    // called with the dropped team and the id instead of moving the team to the group
+    #[prop_or_default]
+    pub on_drop: Option<Callback<(i32, i32)>>,
 }
 
 #[function_component]
@@ -25,12 +36,29 @@ pub fn GroupDrop(props: &GroupDropProps) -> Html {
         id,
         teams,
         update_trigger,
-        editable
+        editable,
+        standings,
+        title,
+        zone,
+        on_drop,
     } = props;
+
+    // show the teams in the order of the standings once games have been played
+    let has_played = standings.iter().any(|s| s.played > 0);
+    let mut teams = teams.clone();
+    if has_played {
+        teams.sort_by_key(|team| {
+            standings
+                .iter()
+                .position(|s| s.team_id == team.id)
+                .unwrap_or(usize::MAX)
+        });
+    }
     let node = use_node_ref();
     let state = {
         let id = id.clone();
         let update_trigger = update_trigger.clone();
+        let on_drop = on_drop.clone();
 
         use_drop_with_options(
             node.clone(),
@@ -44,6 +72,11 @@ pub fn GroupDrop(props: &GroupDropProps) -> Html {
                         .parse::<i32>()
                         .unwrap();
 
+                    if let Some(on_drop) = &on_drop {
+                        on_drop.emit((team_id, id));
+                        return;
+                    }
+
                     let update_trigger = update_trigger.clone();
                     spawn_local(async move {
                         let _ = api::teams::update(
@@ -63,21 +96,61 @@ pub fn GroupDrop(props: &GroupDropProps) -> Html {
     };
 
     html! {
-         <li id={id.to_string()} class={format!("group-item relative {}", if *state.over {"bg-green-200"} else {"bg-nutLighter"})} ref={node}>
+         <li id={format!("{}-{}", zone, id)} data-id={id.to_string()} class={format!("{} relative {}", zone, if *state.over {"bg-green-200"} else {"bg-nutLighter"})} ref={node}>
             <div class="flex justify-center">
-                <div>{"Groupe "}{id}</div>
+                <div>{title.clone().unwrap_or_else(|| format!("Groupe {}", id))}</div>
             </div>
             <div class="flex flex-col gap-1 team-list">
                 {
                     teams.iter().map(|team| {
                         if *editable {
-                            html! (<TeamDrag team={team.clone()} update_trigger={update_trigger.clone()} />)
+                            html! (<TeamDrag team={team.clone()} update_trigger={update_trigger.clone()} zone={zone.clone()} on_drop={on_drop.clone()} />)
                         } else {
                             html! (<TeamNoDrag team={team.clone()} />)
                         }
                     }).collect::<Html>()
                 }
             </div>
+            if has_played {
+                <table class="w-full mt-2 text-sm text-center">
+                    <thead>
+                        <tr>
+                            <th>{"#"}</th>
+                            <th>{"Équipe"}</th>
+                            <th>{"J"}</th>
+                            <th>{"V"}</th>
+                            <th>{"N"}</th>
+                            <th>{"D"}</th>
+                            <th>{"Diff."}</th>
+                            <th>{"Pts"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            standings.iter().enumerate().map(|(index, standing)| {
+                                let name = teams
+                                    .iter()
+                                    .find(|t| t.id == standing.team_id)
+                                    .map(|t| t.name.clone())
+                                    .unwrap_or_default();
+
+                                html!{
+                                    <tr>
+                                        <td>{index + 1}</td>
+                                        <td class="text-left">{name}</td>
+                                        <td>{standing.played}</td>
+                                        <td>{standing.won}</td>
+                                        <td>{standing.drawn}</td>
+                                        <td>{standing.lost}</td>
+                                        <td>{format!("{} ({}:{})", standing.score_diff, standing.scored, standing.conceded)}</td>
+                                        <td class="font-bold">{standing.points}</td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </tbody>
+                </table>
+            }
         </li>
     }
 }
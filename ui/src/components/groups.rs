@@ -7,7 +7,7 @@ use yew_hooks::{use_drop_with_options, UseDropOptions};
 use crate::{
     api::{
         self,
-        models::{Team, TeamUpdate, Tournament},
+        models::{Standing, Team, TeamUpdate, Tournament},
     },
     components::{group_drop::GroupDrop, loading_circle::LoadingCircle, team_drag::TeamDrag},
 };
@@ -32,6 +32,7 @@ pub fn Groups(props: &GroupsProps) -> Html {
     } = props;
 
     let groups: UseStateHandle<BTreeMap<i32, Vec<Team>>> = use_state(|| BTreeMap::new());
+    let standings: UseStateHandle<BTreeMap<i32, Vec<Standing>>> = use_state(|| BTreeMap::new());
     let trigger = use_state(|| false);
     let loading = use_state(|| true);
 
@@ -108,6 +109,7 @@ pub fn Groups(props: &GroupsProps) -> Html {
     {
         let tournament = tournament.clone();
         let groups = groups.clone();
+        let standings = standings.clone();
         let loading = loading.clone();
 
         use_effect_with_deps(
@@ -125,7 +127,15 @@ pub fn Groups(props: &GroupsProps) -> Html {
                             }
                         }
 
+                        let mut new_standings: BTreeMap<i32, Vec<Standing>> = BTreeMap::new();
+                        for group in new_groups.keys().filter(|group| **group != 0) {
+                            if let Some(group_standings) = tournament.get_group_standings(*group).await.ok() {
+                                new_standings.insert(*group, group_standings);
+                            }
+                        }
+
                         groups.set(new_groups);
+                        standings.set(new_standings);
                         loading.set(false);
                     }
                 });
@@ -166,7 +176,7 @@ pub fn Groups(props: &GroupsProps) -> Html {
                         groups.iter().map(|(index, teams)| {
                             if *index == 0 {return html!(<></>)}
                             html!{
-                                <GroupDrop id={index} teams={teams.clone()} update_trigger={trigger.clone()} editable={*editable} />
+                                <GroupDrop id={index} teams={teams.clone()} standings={standings.get(index).cloned().unwrap_or_default()} update_trigger={trigger.clone()} editable={*editable} />
                             }
                         }).collect::<Html>()
                     }
@@ -4,15 +4,22 @@ pub mod bracket;
 pub mod bracket_match;
 pub mod bracket_round;
 pub mod button;
+pub mod champion_bets;
 pub mod checkbox;
+pub mod elim_seeding;
 pub mod form_input;
 pub mod group_drop;
 pub mod groups;
 pub mod join_code;
 pub mod loading_circle;
+pub mod nut_audit;
+pub mod nut_history;
 pub mod qualif_game;
 pub mod qualification_phase;
 pub mod results;
+pub mod score_bets;
+pub mod series_formats;
+pub mod swiss_standings;
 pub mod team_bet;
 pub mod team_card;
 pub mod team_drag;
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use time::Duration;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::{
+    api::models::{NutAudit, PatchNut, Tournament},
+    components::{button::Button, nut_history::transaction_rows},
+    notification::{CustomNotification, NotifType},
+};
+
+#[derive(PartialEq, Properties)]
+pub struct NutAuditsProps {
+    pub tournament: Tournament,
+    pub should_update: UseStateHandle<bool>,
+}
+
+#[function_component]
+pub fn NutAudits(props: &NutAuditsProps) -> Html {
+    let NutAuditsProps {
+        tournament,
+        should_update,
+    } = props;
+    let notifs = use_notification::<CustomNotification>();
+
+    let audits: UseStateHandle<Vec<NutAudit>> = use_state(|| vec![]);
+    // new stock and reason typed for each nut
+    let stock_refs = use_state(|| HashMap::<i32, (NodeRef, NodeRef)>::new());
+    let trigger = use_state(|| false);
+
+    {
+        let tournament = tournament.clone();
+        let audits = audits.clone();
+        let stock_refs = stock_refs.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Ok(new_audits) = tournament.get_nut_audit().await {
+                        stock_refs.set(
+                            new_audits
+                                .iter()
+                                .map(|a| (a.fk_nuts, (NodeRef::default(), NodeRef::default())))
+                                .collect(),
+                        );
+                        audits.set(new_audits);
+                    }
+                });
+            },
+            (should_update.clone(), trigger.clone()),
+        );
+    }
+
+    let on_adjust = {
+        let stock_refs = stock_refs.clone();
+        let trigger = trigger.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |audit: NutAudit| {
+            let (stock_ref, reason_ref) = match stock_refs.get(&audit.fk_nuts) {
+                Some(refs) => refs.clone(),
+                None => return,
+            };
+
+            let stock = stock_ref
+                .cast::<HtmlInputElement>()
+                .unwrap()
+                .value()
+                .parse::<i32>()
+                .unwrap_or(audit.stock);
+            let reason = reason_ref.cast::<HtmlInputElement>().unwrap().value();
+
+            let trigger = trigger.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match audit.adjust(PatchNut { stock, reason }).await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Noix ajustées",
+                            &format!("{} possède maintenant {} noix", audit.username, stock),
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            &format!("Erreur: {}", e.error.reason),
+                            &e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                };
+
+                trigger.set(!*trigger);
+            });
+        })
+    };
+
+    html! {
+        <div class="flex flex-col gap-4 w-full">
+            if audits.len() > 0 {
+                {
+                    audits.iter().map(|audit| {
+                        let (stock_ref, reason_ref) = stock_refs.get(&audit.fk_nuts).cloned().unwrap_or_default();
+                        let onclick = {
+                            let audit = audit.clone();
+                            let on_adjust = on_adjust.clone();
+                            Callback::from(move |_| on_adjust.emit(audit.clone()))
+                        };
+
+                        html!{
+                            <div class="bg-nutLight rounded p-2">
+                                <div class="flex flex-wrap items-center gap-4">
+                                    <span class="font-bold">{audit.username.clone()}</span>
+                                    <span>{format!("{} noix", audit.stock)}</span>
+                                    if audit.stock != audit.ledger_stock {
+                                        <span class="text-red-700">{format!("L'historique donne {} noix", audit.ledger_stock)}</span>
+                                    }
+                                    <input ref={stock_ref} type="number" min="0" class="w-20 px-1" value={audit.stock.to_string()}/>
+                                    <input ref={reason_ref} type="text" class="px-1" placeholder="Raison de l'ajustement"/>
+                                    <Button class="px-2 py-1 hover:scale-110" onclick={onclick}>{"Ajuster"}</Button>
+                                </div>
+                                <details>
+                                    <summary class="cursor-pointer">{"Historique"}</summary>
+                                    <table class="bg-nutLighter rounded text-center mt-2">
+                                        <tbody>
+                                            {transaction_rows(&audit.transactions)}
+                                        </tbody>
+                                    </table>
+                                </details>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            } else {
+                {"Aucun joueur pour le moment"}
+            }
+        </div>
+    }
+}
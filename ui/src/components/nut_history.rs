@@ -0,0 +1,78 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::api::models::{NutTransaction, Tournament};
+
+#[derive(PartialEq, Properties)]
+pub struct NutHistoryProps {
+    pub tournament: Tournament,
+    pub should_update: UseStateHandle<bool>,
+}
+
+// rows of a ledger, latest first
+pub fn transaction_rows(transactions: &[NutTransaction]) -> Html {
+    transactions
+        .iter()
+        .map(|transaction| {
+            html! {
+                <tr>
+                    <td class="px-2">{transaction.created_at.format("%d/%m/%Y %H:%M").to_string()}</td>
+                    <td class="px-2 text-left">{transaction.label()}</td>
+                    <td class={classes!("px-2", if transaction.amount < 0 { "text-red-700" } else { "text-green-700" })}>
+                        {format!("{:+}", transaction.amount)}
+                    </td>
+                    <td class="px-2 font-bold">{transaction.balance}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>()
+}
+
+#[function_component]
+pub fn NutHistory(props: &NutHistoryProps) -> Html {
+    let NutHistoryProps {
+        tournament,
+        should_update,
+    } = props;
+
+    let transactions: UseStateHandle<Vec<NutTransaction>> = use_state(|| vec![]);
+
+    {
+        let tournament = tournament.clone();
+        let transactions = transactions.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Ok(new_transactions) = tournament.get_nut_history().await {
+                        transactions.set(new_transactions);
+                    }
+                });
+            },
+            should_update.clone(),
+        );
+    }
+
+    html! {
+        <details class="mb-4">
+            <summary class="cursor-pointer">{"Historique de mes noix"}</summary>
+            if transactions.len() > 0 {
+                <table class="bg-nutLighter rounded text-center mt-2">
+                    <thead>
+                        <tr>
+                            <th class="px-2">{"Date"}</th>
+                            <th class="px-2">{"Opération"}</th>
+                            <th class="px-2">{"Noix"}</th>
+                            <th class="px-2">{"Solde"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {transaction_rows(&transactions)}
+                    </tbody>
+                </table>
+            } else {
+                <p class="discrete">{"Aucune opération pour le moment"}</p>
+            }
+        </details>
+    }
+}
@@ -51,6 +51,8 @@ pub fn QualifGame(props: &QualifGameProps) -> Html {
                         score2: None,
                         phase: None,
                         status: Some(1),
+                        tiebreak: None,
+                        tiebreak_winner: None,
                     },
                 )
                 .await
@@ -121,6 +123,8 @@ pub fn QualifGame(props: &QualifGameProps) -> Html {
                         score2: None,
                         phase: None,
                         status: Some(0),
+                        tiebreak: None,
+                        tiebreak_winner: None,
                     },
                 )
                 .await
@@ -169,6 +173,8 @@ pub fn QualifGame(props: &QualifGameProps) -> Html {
                             score2: None,
                             phase: None,
                             status: None,
+                            tiebreak: None,
+                            tiebreak_winner: None,
                         },
                     )
                     .await
@@ -216,6 +222,8 @@ pub fn QualifGame(props: &QualifGameProps) -> Html {
                             score2: Some(val),
                             phase: None,
                             status: None,
+                            tiebreak: None,
+                            tiebreak_winner: None,
                         },
                     )
                     .await
@@ -236,6 +244,20 @@ pub fn QualifGame(props: &QualifGameProps) -> Html {
         })
     };
 
+    if game.is_bye() {
+        return html! {
+            <div>
+                <hr class="m-0 border-nutLight drop-shadow-none"/>
+                <li class="rounded relative flex justify-center items-center">
+                    <div style={team_color_wrapper(game.team1.name.clone())} class="team-border-color border-r-4 px-2 m-2 rounded-l bg-nutLight w-24 text-right">
+                        {game.team1.name.clone()}
+                    </div>
+                    <div class="font-bebas text-xs rounded m-1 px-2 text-center text-white bg-green-600">{"EXEMPTÉ"}</div>
+                </li>
+            </div>
+        };
+    }
+
     html! {
         <div>
             <hr class="m-0 border-nutLight drop-shadow-none"/>
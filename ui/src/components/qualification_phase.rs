@@ -45,13 +45,20 @@ pub fn QualificationPhase(props: &QualificationPhaseProps) -> Html {
                                 continue;
                             }
 
-                            if game.group.unwrap() == 0 {
+                            // swiss games are shown by round instead of by group
+                            let key = if tournament.is_swiss {
+                                game.round
+                            } else {
+                                game.group.unwrap()
+                            };
+
+                            if key == 0 {
                                 continue;
                             }
-                            if new_groups.contains_key(&game.group.unwrap()) {
-                                new_groups.get_mut(&game.group.unwrap()).unwrap().push(game);
+                            if new_groups.contains_key(&key) {
+                                new_groups.get_mut(&key).unwrap().push(game);
                             } else {
-                                new_groups.insert(game.group.unwrap(), vec![game]);
+                                new_groups.insert(key, vec![game]);
                             }
                         }
 
@@ -74,7 +81,7 @@ pub fn QualificationPhase(props: &QualificationPhaseProps) -> Html {
                 {
                     group_matches.iter().map(|(index, group_match)| {
                         html!{<li class="rounded relative basis-72 bg-nutLighter flex flex-col justify-center items-center">
-                            <h3 class="text-center">{"Groupe "}{index}</h3>
+                            <h3 class="text-center">{if tournament.is_swiss {"Ronde "} else {"Groupe "}}{index}</h3>
                             <ul>
                                 {
                                     group_match.iter().map(|game| {
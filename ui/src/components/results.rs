@@ -3,7 +3,7 @@ use time::Duration;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
-use crate::{utils::utils::team_color_wrapper, api::{self, tournoix::Score}, notification::{NotifType, CustomNotification}};
+use crate::{utils::utils::team_color_wrapper, api::{self, tournoix::TeamResult}, notification::{NotifType, CustomNotification}};
 
 #[derive(PartialEq, Properties)]
 pub struct ResultsProps {
@@ -33,39 +33,11 @@ pub fn Results(props: &ResultsProps) -> Html {
                     }
                 };
 
-                if let Some(data) = data {
-                    // Sort by score value
-                    let mut teams_score_copy: Vec<Score> = vec![];
-                    let mut gamblers_score_copy: Vec<Score> = vec![];
-                    for score in (*data.teams).iter() {
-                        teams_score_copy.push({
-                            Score {
-                                name: score.name.clone(),
-                                val: score.val,
-                            }
-                        });
-                    }
-                    for score in (*data.subscribers).iter() {
-                        gamblers_score_copy.push({
-                            Score {
-                                name: score.name.clone(),
-                                val: score.val,
-                            }
-                        });
-                    }
-
-                    // Sort by rank, smaller is better
-                    teams_score_copy.sort_by(|a, b| a.val.cmp(&b.val));
+                if let Some(mut data) = data {
+                    // Teams come ranked by place, sort gamblers by nut number, bigger is better
+                    data.subscribers.sort_by(|a, b| b.val.cmp(&a.val));
 
-                    // Sort by nut number, bigger is better
-                    gamblers_score_copy.sort_by(|a, b| b.val.cmp(&a.val));
-
-                    results.set(Some(
-                        api::tournoix::Results {
-                            teams: teams_score_copy,
-                            subscribers: gamblers_score_copy,
-                        }
-                    ));
+                    results.set(Some(data));
                 }
             });
         }, tournament_id.clone());
@@ -88,6 +60,53 @@ pub fn Results(props: &ResultsProps) -> Html {
                         </ul>
                     </div>
                     <div class="flex flex-col gap-12">
+                        if results.teams.len() > 0 {
+                            <div class="flex flex-col items-center m-4">
+                                <h3 class="mb-4">{"Classement des équipes"}</h3>
+                                <div class="flex items-end gap-4 mb-8">
+                                    // runner-up on the left, champion in the middle
+                                    {podium_step(results.teams.iter().filter(|t| t.place == 2).collect(), "2e", "h-16")}
+                                    {podium_step(results.teams.iter().filter(|t| t.place == 1).collect(), "1er", "h-24")}
+                                    {podium_step(results.teams.iter().filter(|t| t.place == 3).collect(), "3e", "h-10")}
+                                </div>
+                                <table class="bg-nutLighter rounded text-center">
+                                    <thead>
+                                        <tr>
+                                            <th class="px-2">{"#"}</th>
+                                            <th class="px-2">{"Équipe"}</th>
+                                            <th class="px-2">{"J"}</th>
+                                            <th class="px-2">{"V"}</th>
+                                            <th class="px-2">{"N"}</th>
+                                            <th class="px-2">{"D"}</th>
+                                            <th class="px-2">{"Marqués"}</th>
+                                            <th class="px-2">{"Encaissés"}</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {
+                                            results.teams.iter().map(|team| {
+                                                html!{
+                                                    <tr>
+                                                        <td class="px-2 font-bold">{team.place}</td>
+                                                        <td class="px-2">
+                                                            <div style={team_color_wrapper(team.name.clone())} class="team-border-color border-l-4 px-2 m-1 rounded-r bg-nutLight text-left">
+                                                                {team.name.clone()}
+                                                            </div>
+                                                        </td>
+                                                        <td class="px-2">{team.played}</td>
+                                                        <td class="px-2">{team.won}</td>
+                                                        <td class="px-2">{team.drawn}</td>
+                                                        <td class="px-2">{team.lost}</td>
+                                                        <td class="px-2">{team.scored}</td>
+                                                        <td class="px-2">{team.conceded}</td>
+                                                    </tr>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
                         <div class="flex flex-col justify-center items-center m-4">
                             if let Some(winning_gambler_score) = results.subscribers.get(0) {
                                 <h1 class="mb-8">{"Bravo !"}</h1>
@@ -116,3 +135,27 @@ pub fn Results(props: &ResultsProps) -> Html {
         </div>
     }
 }
+
+// one step of the podium, teams knocked out in the same round share it
+fn podium_step(teams: Vec<&TeamResult>, label: &str, height: &str) -> Html {
+    if teams.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="flex flex-col items-center w-32">
+            {
+                teams.iter().map(|team| {
+                    html!{
+                        <div style={team_color_wrapper(team.name.clone())} class="team-border-color border-b-4 px-2 mb-2 text-center">
+                            {team.name.clone()}
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            <div class={classes!("w-full", "bg-nutLight", "rounded-t", "flex", "justify-center", "items-center", "font-bold", height.to_string())}>
+                {label}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,144 @@
+use time::Duration;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::{
+    api::{
+        self,
+        game::ScoreBetData,
+        models::{GameWithTeams, MarketBets},
+    },
+    components::{button::Button, user_provider::UserContext},
+    notification::{CustomNotification, NotifType},
+};
+
+#[derive(PartialEq, Properties)]
+pub struct ScoreBetsProps {
+    pub game: GameWithTeams,
+}
+
+#[function_component]
+pub fn ScoreBets(props: &ScoreBetsProps) -> Html {
+    let ScoreBetsProps { game } = props;
+    let notifs = use_notification::<CustomNotification>();
+    let user_info = use_context::<UserContext>().expect("Missing user context provider");
+
+    let market: UseStateHandle<Option<MarketBets>> = use_state(|| None);
+    let trigger = use_state(|| false);
+    let score1_ref = use_node_ref();
+    let score2_ref = use_node_ref();
+    let nut_ref = use_node_ref();
+
+    {
+        let market = market.clone();
+        let game_id = game.id;
+
+        // the bets are settled when the game is closed
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    market.set(api::game::get_score_bets(game_id).await.ok());
+                });
+            },
+            (game.status, trigger.clone()),
+        );
+    }
+
+    let on_bet = {
+        let game_id = game.id;
+        let score1_ref = score1_ref.clone();
+        let score2_ref = score2_ref.clone();
+        let nut_ref = nut_ref.clone();
+        let trigger = trigger.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |_| {
+            let value = |node: &NodeRef| {
+                node.cast::<HtmlInputElement>()
+                    .unwrap()
+                    .value()
+                    .parse::<i32>()
+                    .unwrap_or(-1)
+            };
+            let bet = ScoreBetData {
+                score1: value(&score1_ref),
+                score2: value(&score2_ref),
+                nut: value(&nut_ref),
+            };
+
+            let trigger = trigger.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                if let Err(e) = api::game::score_bet(game_id, bet).await {
+                    notifs.spawn(CustomNotification::new(
+                        &format!("Erreur: {}", e.error.reason),
+                        &e.error.description,
+                        NotifType::Error,
+                        Duration::seconds(5),
+                    ));
+                }
+
+                trigger.set(!*trigger);
+            });
+        })
+    };
+
+    let on_cancel = {
+        let game_id = game.id;
+        let trigger = trigger.clone();
+
+        Callback::from(move |_| {
+            let trigger = trigger.clone();
+
+            spawn_local(async move {
+                let _ = api::game::delete_score_bet(game_id).await;
+                trigger.set(!*trigger);
+            });
+        })
+    };
+
+    let market = match &*market {
+        Some(market) => market.clone(),
+        None => return html! {},
+    };
+
+    let user_id = user_info.user.as_ref().map(|u| u.id as i32);
+    let user_bet = market.bets.iter().find(|b| Some(b.fk_users) == user_id);
+
+    html! {
+        <div class="flex flex-col items-center mt-6">
+            <h2>{"Score exact"}</h2>
+            <p class="discrete">{format!("{} noix en jeu, partagées entre ceux qui trouvent le score", market.pot)}</p>
+            if let Some(user_bet) = user_bet {
+                <div>{format!("Vous avez misé {} noix sur {} - {}", user_bet.nb_nut, user_bet.score1.unwrap_or(0), user_bet.score2.unwrap_or(0))}</div>
+                if let Some(payout) = user_bet.payout {
+                    <div>{format!("Vous avez récupéré {} noix.", payout)}</div>
+                } else if market.is_open {
+                    <Button class="px-3 py-2 hover:scale-110 mt-2" onclick={on_cancel}>{"Annuler la mise"}</Button>
+                }
+            } else if market.is_open {
+                <div class="flex flex-wrap items-center justify-center gap-2">
+                    <input ref={score1_ref} type="number" min="0" class="w-16 px-1" placeholder={game.team1.name.clone()}/>
+                    {"-"}
+                    <input ref={score2_ref} type="number" min="0" class="w-16 px-1" placeholder={game.team2.name.clone()}/>
+                    <input ref={nut_ref} type="number" min="1" class="w-20 px-1" placeholder="Noix"/>
+                    <Button class="px-3 py-2 hover:scale-110" onclick={on_bet}>{"Miser sur ce score"}</Button>
+                </div>
+            }
+            <ul class="bg-white rounded mt-2 max-h-[17rem] overflow-y-auto">
+                {
+                    market.bets.iter().enumerate().map(|(index, bet)| {
+                        html!{
+                            <li class={if index % 2 == 0 { "px-2 py-1" } else { "px-2 py-1 bg-[#f1f1f1]" }}>
+                                {format!("{} - {} : {} ({} noix)", bet.score1.unwrap_or(0), bet.score2.unwrap_or(0), bet.username, bet.nb_nut)}
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+
+use time::Duration;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::{
+    api::models::{
+        AddSeriesFormat, Tournament, BRACKET_GRAND_FINAL, BRACKET_LOWER, BRACKET_THIRD_PLACE,
+        BRACKET_UPPER,
+    },
+    components::button::Button,
+    notification::{CustomNotification, NotifType},
+};
+
+// longest series a round can be played in
+const MAX_BEST_OF: i32 = 9;
+
+#[derive(PartialEq, Properties)]
+pub struct SeriesFormatsProps {
+    pub tournament: Tournament,
+    pub should_update: UseStateHandle<bool>,
+}
+
+// (bracket, phase) of a round and its series length
+type Formats = BTreeMap<(i32, i32), i32>;
+
+fn round_label(bracket: i32, phase: i32, nb_rounds: i32) -> String {
+    match bracket {
+        BRACKET_LOWER => format!("Repêchage {}", phase),
+        BRACKET_GRAND_FINAL if phase == 1 => "Grande finale".to_string(),
+        BRACKET_GRAND_FINAL => "Finale rejouée".to_string(),
+        BRACKET_THIRD_PLACE => "Petite finale".to_string(),
+        _ if phase == nb_rounds => "Finale".to_string(),
+        _ if phase == nb_rounds - 1 => "Demi-finales".to_string(),
+        _ => format!("Round {}", phase),
+    }
+}
+
+#[function_component]
+pub fn SeriesFormats(props: &SeriesFormatsProps) -> Html {
+    let SeriesFormatsProps {
+        tournament,
+        should_update,
+    } = props;
+    let notifs = use_notification::<CustomNotification>();
+
+    let formats: UseStateHandle<Formats> = use_state(|| BTreeMap::new());
+    let nb_rounds = use_state(|| 0);
+
+    {
+        let tournament = tournament.clone();
+        let formats = formats.clone();
+        let nb_rounds = nb_rounds.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    let games = tournament.get_matches().await.unwrap_or_default();
+                    let first_round = games
+                        .iter()
+                        .filter(|g| g.phase == 1 && g.bracket == BRACKET_UPPER)
+                        .count() as i32;
+
+                    if first_round == 0 {
+                        formats.set(BTreeMap::new());
+                        nb_rounds.set(0);
+                        return;
+                    }
+
+                    // the first round holds every team, each round halves them
+                    let mut rounds = 1;
+                    while (1 << (rounds - 1)) < first_round {
+                        rounds += 1;
+                    }
+
+                    let mut new_formats: Formats = BTreeMap::new();
+                    for phase in 1..=rounds {
+                        new_formats.insert((BRACKET_UPPER, phase), 1);
+                    }
+
+                    if tournament.is_double_elim {
+                        for phase in 1..=2 * (rounds - 1) {
+                            new_formats.insert((BRACKET_LOWER, phase), 1);
+                        }
+
+                        new_formats.insert((BRACKET_GRAND_FINAL, 1), 1);
+                        if tournament.has_bracket_reset {
+                            new_formats.insert((BRACKET_GRAND_FINAL, 2), 1);
+                        }
+                    } else if tournament.has_third_place && rounds > 1 {
+                        new_formats.insert((BRACKET_THIRD_PLACE, 1), 1);
+                    }
+
+                    if let Ok(saved) = tournament.get_series_formats().await {
+                        for format in saved {
+                            if let Some(best_of) = new_formats.get_mut(&(format.bracket, format.phase)) {
+                                *best_of = format.best_of;
+                            }
+                        }
+                    }
+
+                    formats.set(new_formats);
+                    nb_rounds.set(rounds);
+                });
+            },
+            should_update.clone(),
+        );
+    }
+
+    let on_change = |key: (i32, i32)| {
+        let formats = formats.clone();
+
+        Callback::from(move |e: Event| {
+            let value = e
+                .target_unchecked_into::<HtmlInputElement>()
+                .value()
+                .parse::<i32>()
+                .unwrap_or(1)
+                .clamp(1, MAX_BEST_OF);
+
+            let mut new_formats = (*formats).clone();
+            new_formats.insert(key, value);
+            formats.set(new_formats);
+        })
+    };
+
+    let on_save_click = {
+        let tournament = tournament.clone();
+        let formats = formats.clone();
+        let should_update = should_update.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |_| {
+            let tournament = tournament.clone();
+            let should_update = should_update.clone();
+            let notifs = notifs.clone();
+            let new_formats = formats
+                .iter()
+                .map(|((bracket, phase), best_of)| AddSeriesFormat {
+                    bracket: *bracket,
+                    phase: *phase,
+                    best_of: *best_of,
+                })
+                .collect();
+
+            spawn_local(async move {
+                match tournament.update_series_formats(new_formats).await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Séries enregistrées",
+                            "Le format des séries a été mis à jour",
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            &format!("Erreur: {}", e.error.reason),
+                            &e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                };
+
+                should_update.set(!*should_update);
+            });
+        })
+    };
+
+    html! {
+        <div class="flex flex-col items-center p-3 w-full">
+            <h3>{"Format des séries"}</h3>
+            if formats.len() > 0 {
+                <ul class="flex flex-wrap gap-3 w-full justify-center">
+                    {
+                        formats.iter().map(|((bracket, phase), best_of)| {
+                            html!{
+                                <li class="flex flex-col items-center bg-nutLighter p-2">
+                                    <span class="font-bold">{round_label(*bracket, *phase, *nb_rounds)}</span>
+                                    <label class="flex gap-2 items-center">
+                                        {"Au meilleur de"}
+                                        <input type="number" min="1" max={MAX_BEST_OF.to_string()} class="w-12" value={best_of.to_string()} onchange={on_change((*bracket, *phase))} />
+                                    </label>
+                                </li>
+                            }
+                        }).collect::<Html>()
+                    }
+                </ul>
+                <Button class="text-lg px-3 py-2 mt-3 hover:scale-110 bg-green-700" onclick={on_save_click}>{"Enregistrer les séries"}</Button>
+            } else {
+                <p>{"Générez les matches pour configurer les séries"}</p>
+            }
+        </div>
+    }
+}
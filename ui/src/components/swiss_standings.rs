@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::{
+    api::models::{SwissStanding, Tournament},
+    utils::utils::team_color_wrapper,
+};
+
+#[derive(PartialEq, Properties)]
+pub struct SwissStandingsProps {
+    pub tournament: Tournament,
+    pub should_update: UseStateHandle<bool>,
+}
+
+#[function_component]
+pub fn SwissStandings(props: &SwissStandingsProps) -> Html {
+    let SwissStandingsProps {
+        tournament,
+        should_update,
+    } = props;
+
+    let standings: UseStateHandle<Vec<SwissStanding>> = use_state(|| vec![]);
+    let team_names: UseStateHandle<HashMap<i32, String>> = use_state(|| HashMap::new());
+
+    {
+        let tournament = tournament.clone();
+        let standings = standings.clone();
+        let team_names = team_names.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Some(teams) = tournament.get_teams().await.ok() {
+                        team_names.set(teams.into_iter().map(|t| (t.id, t.name)).collect());
+                    }
+
+                    if let Some(new_standings) = tournament.get_swiss_standings().await.ok() {
+                        standings.set(new_standings);
+                    }
+                });
+            },
+            should_update.clone(),
+        );
+    }
+
+    html! {
+        <div class="w-full mt-4 flex justify-center">
+            if standings.len() > 0 {
+                <table class="bg-nutLighter rounded text-center">
+                    <thead>
+                        <tr>
+                            <th class="px-2">{"#"}</th>
+                            <th class="px-2">{"Équipe"}</th>
+                            <th class="px-2">{"Pts"}</th>
+                            <th class="px-2">{"V"}</th>
+                            <th class="px-2">{"N"}</th>
+                            <th class="px-2">{"D"}</th>
+                            <th class="px-2">{"Buchholz"}</th>
+                            <th class="px-2">{"Buchholz -1"}</th>
+                            <th class="px-2">{"Diff."}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            standings.iter().enumerate().map(|(index, standing)| {
+                                let name = team_names.get(&standing.team_id).cloned().unwrap_or_default();
+
+                                html!{
+                                    <tr>
+                                        <td class="px-2">{index + 1}</td>
+                                        <td class="px-2">
+                                            <div style={team_color_wrapper(name.clone())} class="team-border-color border-l-4 px-2 m-1 rounded-r bg-nutLight text-left">
+                                                {name}
+                                            </div>
+                                        </td>
+                                        <td class="px-2 font-bold">{standing.points}</td>
+                                        <td class="px-2">{standing.won}</td>
+                                        <td class="px-2">{standing.drawn}</td>
+                                        <td class="px-2">{standing.lost}</td>
+                                        <td class="px-2">{standing.buchholz}</td>
+                                        <td class="px-2">{standing.buchholz_cut}</td>
+                                        <td class="px-2">{standing.score_diff}</td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </tbody>
+                </table>
+            } else {
+                {"Aucune équipe pour le moment"}
+            }
+        </div>
+    }
+}
@@ -15,6 +15,10 @@ pub struct TeamBetProps {
     pub bets: Vec<BetItem>,
     pub total: i32, // Computed in the component above
     pub is_left: bool,
+    #[prop_or_default]
+    pub bettors: i32,
+    #[prop_or_default]
+    pub odds: Option<f64>, // None while nobody bet on the team
 }
 
 #[function_component]
@@ -25,6 +29,8 @@ pub fn TeamBet(props: &TeamBetProps) -> Html {
         bets,
         total,
         is_left,
+        bettors,
+        odds,
     } = props;
 
     html! {
@@ -48,6 +54,12 @@ pub fn TeamBet(props: &TeamBetProps) -> Html {
             </ul>
             <div class={format!("bg-white p-2 rounded-b border-t {}", if is_left.clone() { "text-right" } else { "" })}>
                 {format!("Total: {} noix", total.to_string())}
+                <div class="text-sm text-gray-500">
+                    {format!("{} parieur{} - cote {}", bettors, if *bettors > 1 { "s" } else { "" }, match odds {
+                        Some(odds) => format!("{:.2}", odds),
+                        None => "-".to_string(),
+                    })}
+                </div>
             </div>
         </div>
     }
@@ -15,13 +15,21 @@ use crate::{api::{
 pub struct TeamDragProps {
     pub team: Team,
     pub update_trigger: UseStateHandle<bool>,
+    // class of the elements the team can be dropped on
+    #[prop_or(AttrValue::Static("group-item"))]
+    pub zone: AttrValue,
+    // called with the team and the zone instead of moving the team to a group
+    #[prop_or_default]
+    pub on_drop: Option<Callback<(i32, i32)>>,
 }
 
 #[function_component]
 pub fn TeamDrag(props: &TeamDragProps) -> Html {
     let TeamDragProps {
         team,
-        update_trigger
+        update_trigger,
+        zone,
+        on_drop,
     } = props;
     let node = use_node_ref();
     let state = {
@@ -61,6 +69,7 @@ pub fn TeamDrag(props: &TeamDragProps) -> Html {
 
     let on_touch_move = {
         let drag_pos = drag_pos.clone();
+        let zone = zone.clone();
 
         Callback::from(move |e: TouchEvent| {
             info!("Touch move");
@@ -70,7 +79,7 @@ pub fn TeamDrag(props: &TeamDragProps) -> Html {
             drag_pos.set((touch.client_x(), touch.client_y()));
 
             let document = window().unwrap().document().unwrap();
-            let groups = document.get_elements_by_class_name("group-item");
+            let groups = document.get_elements_by_class_name(&zone);
 
             for i in 0..groups.length() {
                 let group = groups.item(i).unwrap();
@@ -98,13 +107,15 @@ pub fn TeamDrag(props: &TeamDragProps) -> Html {
         let drag_pos = drag_pos.clone();
         let update_trigger = update_trigger.clone();
         let team_id = team.id;
+        let zone = zone.clone();
+        let on_drop = on_drop.clone();
 
         Callback::from(move |_e: TouchEvent| {
             let document = window().unwrap().document().unwrap();
             info!("Touch end");
             dragging.set(false);
 
-            let groups = document.get_elements_by_class_name("group-item");
+            let groups = document.get_elements_by_class_name(&zone);
 
             for i in 0..groups.length() {
                 let group = groups.item(i).unwrap();
@@ -121,6 +132,17 @@ pub fn TeamDrag(props: &TeamDragProps) -> Html {
 
                 if in_bound {
                     info!("Dropped to group: {}", group.id());
+                    let zone_id = group
+                        .get_attribute("data-id")
+                        .unwrap_or_else(|| group.id())
+                        .parse::<i32>()
+                        .unwrap();
+
+                    if let Some(on_drop) = &on_drop {
+                        on_drop.emit((team_id, zone_id));
+                        break;
+                    }
+
                     let update_trigger = update_trigger.clone();
 
                     spawn_local(async move {
@@ -128,7 +150,7 @@ pub fn TeamDrag(props: &TeamDragProps) -> Html {
                             team_id,
                             TeamUpdate {
                                 name: None,
-                                group: Some(group.id().parse::<i32>().unwrap()),
+                                group: Some(zone_id),
                             },
                         )
                         .await;
@@ -1,3 +1,5 @@
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use std::cell::RefCell;
 use std::rc::Rc;
 use web_sys::window;
 use yew::{platform::spawn_local, prelude::*};
@@ -5,6 +7,11 @@ use yew_hooks::use_effect_once;
 
 use crate::api::{self, models::User};
 
+thread_local! {
+    // refresh in progress, shared by the requests which got a 401
+    static PENDING_REFRESH: RefCell<Option<Shared<LocalBoxFuture<'static, bool>>>> = RefCell::new(None);
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct UserInfo {
     pub user: Option<User>,
@@ -24,14 +31,75 @@ impl UserInfo {
         None
     }
 
+    pub fn get_refresh_token() -> Option<String> {
+        if let Some(win) = window() {
+            if let Ok(Some(store)) = win.local_storage() {
+                if let Ok(item) = store.get_item("refreshToken") {
+                    return item;
+                }
+            }
+        }
+
+        None
+    }
+
     pub fn token(&self) -> Option<String> {
         UserInfo::get_token()
     }
 
-    pub fn login(&self, token: &str) -> bool {
+    /// Replace the expired session token with the refresh token
+    /// Return true if a new session token is available
+    ///
+    /// **Note**: The requests expired at the same time wait for the same refresh, the server
+    /// rotates the refresh token so only the first use of it succeeds
+    pub async fn refresh() -> bool {
+        let pending = PENDING_REFRESH.with(|pending| {
+            pending
+                .borrow_mut()
+                .get_or_insert_with(|| {
+                    async {
+                        let refreshed = UserInfo::refresh_session().await;
+                        PENDING_REFRESH.with(|pending| *pending.borrow_mut() = None);
+                        refreshed
+                    }
+                    .boxed_local()
+                    .shared()
+                })
+                .clone()
+        });
+
+        pending.await
+    }
+
+    async fn refresh_session() -> bool {
+        let refresh_token = match UserInfo::get_refresh_token() {
+            Some(refresh_token) => refresh_token,
+            None => return false,
+        };
+
+        match api::auth::refresh(refresh_token.clone()).await {
+            Ok(token_response) => {
+                if let Some(win) = window() {
+                    if let Ok(Some(store)) = win.local_storage() {
+                        let _ = store.set_item("loginToken", &token_response.token);
+                        let _ = store.set_item("refreshToken", &token_response.refresh_token);
+                        return true;
+                    }
+                }
+
+                false
+            }
+
+            // Another request may have used the refresh token in the meantime
+            Err(_) => UserInfo::get_refresh_token().map_or(false, |t| t != refresh_token),
+        }
+    }
+
+    pub fn login(&self, token: &str, refresh_token: &str) -> bool {
         if let Some(win) = window() {
             if let Ok(Some(store)) = win.local_storage() {
                 let _ = store.set_item("loginToken", token);
+                let _ = store.set_item("refreshToken", refresh_token);
                 let reducer = self.reducer.clone();
 
                 spawn_local(async move {
@@ -50,6 +118,7 @@ impl UserInfo {
     pub fn logout(&self) -> bool {
         if let Some(win) = window() {
             if let Ok(Some(store)) = win.local_storage() {
+                let _ = store.remove_item("refreshToken");
                 if store.remove_item("loginToken").is_ok() {
                     self.reducer
                         .as_ref()
@@ -122,10 +191,12 @@ pub fn UserProvider(props: &UserProviderProps) -> Html {
                 if user_reducer.is_logged() {
                     match api::me().await {
                         Ok(user) => user_reducer.dispatch((Action::SetUser, None, Some(user))),
+                        // The session token is refreshed by the api call, a 401 means the session is over
                         Err(e) => match e.error.code {
                             401 => {
                                 if let Some(win) = window() {
                                     if let Ok(Some(store)) = win.local_storage() {
+                                        let _ = store.remove_item("refreshToken");
                                         if store.remove_item("loginToken").is_ok() {
                                             user_reducer.dispatch((Action::Refresh, None, None));
                                         }
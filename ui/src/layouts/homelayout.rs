@@ -59,6 +59,16 @@ pub fn HomeLayout(props: &HomeLayoutProps) -> Html {
         Callback::from(move |_| navigator.push(&Route::Tournoix))
     };
 
+    let on_profile_click = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| navigator.push(&Route::Profile))
+    };
+
+    let on_leaderboard_click = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| navigator.push(&Route::Leaderboard))
+    };
+
     let on_logout_click = {
         let user_info = user_info.clone();
 
@@ -93,12 +103,13 @@ pub fn HomeLayout(props: &HomeLayoutProps) -> Html {
                     if *is_logged {
                         <div class="ml-auto my-auto mr-10 flex">
                             if let Some(user) = &user_info.user {
-                                <div class="drop-shadow mr-4 font-bold">
+                                <a onclick={on_profile_click} href="javascript:void" class="drop-shadow mr-4 font-bold hover:underline">
                                     {user.name.clone()}
-                                </div>
+                                </a>
                             }
                             <div class="flex flex-col gap-1">
                                 <Button class="px-2 py-1 hover:scale-110 text-xs" onclick={on_tournoix_click}>{"Liste des tournoix"}</Button>
+                                <Button class="px-2 py-1 hover:scale-110 text-xs" onclick={on_leaderboard_click}>{"Classement"}</Button>
                                 <Button class="px-2 py-1 hover:scale-110 text-xs" onclick={on_logout_click}>{"Déconnexion"}</Button>
                             </div>
                         </div>
@@ -0,0 +1,67 @@
+use time::Duration;
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::api;
+use crate::components::{button::Button, form_input::FormInput};
+use crate::layouts::homelayout::HomeLayout;
+use crate::notification::{CustomNotification, NotifType};
+
+#[function_component]
+pub fn ForgotPassword() -> Html {
+    let email_ref = use_node_ref();
+    let loading = use_state(|| false);
+    let is_sent = use_state(|| false);
+    let notifs = use_notification::<CustomNotification>();
+
+    let on_submit = {
+        let email_ref = email_ref.clone();
+        let loading = loading.clone();
+        let is_sent = is_sent.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            loading.set(true);
+
+            let email = email_ref.cast::<HtmlInputElement>().unwrap().value();
+
+            let loading = loading.clone();
+            let is_sent = is_sent.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::auth::forgot_password(email).await {
+                    Ok(_) => is_sent.set(true),
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    html! {
+        <HomeLayout>
+            <div class="flex flex-col items-center h-full sm:w-9/12 w-11/12 mx-auto relative">
+                <h1 class="mt-12 mb-5">{"Mot de passe oublié"}</h1>
+                if *is_sent {
+                    <p class="text-center">{"Si un compte utilise cette adresse, un mail avec un lien pour choisir un nouveau mot de passe vient d'être envoyé. Le lien est valable une heure."}</p>
+                } else {
+                    <form class="flex flex-col sm:text-xl text-lg" onsubmit={on_submit}>
+                        <FormInput id="email" label="E-mail" form_type="email" required={true} _ref={email_ref}/>
+                        <Button class={classes!("px-3", "py-2", "mx-auto", "mt-3", "mb-16", if *loading {"animate-pulse"} else {"hover:scale-110"})} disabled={*loading}>{"Envoyer le lien"}</Button>
+                    </form>
+                }
+            </div>
+        </HomeLayout>
+    }
+}
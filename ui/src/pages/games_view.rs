@@ -4,10 +4,11 @@ use crate::{
         button::Button,
         form_input::FormInput,
         team_bet::{TeamBet, BetItem}, loading_circle::LoadingCircle, user_provider::UserContext,
+        score_bets::ScoreBets,
     },
     layouts::homelayout::HomeLayout,
     routers::Route,
-    utils::utils::team_color_wrapper, api::{models::{GameWithTeams, self, BetWithUser}, self, game::BetData}, notification::{NotifType, CustomNotification},
+    utils::utils::team_color_wrapper, api::{models::{GameWithTeams, self, BetWithUser, GameOdds, BetPreview}, self, game::BetData}, notification::{NotifType, CustomNotification},
 };
 use time::Duration;
 use wasm_bindgen::JsCast;
@@ -41,6 +42,34 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
     let ratio = use_state(|| "".to_string());
     let user_nut = use_state(|| 0);
     let user_gains = use_state(|| 0);
+    let odds: UseStateHandle<Option<GameOdds>> = use_state(|| None);
+    let previews: UseStateHandle<Vec<BetPreview>> = use_state(|| vec![]);
+    let bet_nut = use_state(|| 0);
+
+    // the odds and the payouts follow the bets of the others, refreshed with them
+    let refresh_odds = {
+        let odds = odds.clone();
+        let previews = previews.clone();
+        let stakes = preview_stakes(&*game, &*user_bet, *bet_nut);
+        let match_id = match_id.clone();
+
+        Callback::from(move |_: ()| {
+            let odds = odds.clone();
+            let previews = previews.clone();
+            let stakes = stakes.clone();
+
+            spawn_local(async move {
+                if let Some((new_odds, new_previews)) = fetch_odds(match_id, stakes).await {
+                    if Some(&new_odds) != (*odds).as_ref() {
+                        odds.set(Some(new_odds));
+                    }
+                    if new_previews != *previews {
+                        previews.set(new_previews);
+                    }
+                }
+            });
+        })
+    };
 
     {
         let game_clone = game.clone();
@@ -56,8 +85,11 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
         let user_gains = user_gains.clone();
         let tournament_id = tournament_id.clone();
         let user_nut = user_nut.clone();
+        let refresh_odds = refresh_odds.clone();
 
         use_interval(move || {
+            refresh_odds.emit(());
+
             let game = game_clone.clone();
             spawn_local(async move {
                 let response = api::game::get(match_id).await.ok();
@@ -258,6 +290,36 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
         );
     }
 
+    {
+        let refresh_odds = refresh_odds.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                refresh_odds.emit(());
+                || ()
+            },
+            (*bet_nut, (*user_bet).clone(), (*game).clone().map(|g| g.status)),
+        );
+    }
+
+    let on_bet_nut_input = {
+        let bet_nut = bet_nut.clone();
+
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_dyn_into::<HtmlInputElement>();
+            if let Some(input) = input {
+                bet_nut.set(input.value().parse::<i32>().unwrap_or(0));
+            }
+        })
+    };
+
+    let team_odds = |team_id: i32| {
+        match &*odds {
+            Some(odds) => odds.team(team_id).cloned(),
+            None => None,
+        }
+    };
+
     let local_team_name_from_id = |id| {
         if let Some(game) = (*game).clone() {
             if game.team1.id == id {
@@ -376,7 +438,8 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
                     } else {
                         if let Some(game) = &*game {
                             <div class="flex sm:flex-row flex-col gap-5">
-                                <TeamBet total={(*total_1).clone()} is_left={true} team_name={game.team1.name.clone()} score={game.score1.clone()} bets={(*bets_1).clone()}/>
+                                <TeamBet total={(*total_1).clone()} is_left={true} team_name={game.team1.name.clone()} score={game.score1.clone()} bets={(*bets_1).clone()}
+                                    bettors={team_odds(game.team1.id).map(|o| o.bettors).unwrap_or(0)} odds={team_odds(game.team1.id).and_then(|o| o.odds)}/>
                                 <div class="flex flex-col w-96">
                                     <img src="/img/versus_big.png" class="w-72 mx-auto"/>
                                     <div class="flex justify-center items-center mb-2">
@@ -394,13 +457,23 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
                                     if game.status == 0 {
                                         if let Some(user_bet) = &*user_bet {
                                             <div class="text-xl text-center my-[0.92rem]">{format!("Vous avez misé {} noix sur \"{}\"", user_bet.nb_nut, local_team_name_from_id(user_bet.fk_teams.clone()))}</div>
+                                            {
+                                                previews.iter().map(|preview| html! {
+                                                    <div class="text-center mb-2">{format!("Si \"{}\" gagne, vous recevrez {} noix", local_team_name_from_id(preview.fk_teams), preview.payout)}</div>
+                                                }).collect::<Html>()
+                                            }
                                             <div style={team_color_wrapper(local_team_name_from_id(user_bet.fk_teams.clone()).to_string())} class="flex rounded team-bg-color">
                                                 <Button class="bg-transparent px-4 py-3 w-full" onclick={on_revert_bet_click}>
                                                     {"Annuler la mise"}
                                                 </Button>
                                             </div>
                                         } else {
-                                            <FormInput id="nut_bet" label="Nombre de noix à miser" form_type="number" min_num={1} required={true}/>
+                                            <FormInput id="nut_bet" label="Nombre de noix à miser" form_type="number" min_num={1} required={true} oninput={on_bet_nut_input}/>
+                                            {
+                                                previews.iter().map(|preview| html! {
+                                                    <div class="text-center">{format!("Si vous misez {} noix sur \"{}\", vous gagneriez {} noix", preview.nb_nut, local_team_name_from_id(preview.fk_teams), preview.gain)}</div>
+                                                }).collect::<Html>()
+                                            }
                                             <div class="flex relative drop-shadow-lg">
                                                 <div style={team_color_wrapper(game.team1.name.clone())} class="flex grow-[1] hover:duration-[200ms] duration-[600ms] hover:grow-[3] rounded-l team-bg-color">
                                                     <Button class="bg-transparent px-4 py-3 text-right w-full hover:tracking-normal" onclick={on_bet_click(game.team1.id.clone())}>
@@ -446,8 +519,12 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
                                         }
                                     }
                                 </div>
-                                <TeamBet total={(*total_2).clone()} is_left={false} team_name={game.team2.name.clone()} score={game.score2.clone()} bets={(*bets_2).clone()}/>
+                                <TeamBet total={(*total_2).clone()} is_left={false} team_name={game.team2.name.clone()} score={game.score2.clone()} bets={(*bets_2).clone()}
+                                    bettors={team_odds(game.team2.id).map(|o| o.bettors).unwrap_or(0)} odds={team_odds(game.team2.id).and_then(|o| o.odds)}/>
                             </div>
+                            if !game.is_bye() {
+                                <ScoreBets game={game.clone()} />
+                            }
                         } else  {
                             <div>{"Oups, ce match n'existe pas :("}</div>
                         }
@@ -457,3 +534,30 @@ pub fn MatchView(props: &MatchViewProps) -> Html {
         </HomeLayout>
     }
 }
+
+// bets to preview: the bet of the user once placed, or the nuts typed on each team
+fn preview_stakes(game: &Option<GameWithTeams>, user_bet: &Option<models::Bet>, nut: i32) -> Vec<(i32, i32)> {
+    match (game, user_bet) {
+        (Some(game), _) if game.status != 0 => vec![],
+        (Some(_), Some(user_bet)) => vec![(user_bet.fk_teams, user_bet.nb_nut)],
+        (Some(game), None) if nut > 0 => vec![(game.team1.id, nut), (game.team2.id, nut)],
+        _ => vec![],
+    }
+}
+
+// odds of the game with the payout of each previewed bet
+async fn fetch_odds(game_id: i32, stakes: Vec<(i32, i32)>) -> Option<(GameOdds, Vec<BetPreview>)> {
+    if stakes.is_empty() {
+        return api::game::get_odds(game_id, None).await.ok().map(|odds| (odds, vec![]));
+    }
+
+    let mut odds = None;
+    let mut previews = vec![];
+    for stake in stakes {
+        let response = api::game::get_odds(game_id, Some(stake)).await.ok()?;
+        previews.extend(response.preview.clone());
+        odds = Some(response);
+    }
+
+    odds.map(|odds| (odds, previews))
+}
@@ -0,0 +1,129 @@
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::{
+    api::{
+        self,
+        models::{Leaderboard as LeaderboardData, Season},
+    },
+    components::loading_circle::LoadingCircle,
+    layouts::homelayout::HomeLayout,
+};
+
+fn percent(value: Option<f64>) -> String {
+    match value {
+        Some(value) => format!("{:.0} %", value * 100.0),
+        None => "-".into(),
+    }
+}
+
+#[function_component]
+pub fn Leaderboard() -> Html {
+    let seasons: UseStateHandle<Vec<Season>> = use_state(|| vec![]);
+    // none for all the tournaments
+    let season: UseStateHandle<Option<i32>> = use_state(|| None);
+    let leaderboard: UseStateHandle<Option<LeaderboardData>> = use_state(|| None);
+    let loading = use_state(|| true);
+
+    {
+        let seasons = seasons.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Ok(new_seasons) = api::leaderboard::get_seasons().await {
+                        seasons.set(new_seasons);
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    {
+        let leaderboard = leaderboard.clone();
+        let loading = loading.clone();
+        let selected = *season;
+
+        use_effect_with_deps(
+            move |_| {
+                loading.set(true);
+
+                spawn_local(async move {
+                    leaderboard.set(api::leaderboard::get_leaderboard(selected).await.ok());
+                    loading.set(false);
+                });
+            },
+            season.clone(),
+        );
+    }
+
+    let on_season_change = {
+        let season = season.clone();
+
+        Callback::from(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            season.set(value.parse::<i32>().ok());
+        })
+    };
+
+    html! {
+        <HomeLayout>
+            <div class="flex flex-col items-center h-full pb-16 sm:w-9/12 w-11/12 mx-auto relative">
+                <h1 class="mt-12 mb-5">{"Classement"}</h1>
+                <p class="discrete text-center">{"Noix gagnées grâce aux paris, sur tous les tournoix de la saison."}</p>
+                <select class="mt-4" onchange={on_season_change}>
+                    <option value="" selected={season.is_none()}>{"Toutes les saisons"}</option>
+                    {
+                        seasons.iter().map(|s| {
+                            html!{
+                                <option value={s.id.to_string()} selected={*season == Some(s.id)}>
+                                    {format!("{} ({} - {})", s.name, s.start_date.format("%d/%m/%Y"), s.end_date.format("%d/%m/%Y"))}
+                                </option>
+                            }
+                        }).collect::<Html>()
+                    }
+                </select>
+                if *loading {
+                    <LoadingCircle />
+                } else if let Some(leaderboard) = &*leaderboard {
+                    if leaderboard.entries.is_empty() {
+                        <p class="mt-6">{"Aucun pari n'a encore été réglé."}</p>
+                    } else {
+                        <table class="bg-nutLighter rounded text-center mt-6">
+                            <thead>
+                                <tr>
+                                    <th class="px-2">{"#"}</th>
+                                    <th class="px-2 text-left">{"Joueur"}</th>
+                                    <th class="px-2">{"Noix gagnées"}</th>
+                                    <th class="px-2">{"Paris (gagnés / perdus)"}</th>
+                                    <th class="px-2">{"Réussite"}</th>
+                                    <th class="px-2">{"Rendement"}</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {
+                                    leaderboard.entries.iter().map(|entry| {
+                                        html!{
+                                            <tr>
+                                                <td class="px-2">{entry.rank}</td>
+                                                <td class="px-2 text-left">{entry.username.clone()}</td>
+                                                <td class="px-2">{format!("{:+}", entry.net)}</td>
+                                                <td class="px-2">{format!("{} ({} / {})", entry.bets, entry.won, entry.lost)}</td>
+                                                <td class="px-2">{percent(entry.accuracy)}</td>
+                                                <td class="px-2">{percent(entry.roi)}</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </tbody>
+                        </table>
+                    }
+                } else {
+                    <p class="mt-6">{"Impossible de charger le classement."}</p>
+                }
+            </div>
+        </HomeLayout>
+    }
+}
@@ -51,7 +51,7 @@ pub fn Login(props: &LoginProps) -> Html {
                 spawn_local(async move {
                     match api::auth::login(login_request).await {
                         Ok(token_response) => {
-                            user_info.login(&token_response.token);
+                            user_info.login(&token_response.token, &token_response.refresh_token);
 
                             loading.set(false);
 
@@ -86,6 +86,11 @@ pub fn Login(props: &LoginProps) -> Html {
         Callback::from(move |_| navigator.push(&Route::Register))
     };
 
+    let on_forgot_password_click: Callback<MouseEvent> = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| navigator.push(&Route::ForgotPassword))
+    };
+
     html! {
         <HomeLayout>
             <div class="flex flex-col items-center h-full sm:w-9/12 w-11/12 mx-auto relative">
@@ -95,6 +100,7 @@ pub fn Login(props: &LoginProps) -> Html {
                     <FormInput id="password" label="Mot de passe" form_type="password" required={true} _ref={password_ref.clone()} />
                     <Button class={classes!("px-3", "py-2", "mx-auto", "mt-3", "mb-4", if *loading {"animate-pulse"} else {"hover:scale-110"})} disabled={*loading}>{"Connexion"}</Button>
                 </form>
+                <a onclick={on_forgot_password_click} href="javascript:void" class="underline">{"Mot de passe oublié ?"}</a>
                 <Button class="sm:text-xl text-lg px-3 py-2 mx-auto mt-3 mb-16 hover:scale-110" onclick={on_register_click}>{"Créer un compte"}</Button>
             </div>
         </HomeLayout>
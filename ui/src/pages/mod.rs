@@ -1,9 +1,15 @@
+pub mod forgot_password;
 pub mod games_view;
 pub mod home;
 pub mod join;
+pub mod leaderboard;
 pub mod login;
+pub mod profile;
 pub mod register;
+pub mod reset_password;
+pub mod timetable;
 pub mod tournoix;
 pub mod tournoix_create;
 pub mod tournoix_edit;
 pub mod tournoix_view;
+pub mod verify_email;
@@ -0,0 +1,365 @@
+use time::Duration;
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::api;
+use crate::api::models::SessionInfo;
+use crate::api::users::{ChangePasswordRequest, UpdateUserRequest};
+use crate::components::user_provider::{Action, UserContext};
+use crate::components::{button::Button, form_input::FormInput};
+use crate::layouts::homelayout::HomeLayout;
+use crate::notification::{CustomNotification, NotifType};
+use crate::utils::utils::error_description;
+
+#[function_component]
+pub fn Profile() -> Html {
+    let user_info = use_context::<UserContext>().expect("Missing user context provider");
+    let notifs = use_notification::<CustomNotification>();
+
+    let name_ref = use_node_ref();
+    let email_ref = use_node_ref();
+    let current_password_ref = use_node_ref();
+    let new_password_ref = use_node_ref();
+    let delete_password_ref = use_node_ref();
+    let loading = use_state(|| false);
+    let sessions: UseStateHandle<Vec<SessionInfo>> = use_state(|| vec![]);
+
+    {
+        let sessions = sessions.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Ok(new_sessions) = api::users::get_sessions().await {
+                        sessions.set(new_sessions);
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    let on_profile_submit = {
+        let user_info = user_info.clone();
+        let name_ref = name_ref.clone();
+        let email_ref = email_ref.clone();
+        let loading = loading.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            loading.set(true);
+
+            let update_request = UpdateUserRequest {
+                name: Some(name_ref.cast::<HtmlInputElement>().unwrap().value()),
+                email: Some(email_ref.cast::<HtmlInputElement>().unwrap().value()),
+            };
+            let is_new_email = user_info
+                .user
+                .as_ref()
+                .map_or(false, |u| Some(&u.email) != update_request.email.as_ref());
+
+            let user_info = user_info.clone();
+            let loading = loading.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::users::update_me(update_request).await {
+                    Ok(user) => {
+                        user_info.dispatch((Action::SetUser, None, Some(user)));
+
+                        notifs.spawn(CustomNotification::new(
+                            "Profil modifié",
+                            if is_new_email {
+                                "Un mail de confirmation a été envoyé à votre nouvelle adresse."
+                            } else {
+                                "Votre profil a été modifié."
+                            },
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_password_submit = {
+        let current_password_ref = current_password_ref.clone();
+        let new_password_ref = new_password_ref.clone();
+        let loading = loading.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            loading.set(true);
+
+            let password_request = ChangePasswordRequest {
+                current_password: current_password_ref.cast::<HtmlInputElement>().unwrap().value(),
+                new_password: new_password_ref.cast::<HtmlInputElement>().unwrap().value(),
+            };
+
+            let current_password_ref = current_password_ref.clone();
+            let new_password_ref = new_password_ref.clone();
+            let loading = loading.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::users::change_password(password_request).await {
+                    Ok(_) => {
+                        current_password_ref.cast::<HtmlInputElement>().unwrap().set_value("");
+                        new_password_ref.cast::<HtmlInputElement>().unwrap().set_value("");
+
+                        notifs.spawn(CustomNotification::new(
+                            "Mot de passe changé",
+                            "Vos autres sessions ont été déconnectées.",
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_delete_submit = {
+        let user_info = user_info.clone();
+        let delete_password_ref = delete_password_ref.clone();
+        let loading = loading.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message("Supprimer définitivement votre compte ?").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+
+            loading.set(true);
+
+            let password = delete_password_ref.cast::<HtmlInputElement>().unwrap().value();
+            let user_info = user_info.clone();
+            let loading = loading.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::users::delete_me(password).await {
+                    Ok(_) => user_info.logout(),
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+
+                        loading.set(false);
+                        false
+                    }
+                };
+            });
+        })
+    };
+
+    let on_session_revoke = {
+        let user_info = user_info.clone();
+        let sessions = sessions.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |session: SessionInfo| {
+            let user_info = user_info.clone();
+            let sessions = sessions.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::users::delete_session(session.id).await {
+                    Ok(_) if session.is_current => {
+                        user_info.logout();
+                    }
+
+                    Ok(_) => {
+                        sessions.set(
+                            sessions
+                                .iter()
+                                .filter(|s| s.id != session.id)
+                                .cloned()
+                                .collect(),
+                        );
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_logout_everywhere = {
+        let user_info = user_info.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let user_info = user_info.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::users::delete_all_sessions().await {
+                    Ok(_) => {
+                        user_info.logout();
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_resend_verification = {
+        let notifs = notifs.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::auth::resend_verification_email().await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Mail envoyé",
+                            "Un nouveau mail de confirmation a été envoyé.",
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+            });
+        })
+    };
+
+    let (name, email, email_verified) = match &user_info.user {
+        Some(user) => (user.name.clone(), user.email.clone(), user.email_verified),
+        None => (String::new(), String::new(), true),
+    };
+    let button_class = classes!("px-3", "py-2", "mx-auto", "mt-3", if *loading {"animate-pulse"} else {"hover:scale-110"});
+
+    html! {
+        <HomeLayout>
+            <div class="flex flex-col items-center h-full pb-16 sm:w-9/12 w-11/12 mx-auto relative">
+                <h1 class="mt-12 mb-5">{"Mon profil"}</h1>
+                <form class="flex flex-col sm:text-xl text-lg" onsubmit={on_profile_submit}>
+                    <FormInput id="username" label="Nom d'utilisateur" form_type="text" required={true} value={name} _ref={name_ref}/>
+                    <FormInput id="email" label="E-mail" form_type="email" required={true} value={email} _ref={email_ref}/>
+                    if !email_verified {
+                        <p class="discrete text-sm mb-2">
+                            {"Adresse non confirmée. "}
+                            <a class="underline cursor-pointer" onclick={on_resend_verification}>{"Renvoyer le mail de confirmation"}</a>
+                        </p>
+                    }
+                    <Button class={button_class.clone()} disabled={*loading}>{"Enregistrer"}</Button>
+                </form>
+
+                <h2 class="mt-12 mb-5">{"Mot de passe"}</h2>
+                <form class="flex flex-col sm:text-xl text-lg" onsubmit={on_password_submit}>
+                    <FormInput id="current_password" label="Mot de passe actuel" form_type="password" required={true} _ref={current_password_ref}/>
+                    <FormInput id="new_password" label="Nouveau mot de passe" form_type="password" required={true} _ref={new_password_ref}/>
+                    <p class="discrete text-sm mb-2">{"8 à 64 caractères, avec une majuscule, une minuscule, un chiffre et un caractère spécial."}</p>
+                    <Button class={button_class.clone()} disabled={*loading}>{"Changer le mot de passe"}</Button>
+                </form>
+
+                <h2 class="mt-12 mb-5">{"Sessions"}</h2>
+                <table class="table-auto text-left mb-3">
+                    <thead>
+                        <tr>
+                            <th class="px-2">{"Appareil"}</th>
+                            <th class="px-2">{"Connexion"}</th>
+                            <th class="px-2">{"Dernière activité"}</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {for sessions.iter().map(|session| {
+                            let on_revoke = {
+                                let on_session_revoke = on_session_revoke.clone();
+                                let session = session.clone();
+                                Callback::from(move |_: MouseEvent| on_session_revoke.emit(session.clone()))
+                            };
+
+                            html! {
+                                <tr>
+                                    <td class="px-2 break-all">
+                                        {session.user_agent.clone().unwrap_or("Appareil inconnu".into())}
+                                        if session.is_current {
+                                            <span class="discrete">{" (cet appareil)"}</span>
+                                        }
+                                    </td>
+                                    <td class="px-2">{session.created_at.format("%d/%m/%Y %H:%M").to_string()}</td>
+                                    <td class="px-2">{session.last_used_at.format("%d/%m/%Y %H:%M").to_string()}</td>
+                                    <td class="px-2">
+                                        <Button class="px-2 py-1 hover:scale-110" onclick={on_revoke}>{"Déconnecter"}</Button>
+                                    </td>
+                                </tr>
+                            }
+                        })}
+                    </tbody>
+                </table>
+                <Button class="sm:text-xl text-lg px-3 py-2 mx-auto hover:scale-110" onclick={on_logout_everywhere}>{"Déconnecter partout"}</Button>
+
+                <h2 class="mt-12 mb-5">{"Supprimer le compte"}</h2>
+                <p class="discrete text-center mb-2">{"Vos données personnelles sont effacées, vos tournoix et vos paris restent visibles sous le nom \"Utilisateur supprimé\"."}</p>
+                <form class="flex flex-col sm:text-xl text-lg" onsubmit={on_delete_submit}>
+                    <FormInput id="delete_password" label="Mot de passe" form_type="password" required={true} _ref={delete_password_ref}/>
+                    <Button class={button_class} disabled={*loading}>{"Supprimer mon compte"}</Button>
+                </form>
+            </div>
+        </HomeLayout>
+    }
+}
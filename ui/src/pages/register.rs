@@ -12,6 +12,7 @@ use crate::components::{button::Button, form_input::FormInput};
 use crate::layouts::homelayout::HomeLayout;
 use crate::notification::{CustomNotification, NotifType};
 use crate::routers::Route;
+use crate::utils::utils::error_description;
 
 #[derive(Serialize, Default)]
 pub struct RegisterForm {
@@ -72,7 +73,7 @@ pub fn Register(props: &RegisterProps) -> Html {
                             loading.set(false);
                             notifs.spawn(CustomNotification::new(
                                 "Compte créé !",
-                                "Votre compte à été créé !",
+                                "Votre compte à été créé ! Un mail de confirmation vous a été envoyé.",
                                 NotifType::Success,
                                 Duration::seconds(5),
                             ));
@@ -84,7 +85,7 @@ pub fn Register(props: &RegisterProps) -> Html {
                             loading.set(false);
                             notifs.spawn(CustomNotification::new(
                                 "Erreur",
-                                &e.error.description,
+                                &error_description(&e.error),
                                 NotifType::Error,
                                 Duration::seconds(5),
                             ));
@@ -103,6 +104,7 @@ pub fn Register(props: &RegisterProps) -> Html {
                     <FormInput id="email" label="E-mail" form_type="email" required={true} _ref={email_ref}/>
                     <FormInput id="username" label="Nom d'utilisateur" form_type="text" required={true} _ref={username_ref}/>
                     <FormInput id="password" label="Mot de passe" form_type="password" required={true} _ref={password_ref}/>
+                    <p class="discrete text-sm mb-2">{"8 à 64 caractères, avec une majuscule, une minuscule, un chiffre et un caractère spécial."}</p>
                     <Button class={classes!("px-3", "py-2", "mx-auto", "mt-3", "mb-16", if *loading {"animate-pulse"} else {"hover:scale-110"})} disabled={*loading}>{"Créer un compte"}</Button>
                 </form>
             </div>
@@ -0,0 +1,102 @@
+use time::Duration;
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+use yew_router::prelude::use_navigator;
+
+use crate::api;
+use crate::api::auth::ResetPasswordRequest;
+use crate::components::{button::Button, form_input::FormInput};
+use crate::layouts::homelayout::HomeLayout;
+use crate::notification::{CustomNotification, NotifType};
+use crate::routers::Route;
+use crate::utils::utils::error_description;
+
+#[derive(PartialEq, Properties)]
+pub struct ResetPasswordProps {
+    pub token: String,
+}
+
+#[function_component]
+pub fn ResetPassword(props: &ResetPasswordProps) -> Html {
+    let ResetPasswordProps { token } = props;
+    let navigator = use_navigator().unwrap();
+    let password_ref = use_node_ref();
+    let confirm_ref = use_node_ref();
+    let loading = use_state(|| false);
+    let notifs = use_notification::<CustomNotification>();
+
+    let on_submit = {
+        let token = token.clone();
+        let password_ref = password_ref.clone();
+        let confirm_ref = confirm_ref.clone();
+        let loading = loading.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let password = password_ref.cast::<HtmlInputElement>().unwrap().value();
+            let confirm = confirm_ref.cast::<HtmlInputElement>().unwrap().value();
+
+            if password != confirm {
+                notifs.spawn(CustomNotification::new(
+                    "Erreur",
+                    "Les mots de passe ne correspondent pas.",
+                    NotifType::Error,
+                    Duration::seconds(5),
+                ));
+                return;
+            }
+
+            loading.set(true);
+
+            let reset_request = ResetPasswordRequest {
+                token: token.clone(),
+                password,
+            };
+            let navigator = navigator.clone();
+            let loading = loading.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match api::auth::reset_password(reset_request).await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Mot de passe changé",
+                            "Vous pouvez vous connecter avec votre nouveau mot de passe.",
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+
+                        navigator.push(&Route::Login);
+                    }
+
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Erreur",
+                            &error_description(&e.error),
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    html! {
+        <HomeLayout>
+            <div class="flex flex-col items-center h-full sm:w-9/12 w-11/12 mx-auto relative">
+                <h1 class="mt-12 mb-5">{"Nouveau mot de passe"}</h1>
+                <form class="flex flex-col sm:text-xl text-lg" onsubmit={on_submit}>
+                    <FormInput id="password" label="Mot de passe" form_type="password" required={true} _ref={password_ref}/>
+                    <FormInput id="confirm" label="Confirmation" form_type="password" required={true} _ref={confirm_ref}/>
+                    <Button class={classes!("px-3", "py-2", "mx-auto", "mt-3", "mb-16", if *loading {"animate-pulse"} else {"hover:scale-110"})} disabled={*loading}>{"Changer le mot de passe"}</Button>
+                </form>
+            </div>
+        </HomeLayout>
+    }
+}
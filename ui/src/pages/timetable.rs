@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use time::Duration;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_notifications::use_notification;
+
+use crate::{
+    api::{
+        self,
+        models::{GameWithTeams, ScheduleRequest, Team, Tournament},
+    },
+    components::{backlink::Backlink, button::Button, form_input::FormInput, loading_circle::LoadingCircle},
+    layouts::homelayout::HomeLayout,
+    notification::{CustomNotification, NotifType},
+    routers::Route,
+    utils::utils::team_color_wrapper,
+};
+
+#[derive(PartialEq, Properties)]
+pub struct TimetableProps {
+    pub id: i32,
+}
+
+// scheduled games by start time, then by court
+type TimeSlots = BTreeMap<chrono::NaiveDateTime, BTreeMap<i32, GameWithTeams>>;
+
+#[function_component]
+pub fn Timetable(props: &TimetableProps) -> Html {
+    let TimetableProps { id } = props;
+    let notifs = use_notification::<CustomNotification>();
+
+    let tournament: UseStateHandle<Option<Tournament>> = use_state(|| None);
+    let timetable: UseStateHandle<TimeSlots> = use_state(|| BTreeMap::new());
+    let teams: UseStateHandle<Vec<Team>> = use_state(|| vec![]);
+    let nb_courts = use_state(|| 0);
+    let can_edit = use_state(|| false);
+    let loading = use_state(|| true);
+    let trigger = use_state(|| false);
+
+    let start_ref = use_node_ref();
+    let duration_ref = use_node_ref();
+    let break_ref = use_node_ref();
+    let courts_ref = use_node_ref();
+    let rest_ref = use_node_ref();
+
+    {
+        let tournament = tournament.clone();
+        let timetable = timetable.clone();
+        let teams = teams.clone();
+        let nb_courts = nb_courts.clone();
+        let can_edit = can_edit.clone();
+        let loading = loading.clone();
+        let id = id.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    if let Some(t) = api::tournoix::get(id).await.ok() {
+                        if let Some(games) = t.get_matches().await.ok() {
+                            let mut new_timetable: TimeSlots = BTreeMap::new();
+
+                            for game in games {
+                                if let (Some(start), Some(court)) = (game.scheduled_at, game.court) {
+                                    new_timetable.entry(start).or_insert_with(|| BTreeMap::new()).insert(court, game);
+                                }
+                            }
+
+                            nb_courts.set(new_timetable.values().flat_map(|games| games.keys()).max().cloned().unwrap_or(0));
+                            timetable.set(new_timetable);
+                        }
+
+                        if let Some(new_teams) = t.get_teams().await.ok() {
+                            teams.set(new_teams);
+                        }
+
+                        if let Some(is_owner) = api::tournoix::is_tournoix_owner(t.id).await.ok() {
+                            can_edit.set(is_owner);
+                        }
+
+                        tournament.set(Some(t));
+                    }
+
+                    loading.set(false);
+                });
+            },
+            trigger.clone(),
+        );
+    }
+
+    let on_submit = {
+        let tournament = tournament.clone();
+        let start_ref = start_ref.clone();
+        let duration_ref = duration_ref.clone();
+        let break_ref = break_ref.clone();
+        let courts_ref = courts_ref.clone();
+        let rest_ref = rest_ref.clone();
+        let trigger = trigger.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let tournament = match &*tournament {
+                Some(tournament) => tournament.clone(),
+                None => return,
+            };
+
+            let start = start_ref.cast::<HtmlInputElement>().unwrap().value();
+            let start = chrono::NaiveDateTime::from_str(&format!("{}:00", start)).ok();
+            let value = |node: &NodeRef| {
+                node.cast::<HtmlInputElement>()
+                    .unwrap()
+                    .value()
+                    .parse::<i32>()
+                    .unwrap_or(0)
+            };
+
+            let schedule_request = ScheduleRequest {
+                start,
+                match_duration: value(&duration_ref),
+                break_duration: value(&break_ref),
+                courts: value(&courts_ref),
+                min_rest: value(&rest_ref),
+            };
+
+            let trigger = trigger.clone();
+            let notifs = notifs.clone();
+
+            spawn_local(async move {
+                match tournament.create_schedule(schedule_request).await {
+                    Ok(_) => {
+                        notifs.spawn(CustomNotification::new(
+                            "Planning généré",
+                            "Les matches ont reçu un horaire et un terrain",
+                            NotifType::Success,
+                            Duration::seconds(5),
+                        ));
+                    }
+                    Err(e) => {
+                        notifs.spawn(CustomNotification::new(
+                            &format!("Erreur: {}", e.error.reason),
+                            &e.error.description,
+                            NotifType::Error,
+                            Duration::seconds(5),
+                        ));
+                    }
+                };
+
+                trigger.set(!*trigger);
+            });
+        })
+    };
+
+    html! {
+        <HomeLayout>
+            <div class="flex flex-col items-center h-full pb-16 pt-12 sm:w-9/12 w-11/12 mx-auto relative">
+                <Backlink route={Route::TournoixView { id: *id }} label="Retour au tournoi"/>
+                if *loading {
+                    <LoadingCircle />
+                } else {
+                    if let Some(tournament) = &*tournament {
+                        <h1 class="mb-5">{format!("Planning - {}", tournament.name)}</h1>
+                        if *can_edit {
+                            <form onsubmit={on_submit} class="flex flex-col items-center w-full">
+                                <div class="flex flex-wrap justify-center">
+                                    <FormInput id="start" label="Début" form_type="datetime-local" value={tournament.date.format("%Y-%m-%dT%H:%M").to_string()} _ref={start_ref} required={true}/>
+                                    <FormInput id="match_duration" label="Durée d'un match (min)" form_type="number" min_num={1} value="30" _ref={duration_ref} required={true}/>
+                                    <FormInput id="break_duration" label="Pause entre deux matches (min)" form_type="number" min_num={0} value="5" _ref={break_ref} required={true}/>
+                                    <FormInput id="courts" label="Terrains" form_type="number" min_num={1} value="2" _ref={courts_ref} required={true}/>
+                                    <FormInput id="min_rest" label="Repos minimum d'une équipe (min)" form_type="number" min_num={0} value="15" _ref={rest_ref} required={true}/>
+                                </div>
+                                <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700">{"Générer le planning"}</Button>
+                            </form>
+                            <hr/>
+                        }
+                        if timetable.len() > 0 {
+                            <table class="w-full text-center">
+                                <thead>
+                                    <tr>
+                                        <th>{"Heure"}</th>
+                                        {(1..=*nb_courts).map(|court| html!{<th>{format!("Terrain {}", court)}</th>}).collect::<Html>()}
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {
+                                        timetable.iter().map(|(start, games)| {
+                                            html!{
+                                                <tr class="border-b">
+                                                    <td class="font-bold">{start.format("%d.%m %H:%M").to_string()}</td>
+                                                    {
+                                                        (1..=*nb_courts).map(|court| {
+                                                            match games.get(&court) {
+                                                                Some(game) => html!{
+                                                                    <td class="p-1">
+                                                                        <div class="flex flex-col bg-nutLighter">
+                                                                            <span style={team_color_wrapper(game.team1.name.clone())} class="border-l-4 team-border-color">{game.team1.name.clone()}</span>
+                                                                            <span style={team_color_wrapper(game.team2.name.clone())} class="border-l-4 team-border-color">{game.team2.name.clone()}</span>
+                                                                        </div>
+                                                                    </td>
+                                                                },
+                                                                None => html!{<td></td>},
+                                                            }
+                                                        }).collect::<Html>()
+                                                    }
+                                                </tr>
+                                            }
+                                        }).collect::<Html>()
+                                    }
+                                </tbody>
+                            </table>
+                            <hr/>
+                            <h2>{"Ajouter à mon agenda"}</h2>
+                            <a href={tournament.calendar_url()} class="a_link">{"Tous les matches du tournoi"}</a>
+                            <div class="flex flex-wrap gap-3 justify-center mt-3">
+                                {
+                                    teams.iter().map(|team| html!{
+                                        <a href={tournament.team_calendar_url(team.id)} class="a_link">{format!("Matches de {}", team.name)}</a>
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        } else {
+                            <div>{"Aucun match n'est planifié pour le moment"}</div>
+                        }
+                    } else {
+                        <div>{"Oups, ce tournoi n'existe pas :("}</div>
+                    }
+                }
+            </div>
+        </HomeLayout>
+    }
+}
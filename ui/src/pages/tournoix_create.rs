@@ -54,6 +54,16 @@ pub fn TournoixCreate(props: &TournoixCreateProps) -> Html {
                 is_qualif: false,
                 is_elim: false,
                 is_closed: false,
+                is_double_elim: false,
+                has_bracket_reset: true,
+                is_swiss: false,
+                swiss_rounds: None,
+                points_win: 3,
+                points_draw: 1,
+                points_loss: 0,
+                qualified_per_group: 1,
+                wildcards: 0,
+                has_third_place: false,
             };
 
             let notifs = notifs.clone();
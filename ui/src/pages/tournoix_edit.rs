@@ -14,16 +14,21 @@ use crate::{
         backlink::Backlink,
         bracket::Bracket,
         button::Button,
+        elim_seeding::ElimSeeding,
         form_input::FormInput,
         groups::Groups,
         join_code::JoinCode,
         loading_circle::LoadingCircle,
+        nut_audit::NutAudits,
         qualification_phase::QualificationPhase,
+        series_formats::SeriesFormats,
+        swiss_standings::SwissStandings,
         teams::Teams, results::Results,
     },
     layouts::homelayout::HomeLayout,
     notification::{CustomNotification, NotifType},
     routers::Route,
+    utils::events::use_tournament_events,
 };
 
 #[derive(PartialEq, Properties)]
@@ -49,6 +54,17 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
     let description_ref = use_node_ref();
     let qualif_ref = use_node_ref();
     let elim_ref = use_node_ref();
+    let double_elim_ref = use_node_ref();
+    let bracket_reset_ref = use_node_ref();
+    let third_place_ref = use_node_ref();
+    let swiss_ref = use_node_ref();
+    let swiss_rounds_ref = use_node_ref();
+    let points_win_ref = use_node_ref();
+    let points_draw_ref = use_node_ref();
+    let points_loss_ref = use_node_ref();
+    let qualified_per_group_ref = use_node_ref();
+    let wildcards_ref = use_node_ref();
+    let betting_lead_time_ref = use_node_ref();
 
     {
         let tournament = tournament.clone();
@@ -101,6 +117,12 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
         Callback::from(move |_| navigator.push(&Route::TournoixView { id }))
     };
 
+    let on_click_timetable = {
+        let navigator = navigator.clone();
+        let id = id.clone();
+        Callback::from(move |_| navigator.push(&Route::Timetable { id }))
+    };
+
     let on_submit = {
         let tournament = tournament.clone();
 
@@ -110,6 +132,17 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
         let description_ref = description_ref.clone();
         let qualif_ref = qualif_ref.clone();
         let elim_ref = elim_ref.clone();
+        let double_elim_ref = double_elim_ref.clone();
+        let bracket_reset_ref = bracket_reset_ref.clone();
+        let third_place_ref = third_place_ref.clone();
+        let swiss_ref = swiss_ref.clone();
+        let swiss_rounds_ref = swiss_rounds_ref.clone();
+        let points_win_ref = points_win_ref.clone();
+        let points_draw_ref = points_draw_ref.clone();
+        let points_loss_ref = points_loss_ref.clone();
+        let qualified_per_group_ref = qualified_per_group_ref.clone();
+        let wildcards_ref = wildcards_ref.clone();
+        let betting_lead_time_ref = betting_lead_time_ref.clone();
         let trigger = trigger.clone();
         let notifs = notifs.clone();
 
@@ -122,6 +155,17 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
             let description = description_ref.cast::<HtmlInputElement>().unwrap().value();
             let qualif = qualif_ref.cast::<HtmlInputElement>().unwrap().checked();
             let elim = elim_ref.cast::<HtmlInputElement>().unwrap().checked();
+            let double_elim = double_elim_ref.cast::<HtmlInputElement>().unwrap().checked();
+            let bracket_reset = bracket_reset_ref.cast::<HtmlInputElement>().unwrap().checked();
+            let third_place = third_place_ref.cast::<HtmlInputElement>().unwrap().checked();
+            let swiss = swiss_ref.cast::<HtmlInputElement>().unwrap().checked();
+            let swiss_rounds = swiss_rounds_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
+            let points_win = points_win_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
+            let points_draw = points_draw_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
+            let points_loss = points_loss_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
+            let qualified_per_group = qualified_per_group_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
+            let wildcards = wildcards_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
+            let betting_lead_time = betting_lead_time_ref.cast::<HtmlInputElement>().unwrap().value().parse::<i32>().ok();
 
             let date = chrono::NaiveDateTime::from_str(&format!("{}:00", date)).unwrap();
 
@@ -134,7 +178,18 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
                 size_group: None,
                 is_qualif: Some(qualif),
                 is_elim: Some(elim),
-                is_closed: Some(false)
+                is_closed: Some(false),
+                is_double_elim: Some(double_elim),
+                has_bracket_reset: Some(bracket_reset),
+                is_swiss: Some(swiss),
+                swiss_rounds,
+                points_win,
+                points_draw,
+                points_loss,
+                qualified_per_group,
+                wildcards,
+                has_third_place: Some(third_place),
+                betting_lead_time,
             };
 
             {
@@ -172,6 +227,22 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
         })
     };
 
+    // live changes made from another page refresh the phases
+    let received_events = use_tournament_events(&*tournament);
+    {
+        let should_update = should_update.clone();
+
+        use_effect_with_deps(
+            move |received_events| {
+                if *received_events > 0 {
+                    should_update.set(!*should_update);
+                }
+                || ()
+            },
+            received_events,
+        );
+    }
+
     let on_teams_update = {
         let should_update = should_update.clone();
         Callback::from(move |_| {
@@ -226,6 +297,36 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
         })
     };
 
+    let on_swiss_next_click = {
+        let tournament = tournament.clone();
+        let should_update = should_update.clone();
+        let notifs = notifs.clone();
+
+        Callback::from(move |_| {
+            if tournament.is_some() {
+                let tournament = tournament.clone();
+                let should_update = should_update.clone();
+                let notifs = notifs.clone();
+
+                spawn_local(async move {
+                    match tournament.as_ref().unwrap().generate_swiss_round().await {
+                        Ok(_) => {},
+                        Err(e) => {
+                            notifs.spawn(CustomNotification::new(
+                                &format!("Erreur: {}", e.error.reason),
+                                &e.error.description,
+                                NotifType::Error,
+                                Duration::seconds(5),
+                            ));
+                        }
+                    };
+
+                    should_update.set(!*should_update);
+                });
+            }
+        })
+    };
+
     let on_elim_gen_click = {
         let tournament = tournament.clone();
         let should_update = should_update.clone();
@@ -332,6 +433,7 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
                     if let Some(tournament) = &*tournament {
                         <h1 class="mb-5">{format!("Modification de \"{}\"", {tournament.name.to_string()})}</h1>
                         <a onclick={on_click_view} class="a_link mb-6">{"Voir ce tournoi en mode affichage"}</a>
+                        <a onclick={on_click_timetable} class="a_link mb-6">{"Planifier les matches"}</a>
                         <JoinCode code={tournament.code.to_string()}/>
                         <hr/>
                         <h2>{"Informations"}</h2>
@@ -353,6 +455,17 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
                                     <FormInput id="description" label="Description" form_type="text" value={tournament.description.clone()}  _ref={description_ref} required={true}/>
                                     <FormInput id="phase_qualifications" disabled={*tournament_is_started} label="Phase de qualifications" form_type="checkbox" checked={tournament.is_qualif} _ref={qualif_ref} />
                                     <FormInput id="phase_eliminations" disabled={*tournament_is_started} label="Phase d'éliminations" form_type="checkbox" checked={tournament.is_elim} _ref={elim_ref} />
+                                    <FormInput id="double_elimination" disabled={*tournament_is_started} label="Double élimination" form_type="checkbox" checked={tournament.is_double_elim} _ref={double_elim_ref} />
+                                    <FormInput id="bracket_reset" label="Finale rejouée si le repêché gagne" form_type="checkbox" checked={tournament.has_bracket_reset} _ref={bracket_reset_ref} />
+                                    <FormInput id="third_place" label="Match pour la troisième place" form_type="checkbox" checked={tournament.has_third_place} _ref={third_place_ref} />
+                                    <FormInput id="swiss" disabled={*tournament_is_started} label="Qualifications en système suisse" form_type="checkbox" checked={tournament.is_swiss} _ref={swiss_ref} />
+                                    <FormInput id="swiss_rounds" label="Nombre de rondes" form_type="number" min_num={1} value={tournament.swiss_rounds.map(|r| r.to_string()).unwrap_or_default()} _ref={swiss_rounds_ref} />
+                                    <FormInput id="points_win" label="Points par victoire" form_type="number" value={tournament.points_win.to_string()} _ref={points_win_ref} required={true} />
+                                    <FormInput id="points_draw" label="Points par match nul" form_type="number" value={tournament.points_draw.to_string()} _ref={points_draw_ref} required={true} />
+                                    <FormInput id="points_loss" label="Points par défaite" form_type="number" value={tournament.points_loss.to_string()} _ref={points_loss_ref} required={true} />
+                                    <FormInput id="qualified_per_group" label="Équipes qualifiées par groupe" form_type="number" min_num={1} value={tournament.qualified_per_group.to_string()} _ref={qualified_per_group_ref} required={true} />
+                                    <FormInput id="wildcards" label="Meilleurs suivants repêchés" form_type="number" value={tournament.wildcards.to_string()} _ref={wildcards_ref} required={true} />
+                                    <FormInput id="betting_lead_time" label="Fermeture des paris avant un match (min)" form_type="number" min_num={0} value={tournament.betting_lead_time.to_string()} _ref={betting_lead_time_ref} required={true} />
                                     <Button class="text-lg px-3 py-2 mt-3 hover:scale-110 bg-green-700">{"Sauvegarder les informations"}</Button>
                                 </form>
                             </div>
@@ -363,11 +476,19 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
                         if tournament.is_qualif {
                             <hr/>
                             <h2>{"Phase de qualifications"}</h2>
-                            <Groups tournament={tournament.clone()} should_update={should_update.clone()} editable={true} />
-                            <div class={"flex gap-4 mt-3"}>
-                                <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_qualif_gen_click}>{"Générer les matches"}</Button>
-                                <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_qualif_reset_click}>{"Réinitialiser les matches"}</Button>
-                            </div>
+                            if tournament.is_swiss {
+                                <SwissStandings tournament={tournament.clone()} should_update={should_update.clone()} />
+                                <div class={"flex gap-4 mt-3"}>
+                                    <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_swiss_next_click}>{"Générer la ronde suivante"}</Button>
+                                    <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_qualif_reset_click}>{"Réinitialiser les matches"}</Button>
+                                </div>
+                            } else {
+                                <Groups tournament={tournament.clone()} should_update={should_update.clone()} editable={true} />
+                                <div class={"flex gap-4 mt-3"}>
+                                    <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_qualif_gen_click}>{"Générer les matches"}</Button>
+                                    <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_qualif_reset_click}>{"Réinitialiser les matches"}</Button>
+                                </div>
+                            }
                             <QualificationPhase tournament={tournament.clone()} should_update={should_update.clone()} editable={true} />
                         }
                         if tournament.is_elim {
@@ -378,9 +499,14 @@ pub fn TournoixEdit(props: &TournoixEditProps) -> Html {
                                 <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_elim_gen_click}>{"Générer les matches"}</Button>
                                 <Button class="text-lg px-3 py-2 hover:scale-110 bg-green-700" onclick={on_elim_reset_click}>{"Réinitialiser les matches"}</Button>
                             </div>
-                            <Bracket tournament={tournament.clone()} should_update={should_update} editable={true} />
+                            <ElimSeeding tournament={tournament.clone()} should_update={should_update.clone()} />
+                            <SeriesFormats tournament={tournament.clone()} should_update={should_update.clone()} />
+                            <Bracket tournament={tournament.clone()} should_update={should_update.clone()} editable={true} />
                         }
                         <hr/>
+                        <h2>{"Noix des joueurs"}</h2>
+                        <NutAudits tournament={tournament.clone()} should_update={should_update.clone()} />
+                        <hr/>
                         <h2>{"Résultats"}</h2>
                         <Results tournament_id={ id } can_show_results={tournament.is_closed.clone() && *tournament_is_started} />
                         if !tournament.is_closed.clone() {
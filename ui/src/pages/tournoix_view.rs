@@ -10,13 +10,16 @@ use crate::{
         bet_list::BetList,
         bracket::Bracket,
         button::Button,
+        champion_bets::ChampionBets,
         groups::{Group, Groups},
         join_code::JoinCode,
         loading_circle::LoadingCircle,
-        results::Results, user_provider::UserContext,
+        nut_history::NutHistory,
+        results::Results, swiss_standings::SwissStandings, user_provider::UserContext,
     },
     layouts::homelayout::HomeLayout,
     routers::Route,
+    utils::events::use_tournament_events,
 };
 
 #[derive(PartialEq, Properties)]
@@ -157,6 +160,22 @@ pub fn TournoixView(props: &TournoixViewProps) -> Html {
         Callback::from(move |_| trigger.set(!*trigger))
     };
 
+    // live changes refresh the bets, the groups and the bracket
+    let received_events = use_tournament_events(&*tournament);
+    {
+        let trigger = trigger.clone();
+
+        use_effect_with_deps(
+            move |received_events| {
+                if *received_events > 0 {
+                    trigger.set(!*trigger);
+                }
+                || ()
+            },
+            received_events,
+        );
+    }
+
     let groups: UseStateHandle<Vec<Group>> =
         use_state(|| vec![Group {}, Group {}, Group {}, Group {}, Group {}, Group {}]);
 
@@ -166,6 +185,12 @@ pub fn TournoixView(props: &TournoixViewProps) -> Html {
         Callback::from(move |_| navigator.push(&Route::TournoixEdit { id }))
     };
 
+    let on_click_timetable = {
+        let navigator = navigator.clone();
+        let id = id.clone();
+        Callback::from(move |_| navigator.push(&Route::Timetable { id }))
+    };
+
     html! {
         <HomeLayout>
             <div class="flex flex-col items-center h-full pb-16 pt-12 sm:w-9/12 w-11/12 mx-auto relative">
@@ -256,11 +281,18 @@ pub fn TournoixView(props: &TournoixViewProps) -> Html {
                     }, 2000) // Wait so the map library is loaded
 "#}</script>
                         <div>{"Description: "}{tournament.description.to_string()}</div>
+                        <a onclick={on_click_timetable} class="a_link mt-3">{"Voir le planning des matches"}</a>
                         <hr/>
                         <h2>{"Paris disponibles"}</h2>
                         <p class="discrete">{"Vous pouvez misez vos noix dans ces matchs et peut-être remporter le pactole !"}</p>
                         <p class="mb-2">{format!("Vous possédez actuellement {} noix.", &*user_nut)}</p>
+                        if *has_joined_this_tournament {
+                            <NutHistory tournament={tournament.clone()} should_update={trigger.clone()} />
+                        }
                         <Button class="px-3 py-2 hover:scale-110 mb-4" onclick={on_click_refresh_games}>{"Rafraîchir"}</Button>
+                        if *has_joined_this_tournament && tournament.is_elim {
+                            <ChampionBets tournament={tournament.clone()} should_update={trigger.clone()} />
+                        }
                         if *has_joined_this_tournament {
                             if *loading_bettable_games {
                                 <LoadingCircle />
@@ -272,9 +304,13 @@ pub fn TournoixView(props: &TournoixViewProps) -> Html {
                         }
                         <hr/>
                         <h2>{"Phase de qualifications"}</h2>
-                        <ContextProvider<UseStateHandle<Vec<Group>>> context={groups.clone()}>
-                            <Groups tournament={tournament.clone()}/>
-                        </ContextProvider<UseStateHandle<Vec<Group>>>>
+                        if tournament.is_swiss {
+                            <SwissStandings tournament={tournament.clone()} should_update={trigger.clone()} />
+                        } else {
+                            <ContextProvider<UseStateHandle<Vec<Group>>> context={groups.clone()}>
+                                <Groups tournament={tournament.clone()} should_update={trigger.clone()}/>
+                            </ContextProvider<UseStateHandle<Vec<Group>>>>
+                        }
                         <hr/>
                         <h2>{"Phase d'éliminations"}</h2>
                         <Bracket tournament={tournament.clone()} should_update={trigger} editable={false} />
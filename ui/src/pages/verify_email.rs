@@ -0,0 +1,51 @@
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+use crate::api;
+use crate::components::loading_circle::LoadingCircle;
+use crate::layouts::homelayout::HomeLayout;
+
+#[derive(PartialEq, Properties)]
+pub struct VerifyEmailProps {
+    pub token: String,
+}
+
+#[function_component]
+pub fn VerifyEmail(props: &VerifyEmailProps) -> Html {
+    let VerifyEmailProps { token } = props;
+    // none while the token is checked
+    let result: UseStateHandle<Option<Result<(), String>>> = use_state(|| None);
+
+    {
+        let result = result.clone();
+
+        use_effect_with_deps(
+            move |token| {
+                let token = token.clone();
+
+                spawn_local(async move {
+                    match api::auth::verify_email(token).await {
+                        Ok(_) => result.set(Some(Ok(()))),
+                        Err(e) => result.set(Some(Err(e.error.description))),
+                    }
+                });
+            },
+            token.clone(),
+        );
+    }
+
+    html! {
+        <HomeLayout>
+            <div class="flex flex-col items-center h-full sm:w-9/12 w-11/12 mx-auto relative">
+                <h1 class="mt-12 mb-5">{"Confirmation de l'adresse mail"}</h1>
+                {
+                    match &*result {
+                        None => html! {<LoadingCircle />},
+                        Some(Ok(())) => html! {<p>{"Votre adresse mail est confirmée, merci !"}</p>},
+                        Some(Err(_)) => html! {<p>{"Ce lien n'est plus valable."}</p>},
+                    }
+                }
+            </div>
+        </HomeLayout>
+    }
+}
@@ -5,7 +5,8 @@ use crate::{
     components::user_provider::UserContext,
     pages::{
         home::Home, login::Login, register::Register, tournoix::Tournoix,
-        tournoix_create::TournoixCreate, tournoix_edit::TournoixEdit, tournoix_view::TournoixView, games_view::MatchView, join::Join,
+        tournoix_create::TournoixCreate, tournoix_edit::TournoixEdit, tournoix_view::TournoixView, games_view::MatchView, join::Join, timetable::Timetable, leaderboard::Leaderboard, profile::Profile,
+        forgot_password::ForgotPassword, reset_password::ResetPassword, verify_email::VerifyEmail,
     },
 };
 
@@ -21,14 +22,26 @@ pub enum Route {
     TournoixEdit { id: i32 },
     #[at("/tournoix/:id")]
     TournoixView { id: i32 },
+    #[at("/tournoix/:id/schedule")]
+    Timetable { id: i32 },
     #[at("/tournoix/:tournament_id/match/:match_id")]
     MatchView { tournament_id: i32, match_id: i32 },
+    #[at("/leaderboard")]
+    Leaderboard,
+    #[at("/profile")]
+    Profile,
     #[at("/join/:code")]
     Join {code: String},
     #[at("/login")]
     Login,
     #[at("/register")]
     Register,
+    #[at("/forgot-password")]
+    ForgotPassword,
+    #[at("/reset-password/:token")]
+    ResetPassword { token: String },
+    #[at("/verify/:token")]
+    VerifyEmail { token: String },
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -40,11 +53,17 @@ pub fn router(routes: Route) -> Html {
         Route::Tournoix => html! {<LoggedRoute><Tournoix/></LoggedRoute>},
         Route::TournoixView { id } => html! {<LoggedRoute><TournoixView id={id} /></LoggedRoute>},
         Route::TournoixEdit { id } => html! {<LoggedRoute><TournoixEdit id={id} /></LoggedRoute>},
+        Route::Timetable { id } => html! {<LoggedRoute><Timetable id={id} /></LoggedRoute>},
         Route::TournoixCreate => html! {<LoggedRoute><TournoixCreate /></LoggedRoute>},
         Route::MatchView { tournament_id, match_id } => html! {<LoggedRoute><MatchView tournament_id={tournament_id} match_id={match_id} /></LoggedRoute>},
+        Route::Leaderboard => html! {<LoggedRoute><Leaderboard /></LoggedRoute>},
+        Route::Profile => html! {<LoggedRoute><Profile /></LoggedRoute>},
         Route::Join { code } => html! {<LoggedRoute><Join code={code}/></LoggedRoute>},
         Route::Login => html! {<Login/>},
         Route::Register => html! {<Register/>},
+        Route::ForgotPassword => html! {<ForgotPassword/>},
+        Route::ResetPassword { token } => html! {<ResetPassword token={token}/>},
+        Route::VerifyEmail { token } => html! {<VerifyEmail token={token}/>},
         Route::NotFound => html! {<h1>{"404 Not Found"}</h1>},
     }
 }
@@ -0,0 +1,54 @@
+use std::rc::Rc;
+
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, MessageEvent};
+use yew::prelude::*;
+
+use crate::api::models::Tournament;
+
+// Number of changes received since the page was opened
+#[derive(Default, PartialEq)]
+struct Received(u32);
+
+impl Reducible for Received {
+    type Action = ();
+
+    fn reduce(self: Rc<Self>, _action: ()) -> Rc<Self> {
+        Rc::new(Received(self.0 + 1))
+    }
+}
+
+// Listen to the live changes of a tournament, the returned counter goes up on every change
+// so it can be used as a dependency to refresh the page
+#[hook]
+pub fn use_tournament_events(tournament: &Option<Tournament>) -> u32 {
+    let received = use_reducer(Received::default);
+
+    {
+        let dispatcher = received.dispatcher();
+
+        use_effect_with_deps(
+            move |tournament: &Option<Tournament>| {
+                let source = tournament
+                    .as_ref()
+                    .and_then(|t| EventSource::new(&t.events_url()).ok());
+                let on_message =
+                    Closure::<dyn FnMut(MessageEvent)>::new(move |_| dispatcher.dispatch(()));
+
+                if let Some(source) = &source {
+                    source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+                }
+
+                move || {
+                    if let Some(source) = source {
+                        source.close();
+                    }
+                    drop(on_message);
+                }
+            },
+            tournament.clone(),
+        );
+    }
+
+    received.0
+}
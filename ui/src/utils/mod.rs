@@ -1 +1,2 @@
+pub mod events;
 pub mod utils;
\ No newline at end of file
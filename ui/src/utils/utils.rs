@@ -25,4 +25,28 @@ pub fn string_to_unique_color(text: String) -> String {
 
     let rgb = colorsys::Rgb::from(hsl);
     format!("#{:02x}{:02x}{:02x}", rgb.red() as u32, rgb.green() as u32, rgb.blue() as u32)
-}
\ No newline at end of file
+}
+// Explain an error of the API, the broken rules of a weak password are listed one by one
+// Example:
+// input: reason "Weak Password", description "too_short,no_digit"
+// output: "Le mot de passe doit contenir au moins 8 caractères. Le mot de passe doit contenir un chiffre."
+pub fn error_description(error: &crate::api::ErrorBody) -> String {
+    if error.reason != "Weak Password" {
+        return error.description.clone();
+    }
+
+    error
+        .description
+        .split(',')
+        .map(|rule| match rule {
+            "too_short" => "Le mot de passe doit contenir au moins 8 caractères.",
+            "too_long" => "Le mot de passe doit contenir au plus 64 caractères.",
+            "no_uppercase" => "Le mot de passe doit contenir une majuscule.",
+            "no_lowercase" => "Le mot de passe doit contenir une minuscule.",
+            "no_digit" => "Le mot de passe doit contenir un chiffre.",
+            "no_special" => "Le mot de passe doit contenir un caractère spécial.",
+            _ => "Le mot de passe n'est pas assez sûr.",
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}